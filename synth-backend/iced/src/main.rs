@@ -8,92 +8,144 @@ pub enum PowerState {
     Off,
 }
 
-mod consts {
-    // pub const API_DIR: &str = "/tmp/synth/";
-    // pub const SEQ_SOCKET: &str = "sequencer.sock";
-    pub const API_SOCKET: &str = "/tmp/synth/backend.sock";
-}
-
 mod synth_helpers;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     use actix_files::Files;
-    use actix_web::middleware::Logger;
+    use actix_web::middleware::from_fn;
     use actix_web::*;
     use log::*;
     use std::{
-        fs::create_dir,
-        path::PathBuf,
-        sync::{atomic::AtomicBool, Arc, Mutex},
-        usize,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread, usize,
     };
     use stepper_synth_backend::SampleGen;
     use stepper_synth_backend::{sequencer::SequencerIntake, synth_engines::Synth, SAMPLE_RATE};
     use synth_backend::app::*;
-    use synth_helpers::run_midi;
+    use synth_common::{
+        load_auth, request_logger, require_auth, shutdown, socket_path, Shutdown, SocketLock,
+        FADE_OUT,
+    };
+    use synth_helpers::{all_notes_off, run_midi};
+    use synth_lib::{
+        mixer::{write_frame, Mixer},
+        notes::HeldNotes,
+    };
     use tinyaudio::{run_output_device, OutputDeviceParameters};
-    use tokio::task::spawn;
 
-    let socket_parent = PathBuf::from(consts::API_SOCKET);
-    let socket_parent = socket_parent.parent().expect("this exists");
-
-    if !socket_parent.exists() {
-        _ = create_dir(socket_parent);
-    }
+    let auth = web::Data::new(load_auth()?);
+    // held until the server stops, dropping it removes the socket.
+    let socket = SocketLock::acquire(socket_path()).inspect_err(|e| {
+        error!("can't use the API socket. {e}");
+    })?;
+    info!("listening on {}", socket.socket().display());
 
     // TODO: enable midi sequencer
     let seq = web::Data::new(Mutex::new(SequencerIntake::new()));
     let synth = web::Data::new(std::sync::Mutex::new(Synth::new()));
+    // only used to fade the output out when the backend stops.
+    let mixer = web::Data::new(Mutex::new(Mixer::default()));
+    // the notes held on each channel, released when the backend stops.
+    let held = web::Data::new(Mutex::new(HeldNotes::default()));
     // synth.lock().unwrap().set_engine(SynthEngineType::SubSynth);
     let exit: Arc<AtomicBool> = Arc::new(false.into());
+    // set by the `/shutdown` route.
+    let shutdown_request = web::Data::new(Shutdown::default());
 
-    let _jh = {
+    let midi = {
         let seq = seq.clone();
         let synth = synth.clone();
+        let held = held.clone();
+        let exit = exit.clone();
 
-        spawn(async move { run_midi(seq, synth, exit).await });
+        thread::spawn(move || run_midi(seq, synth, held, exit))
     };
     let params = OutputDeviceParameters {
-        channels_count: 1,
+        channels_count: 2,
         sample_rate: SAMPLE_RATE as usize,
         // channel_sample_count: 2048,
         channel_sample_count: 1024,
     };
     let device = run_output_device(params, {
         let synth = synth.clone();
+        let mixer = mixer.clone();
 
         move |data| {
             for samples in data.chunks_mut(params.channels_count) {
-                let value = synth.lock().unwrap().get_sample();
-
-                for sample in samples {
-                    *sample = value;
-                }
+                // NOTE: always lock the synth before the mixer.
+                let mut synth = synth.lock().unwrap();
+                let frame = mixer
+                    .lock()
+                    .unwrap()
+                    .mix(synth.channels.iter_mut().map(|chan| chan.get_sample()));
+                write_frame(frame, samples);
             }
         }
     });
 
-    if let Err(e) = device {
+    if let Err(e) = &device {
         error!("starting audio playback caused error: {e}");
     }
 
-    HttpServer::new(move || {
-        App::new()
-            .wrap(Logger::default())
-            // serve JS/WASM/CSS from `pkg`
-            .service(Files::new("/pkg", format!("{site_root}/pkg")))
-            // serve other assets from the `assets` directory
-            .service(Files::new("/assets", &site_root))
-            .app_data(synth.clone())
-            .app_data(seq.clone())
-        //.wrap(middleware::Compress::default())
+    let server = HttpServer::new({
+        // the originals are kept to silence the synth once the server stops.
+        let synth = synth.clone();
+        let shutdown_request = shutdown_request.clone();
+
+        move || {
+            App::new()
+                .wrap(from_fn(require_auth))
+                .wrap(request_logger())
+                // serve JS/WASM/CSS from `pkg`
+                .service(Files::new("/pkg", format!("{site_root}/pkg")))
+                // serve other assets from the `assets` directory
+                .service(Files::new("/assets", &site_root))
+                .service(shutdown)
+                .app_data(synth.clone())
+                .app_data(seq.clone())
+                .app_data(auth.clone())
+                .app_data(shutdown_request.clone())
+            //.wrap(middleware::Compress::default())
+        }
     })
     .workers(6)
-    .bind(&("0.0.0.0", 3000))?
-    .bind_uds(&consts::API_SOCKET)?
-    .run()
-    .await
+    .bind(&("0.0.0.0", 3000))?;
+    // the unix socket is trusted, so it is bound private to this user.
+    let server = socket.bind_private(|path| server.bind_uds(path))?;
+
+    // actix stops the server on SIGINT and SIGTERM, `/shutdown` stops it the same way.
+    let server = server.run();
+    let handle = server.handle();
+    actix_web::rt::spawn(async move {
+        shutdown_request.requested().await;
+        handle.stop(true).await;
+    });
+
+    let res = server.await;
+    info!("shutting down");
+
+    // no new notes once the MIDI ports are closed.
+    exit.store(true, Ordering::Relaxed);
+    match midi.join() {
+        Ok(Err(e)) => error!("MIDI input stopped with an error. {e}"),
+        Err(_) => error!("the MIDI input thread panicked"),
+        Ok(Ok(())) => {}
+    }
+
+    mixer
+        .lock()
+        .unwrap()
+        .fade_out((SAMPLE_RATE as f32 * FADE_OUT.as_secs_f32()) as usize);
+    actix_web::rt::time::sleep(FADE_OUT).await;
+    all_notes_off(&mut synth.lock().unwrap(), &mut held.lock().unwrap());
+    drop(device);
+
+    // the socket is removed when its lock drops.
+    res
 }
 
 #[actix_web::get("/stepper/")]
@@ -2,17 +2,28 @@ use anyhow::Result;
 use log::*;
 use midi_control::MidiMessage;
 use midir::{Ignore, MidiInput, PortInfoError};
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::sleep,
+    time::Duration,
 };
 use stepper_synth_backend::{
     sequencer::SequencerIntake, synth_engines::Synth, HashMap, MidiControlled,
 };
+use synth_lib::notes::HeldNotes;
 
-pub async fn run_midi(
+/// how often new MIDI ports are looked for.
+const RESCAN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// connects every MIDI input, as they are plugged in, to the synth until `exit` is set, then
+/// closes them. blocks, so it runs on its own thread.
+pub fn run_midi(
     seq: actix_web::web::Data<Mutex<SequencerIntake>>,
     synth: actix_web::web::Data<Mutex<Synth>>,
+    held: actix_web::web::Data<Mutex<HeldNotes>>,
     // updated: Arc<Mutex<bool>>,
     exit: Arc<AtomicBool>,
     // effect_midi: Arc<AtomicBool>,
@@ -55,6 +66,7 @@ pub async fn run_midi(
             let mut midi_in = MidiInput::new("midir reading input")?;
             midi_in.ignore(Ignore::None);
             let synth = synth.clone();
+            let held = held.clone();
             // let tx = tx.clone();
             // let updated = updated.clone();
             // let effect = effect_midi.clone();
@@ -89,6 +101,7 @@ pub async fn run_midi(
                         // }
 
                         synth.lock().unwrap().midi_input(&message);
+                        held.lock().unwrap().midi_input(msg);
 
                         let mut seq = seq.lock().unwrap();
 
@@ -100,7 +113,25 @@ pub async fn run_midi(
                 ),
             );
         }
+
+        sleep(RESCAN_INTERVAL);
+    }
+
+    for (port_name, connection) in registered_ports.drain() {
+        if let Ok(connection) = connection {
+            info!("closing port {port_name}");
+            connection.close();
+        }
     }
 
     Ok(())
 }
+
+/// releases every held note on every channel, so nothing hangs when the MIDI inputs go away.
+pub fn all_notes_off(synth: &mut Synth, held: &mut HeldNotes) {
+    for msg in held.note_offs() {
+        synth.midi_input(&MidiMessage::from(&msg[..]));
+    }
+
+    *held = HeldNotes::default();
+}
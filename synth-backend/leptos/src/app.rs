@@ -1,9 +1,25 @@
+use api::use_send_command;
 use leptos::prelude::*;
 use leptos_meta::{provide_meta_context, Stylesheet, Title};
-use leptos_router::{components::*, StaticSegment, WildcardSegment};
-use screens::channel_editor::ChannelEditor;
+use leptos_router::{components::*, path, StaticSegment, WildcardSegment};
+use screens::{
+    channel_editor::ChannelEditor,
+    dashboard::Dashboard,
+    lfos::Lfos,
+    sequencer::MidiSequencer,
+    settings::{provide_theme, Settings},
+    stepper::MidiStepper,
+    Channel,
+};
+use socket::provide_synth_socket;
+use synth_common::UiToBackend;
+use web_midi::provide_web_midi;
 
+mod api;
+pub mod keyboard;
 mod screens;
+mod socket;
+mod web_midi;
 
 pub trait ApiPage: 'static {
     fn loading_screen() -> impl IntoView {
@@ -38,6 +54,9 @@ pub trait ApiPage: 'static {
 pub fn App() -> impl IntoView {
     // Provides context that manages stylesheets, titles, meta tags, etc.
     provide_meta_context();
+    provide_synth_socket();
+    provide_web_midi();
+    let theme = provide_theme();
     // TODO: make on sse endpoint for each screen
 
     view! {
@@ -50,20 +69,21 @@ pub fn App() -> impl IntoView {
 
         // content for this welcome page
         <Router>
-            <main class="flex flex-row w-dvw h-dvh">
+            <main class=move || format!("{} flex flex-row w-dvw h-dvh", theme.get().class())>
                 <nav class="w-[10%] h-full">
                     // TODO: add side bar component here
                     <SideBar/>
                 </nav>
                 <div class="w-[90%] h-full">
                     <Routes fallback=move || "Not found.">
-                        // <Route path=StaticSegment("") view=HomePage/>
-                        // <Route path=StaticSegment("midi-stepper") view=MidiStepper/>
-                        // <Route path=StaticSegment("midi-seq") view=MidiSequencer/>
+                        <Route path=StaticSegment("") view=Dashboard/>
+                        <Route path=StaticSegment("midi-stepper") view=MidiStepper/>
+                        <Route path=StaticSegment("midi-seq") view=MidiSequencer/>
                         <Route path=StaticSegment("edit") view=ChannelEditor/>
-                        // <Route path=path!("/channel/:channel") view=Channel/>
-                        // <Route path=StaticSegment("settings") view=Settings/>
-                        <Route path=WildcardSegment("any") view=HomePage/>
+                        <Route path=path!("/channel/:channel") view=Channel/>
+                        <Route path=StaticSegment("lfos") view=Lfos/>
+                        <Route path=StaticSegment("settings") view=Settings/>
+                        <Route path=WildcardSegment("any") view=NotFound/>
                     </Routes>
                 </div>
             </main>
@@ -73,6 +93,8 @@ pub fn App() -> impl IntoView {
 
 #[component]
 fn SideBar() -> impl IntoView {
+    let send = use_send_command();
+
     view! {
         // <div class="justify-center">
         // <ol class="text-center felx-col items-center h-[50%] w-full ">
@@ -92,7 +114,15 @@ fn SideBar() -> impl IntoView {
             <a class="w-full h-[12.5%] align-text-middle" href="/channel/B">B</a>
             <a class="w-full h-[12.5%] align-text-middle" href="/channel/C">C</a>
             <a class="w-full h-[12.5%] align-text-middle" href="/channel/D">D</a>
+            <a class="w-full h-[12.5%] align-text-middle" href="/lfos">LFOs</a>
             <a class="w-full h-[12.5%] align-text-middle" href="/settings">Settings</a>
+            // always in reach, for stuck notes.
+            <button
+                class="w-full h-[12.5%] bg-ctp-red text-ctp-base"
+                on:click=move |_| send(UiToBackend::Panic)
+            >
+                "Panic"
+            </button>
         // </ol>
         </aside>
         // </div>
@@ -108,39 +138,26 @@ fn DefaultLoadingScreen() -> impl IntoView {
     }
 }
 
-/// Renders the home page of your application.
+/// 404 - Not Found
 #[component]
-fn HomePage() -> impl IntoView {
-    // Creates a reactive value to update the button
-    let count = RwSignal::new(0);
-    let on_click = move |_| *count.write() += 1;
+fn NotFound() -> impl IntoView {
+    // set an HTTP status code 404
+    // this is feature gated because it can only be done during
+    // initial server-side rendering
+    // if you navigate to the 404 page subsequently, the status
+    // code will not be set because there is not a new HTTP request
+    // to the server
+    #[cfg(feature = "ssr")]
+    {
+        // this can be done inline because it's synchronous
+        // if it were async, we'd use a server function
+        let resp = expect_context::<leptos_actix::ResponseOptions>();
+        resp.set_status(actix_web::http::StatusCode::NOT_FOUND);
+    }
 
     view! {
-        <h1>"Welcome to Leptos!"</h1>
-        <button on:click=on_click>"Click Me: " {count}</button>
+        <h1>
+            "Not Found"
+        </h1>
     }
 }
-
-// /// 404 - Not Found
-// #[component]
-// fn NotFound() -> impl IntoView {
-//     // set an HTTP status code 404
-//     // this is feature gated because it can only be done during
-//     // initial server-side rendering
-//     // if you navigate to the 404 page subsequently, the status
-//     // code will not be set because there is not a new HTTP request
-//     // to the server
-//     #[cfg(feature = "ssr")]
-//     {
-//         // this can be done inline because it's synchronous
-//         // if it were async, we'd use a server function
-//         let resp = expect_context::<leptos_actix::ResponseOptions>();
-//         resp.set_status(actix_web::http::StatusCode::NOT_FOUND);
-//     }
-//
-//     view! {
-//         <h1>
-//             "Not Found"
-//         </h1>
-//     }
-// }
@@ -0,0 +1,133 @@
+use leptos::{prelude::*, server_fn::codec::Json};
+#[cfg(feature = "ssr")]
+use synth_common::ChannelState;
+use synth_common::{TableChoice, UiToBackend};
+
+/// applies a command to the synth, the change comes back to every page through the state stream.
+#[server(input = Json)]
+pub async fn send_command(cmd: UiToBackend) -> Result<(), ServerFnError> {
+    use crate::synth_state::run_command;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    use std::sync::Mutex;
+    use stepper_synth_backend::synth_engines::Synth;
+    use synth_lib::{mixer::Mixer, notes::HeldNotes, voices::Voices, wavetable::WaveTables};
+
+    let synth: Data<Mutex<Synth>> = extract().await?;
+    let mixer: Data<Mutex<Mixer>> = extract().await?;
+    let held: Data<Mutex<HeldNotes>> = extract().await?;
+    let voices: Data<Mutex<Voices>> = extract().await?;
+    let tables: Data<Mutex<WaveTables>> = extract().await?;
+
+    run_command(&synth, &mixer, &held, &voices, &tables, cmd).map_err(ServerFnError::new)
+}
+
+/// an action that sends commands to the synth with `send_command`.
+pub fn use_send_command() -> impl Fn(UiToBackend) + Copy {
+    let action = ServerAction::<SendCommand>::new();
+
+    move |cmd| {
+        action.dispatch(SendCommand { cmd });
+    }
+}
+
+/// imports a base64 encoded WAV file as a user wavetable, it is kept with the other imported
+/// tables so it is there after a restart.
+#[server(input = Json)]
+pub async fn import_wave_table(name: String, wav: String) -> Result<TableChoice, ServerFnError> {
+    use actix_web::web::Data;
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use leptos_actix::extract;
+    use std::sync::Mutex;
+    use synth_lib::wavetable::WaveTables;
+
+    let tables: Data<Mutex<WaveTables>> = extract().await?;
+    let wav = BASE64_STANDARD.decode(wav).map_err(ServerFnError::new)?;
+    let choice = tables
+        .lock()
+        .unwrap()
+        .import(&name, &wav)
+        .map_err(ServerFnError::new)?;
+
+    Ok(choice)
+}
+
+/// the state of one channel, for saving it as a patch or loading one onto it.
+#[cfg(feature = "ssr")]
+async fn channel_state(channel: u8) -> Result<ChannelState, ServerFnError> {
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    use std::sync::Mutex;
+    use stepper_synth_backend::synth_engines::Synth;
+    use synth_engine::synth_state;
+    use synth_lib::{mixer::Mixer, notes::HeldNotes, voices::Voices, wavetable::WaveTables};
+
+    let synth: Data<Mutex<Synth>> = extract().await?;
+    let mixer: Data<Mutex<Mixer>> = extract().await?;
+    let held: Data<Mutex<HeldNotes>> = extract().await?;
+    let voices: Data<Mutex<Voices>> = extract().await?;
+    let tables: Data<Mutex<WaveTables>> = extract().await?;
+
+    // NOTE: always lock the synth before the mixer, the audio thread does the same.
+    let state = synth_state(
+        &synth.lock().unwrap(),
+        &mixer.lock().unwrap(),
+        &held.lock().unwrap(),
+        &voices.lock().unwrap(),
+        &tables.lock().unwrap(),
+    );
+
+    state
+        .channels
+        .get(channel as usize)
+        .cloned()
+        .ok_or_else(|| ServerFnError::new(format!("there is no channel {channel}")))
+}
+
+/// the names of the saved patches.
+#[server]
+pub async fn list_patches() -> Result<Vec<String>, ServerFnError> {
+    Ok(synth_common::list_patches())
+}
+
+/// saves a channel's sound as a patch.
+#[server(input = Json)]
+pub async fn save_patch(channel: u8, name: String) -> Result<(), ServerFnError> {
+    use synth_common::Patch;
+
+    let patch = Patch::from(&channel_state(channel).await?);
+
+    synth_common::save_patch(&name, &patch).map_err(ServerFnError::new)
+}
+
+/// replaces a channel's sound with a saved patch.
+#[server(input = Json)]
+pub async fn load_patch(channel: u8, name: String) -> Result<(), ServerFnError> {
+    use crate::synth_state::run_command;
+    use actix_web::web::Data;
+    use leptos_actix::extract;
+    use std::sync::Mutex;
+    use stepper_synth_backend::synth_engines::Synth;
+    use synth_lib::{mixer::Mixer, notes::HeldNotes, voices::Voices, wavetable::WaveTables};
+
+    let patch = synth_common::load_patch(&name).map_err(ServerFnError::new)?;
+    let current = channel_state(channel).await?;
+    let synth: Data<Mutex<Synth>> = extract().await?;
+    let mixer: Data<Mutex<Mixer>> = extract().await?;
+    let held: Data<Mutex<HeldNotes>> = extract().await?;
+    let voices: Data<Mutex<Voices>> = extract().await?;
+    let tables: Data<Mutex<WaveTables>> = extract().await?;
+
+    let cmds = patch.commands(channel, &current);
+
+    // a patch with a bad number in it is refused whole, rather than loaded half way.
+    for cmd in &cmds {
+        cmd.validate().map_err(ServerFnError::new)?;
+    }
+
+    for cmd in cmds {
+        run_command(&synth, &mixer, &held, &voices, &tables, cmd).map_err(ServerFnError::new)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use crate::app::socket::use_synth;
+use leptos::{ev, prelude::*};
+use std::collections::{HashMap, HashSet};
+use synth_common::{MidiNote, MidiToBackend, UiToBackend, N_CHANNELS};
+
+/// how many octaves of keys are drawn.
+const OCTAVES: u8 = 2;
+/// the lowest and highest octave the keyboard can be shifted to.
+const OCTAVE_RANGE: (u8, u8) = (1, 8);
+const DEFAULT_VELOCITY: u8 = 100;
+/// the velocity change per press of the velocity keys.
+const VELOCITY_STEP: u8 = 10;
+
+/// the computer keys that play notes, in semitones from the keyboard's first C. the bottom two
+/// letter rows laid out like a piano, the way trackers do it.
+const NOTE_KEYS: [(&str, u8); 17] = [
+    ("a", 0),
+    ("w", 1),
+    ("s", 2),
+    ("e", 3),
+    ("d", 4),
+    ("f", 5),
+    ("t", 6),
+    ("g", 7),
+    ("y", 8),
+    ("h", 9),
+    ("u", 10),
+    ("j", 11),
+    ("k", 12),
+    ("o", 13),
+    ("l", 14),
+    ("p", 15),
+    (";", 16),
+];
+
+fn is_black(note: MidiNote) -> bool {
+    matches!(note % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+/// a piano that plays a channel from the mouse, touch screens and the computer keyboard. `z`/`x`
+/// shift the octave and `c`/`v` change the velocity.
+#[component]
+pub fn Keyboard(
+    /// the channel to play, when not given a picker is shown.
+    #[prop(optional)]
+    channel: Option<u8>,
+) -> impl IntoView {
+    let synth = use_synth();
+    let picked_channel = RwSignal::new(channel.unwrap_or_default());
+    let octave = RwSignal::new(4u8);
+    let velocity = RwSignal::new(DEFAULT_VELOCITY);
+    // the notes sounding, with the channel they were started on so they stop there too.
+    let sounding = RwSignal::new(HashMap::<MidiNote, u8>::new());
+    // the computer keys held down and the note each one started.
+    let held_keys = StoredValue::new(HashMap::<String, MidiNote>::new());
+
+    let first_note = move || (octave.get_untracked() + 1) * 12;
+    let octave_down =
+        move || octave.update(|octave| *octave = octave.saturating_sub(1).max(OCTAVE_RANGE.0));
+    let octave_up = move || octave.update(|octave| *octave = (*octave + 1).min(OCTAVE_RANGE.1));
+
+    let note_on = move |note: MidiNote| {
+        if note > 127 || sounding.read_untracked().contains_key(&note) {
+            return;
+        }
+
+        let channel = picked_channel.get_untracked();
+        sounding.update(|sounding| {
+            sounding.insert(note, channel);
+        });
+        synth.send(UiToBackend::Midi(MidiToBackend::NodeOn {
+            note,
+            vel: velocity.get_untracked(),
+            channel,
+        }));
+    };
+    let note_off = move |note: MidiNote| {
+        let mut channel = None;
+        sounding.update(|sounding| channel = sounding.remove(&note));
+
+        if let Some(channel) = channel {
+            synth.send(UiToBackend::Midi(MidiToBackend::NodeOff { note, channel }));
+        }
+    };
+    let all_off = move || {
+        let notes: Vec<_> = sounding.read_untracked().keys().copied().collect();
+        notes.into_iter().for_each(note_off);
+    };
+
+    let key_down = window_event_listener(ev::keydown, move |ev| {
+        if ev.repeat() || ev.ctrl_key() || ev.alt_key() || ev.meta_key() {
+            return;
+        }
+
+        let key = ev.key().to_lowercase();
+
+        match key.as_str() {
+            "z" => octave_down(),
+            "x" => octave_up(),
+            "c" => velocity.update(|vel| *vel = vel.saturating_sub(VELOCITY_STEP).max(1)),
+            "v" => velocity.update(|vel| *vel = (*vel + VELOCITY_STEP).min(127)),
+            _ => {
+                if let Some((_, semitone)) = NOTE_KEYS.iter().find(|(k, _)| *k == key) {
+                    let note = first_note() + semitone;
+                    held_keys.update_value(|keys| {
+                        keys.insert(key, note);
+                    });
+                    note_on(note);
+                }
+            }
+        }
+    });
+    let key_up = window_event_listener(ev::keyup, move |ev| {
+        let mut note = None;
+        held_keys.update_value(|keys| note = keys.remove(&ev.key().to_lowercase()));
+
+        if let Some(note) = note {
+            note_off(note);
+        }
+    });
+    // a key released while the page is in the background never sends a keyup.
+    let blur = window_event_listener(ev::blur, move |_| {
+        held_keys.update_value(|keys| keys.clear());
+        all_off();
+    });
+    on_cleanup(move || {
+        key_down.remove();
+        key_up.remove();
+        blur.remove();
+        all_off();
+    });
+
+    let keys = move || {
+        let first = (octave.get() + 1) * 12;
+        let pressed: HashSet<_> = sounding.read().keys().copied().collect();
+
+        (first..=(first + OCTAVES * 12).min(127))
+            .map(|note| {
+                let black = is_black(note);
+
+                view! {
+                    <div
+                        class="border border-ctp-crust select-none"
+                        class=(["w-[4%]", "h-[60%]", "-mx-[2%]", "z-10", "bg-ctp-crust"], black)
+                        class=(["w-[7%]", "h-full", "bg-ctp-text"], !black)
+                        class:bg-ctp-blue=pressed.contains(&note)
+                        style="touch-action: none"
+                        on:pointerdown=move |_| note_on(note)
+                        on:pointerup=move |_| note_off(note)
+                        on:pointerleave=move |_| note_off(note)
+                        on:pointerenter=move |ev| {
+                            // slide across the keys while the button or finger is down.
+                            if ev.buttons() != 0 {
+                                note_on(note)
+                            }
+                        }
+                    ></div>
+                }
+            })
+            .collect_view()
+    };
+
+    let set_channel = move |ev| {
+        if let Ok(channel) = event_target_value(&ev).parse() {
+            all_off();
+            picked_channel.set(channel);
+        }
+    };
+    let set_velocity = move |ev| {
+        if let Ok(vel) = event_target_value(&ev).parse() {
+            velocity.set(vel);
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 w-full h-[30%]">
+            <div class="flex flex-row gap-4 items-center">
+                {channel
+                    .is_none()
+                    .then(|| {
+                        view! {
+                            <select on:change=set_channel>
+                                {["A", "B", "C", "D"]
+                                    .into_iter()
+                                    .take(N_CHANNELS)
+                                    .enumerate()
+                                    .map(|(i, name)| {
+                                        view! { <option value=i.to_string()>{name}</option> }
+                                    })
+                                    .collect_view()}
+                            </select>
+                        }
+                    })}
+                <button on:click=move |_| octave_down()>"oct -"</button>
+                <p>{move || format!("C{}", octave.get())}</p>
+                <button on:click=move |_| octave_up()>"oct +"</button>
+                <p>"velocity"</p>
+                <input
+                    type="range"
+                    min=1
+                    max=127
+                    step=1
+                    prop:value=move || velocity.get()
+                    on:input=set_velocity
+                />
+                <p>{move || velocity.get()}</p>
+            </div>
+            <div class="flex flex-row items-start h-full">{keys}</div>
+        </div>
+    }
+}
@@ -1,9 +1,198 @@
+use crate::app::{api::use_send_command, socket::use_synth};
 use leptos::prelude::*;
+use synth_common::{
+    EffectType, EngineType, MeterState, UiToBackend, CHANNEL_NAMES, METER_FLOOR_DB, N_CHANNELS,
+};
+
+/// how far along the meter a level in dB is, as a css width.
+fn meter_width(db: f32) -> String {
+    format!(
+        "width: {}%",
+        (1.0 - db / METER_FLOOR_DB).clamp(0.0, 1.0) * 100.0
+    )
+}
+
+/// the index of the picked `<option>`, they are numbered by their place in the list.
+fn picked(ev: &leptos::ev::Event) -> Option<usize> {
+    event_target_value(ev).parse().ok()
+}
+
+/// a peak bar over an RMS bar, the peak turns red when it clips.
+#[component]
+fn Meter(meter: Signal<MeterState>) -> impl IntoView {
+    view! {
+        <div class="flex flex-col gap-1 w-full">
+            <div class="h-2 w-full bg-gray-700">
+                <div
+                    class="h-full"
+                    class:bg-green-500=move || !meter.get().clipping()
+                    class:bg-red-500=move || meter.get().clipping()
+                    style=move || meter_width(meter.get().peak_db)
+                ></div>
+            </div>
+            <div class="h-2 w-full bg-gray-700">
+                <div class="h-full bg-blue-500" style=move || meter_width(meter.get().rms_db)></div>
+            </div>
+            <p class="text-xs">
+                {move || format!("{:.0} / {:.0} dB", meter.get().peak_db, meter.get().rms_db)}
+            </p>
+        </div>
+    }
+}
+
+/// the effect picker and bypass switch for one of a channel's effect slots.
+#[component]
+fn EffectSlot(channel: u8, slot: u8) -> impl IntoView {
+    let synth = use_synth();
+    let send = use_send_command();
+    let effect = move || {
+        synth
+            .state
+            .get()
+            .and_then(|state| state.channels[channel as usize].effects[slot as usize])
+    };
+
+    // option 0 is the empty slot, the rest follow `EffectType::ALL`.
+    let set_effect = move |ev| {
+        if let Some(i) = picked(&ev) {
+            let effect = i
+                .checked_sub(1)
+                .and_then(|i| EffectType::ALL.get(i).copied());
+            send(UiToBackend::SetEffect {
+                channel,
+                slot,
+                effect,
+            });
+        }
+    };
+    let set_power = move |ev| {
+        send(UiToBackend::SetEffectPower {
+            channel,
+            slot,
+            on: event_target_checked(&ev),
+        })
+    };
+
+    view! {
+        <div class="flex flex-row gap-2 w-[25%]">
+            <select on:change=set_effect>
+                <option value="0" selected=move || effect().is_none()>"None"</option>
+                {EffectType::ALL
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, effect_type)| {
+                        view! {
+                            <option
+                                value=(i + 1).to_string()
+                                selected=move || effect().is_some_and(|e| e.effect == effect_type)
+                            >
+                                {effect_type.to_string()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+            <input
+                type="checkbox"
+                disabled=move || effect().is_none()
+                prop:checked=move || effect().is_some_and(|e| e.on)
+                on:change=set_power
+            />
+        </div>
+    }
+}
+
+/// one row of the editor, the channel's engine, effects, volume and level.
+#[component]
+fn ChannelRow(channel: u8) -> impl IntoView {
+    let synth = use_synth();
+    let send = use_send_command();
+    let chan = move || {
+        synth
+            .state
+            .get()
+            .map(|state| state.channels[channel as usize].clone())
+            .unwrap_or_default()
+    };
+    let meter = Signal::derive(move || chan().meter);
+
+    let set_engine = move |ev| {
+        if let Some(engine) = picked(&ev).and_then(|i| EngineType::ALL.get(i).copied()) {
+            send(UiToBackend::SetEngine { channel, engine });
+        }
+    };
+    let set_volume = move |ev| {
+        if let Ok(volume) = event_target_value(&ev).parse() {
+            send(UiToBackend::SetVolume { channel, volume });
+        }
+    };
+
+    view! {
+        <div class="flex flex-row items-center gap-4 h-[20%]">
+            <p class="w-[5%] text-center">{CHANNEL_NAMES[channel as usize]}</p>
+            <select class="w-[20%]" on:change=set_engine>
+                {EngineType::ALL
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, engine)| {
+                        view! {
+                            <option value=i.to_string() selected=move || chan().engine == engine>
+                                {engine.to_string()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+            <EffectSlot channel slot=0/>
+            <EffectSlot channel slot=1/>
+            <button class="w-[5%]" on:click=move |_| send(UiToBackend::SwapEffects { channel })>
+                "<>"
+            </button>
+            <div class="flex flex-col gap-1 w-[20%]">
+                <div class="flex flex-row gap-2">
+                    <button
+                        class:text-red-500=move || chan().mute
+                        on:click=move |_| send(UiToBackend::SetMute { channel, mute: !chan().mute })
+                    >
+                        "M"
+                    </button>
+                    <input
+                        type="range"
+                        min="0"
+                        max="1"
+                        step="0.01"
+                        prop:value=move || chan().volume
+                        on:change=set_volume
+                    />
+                </div>
+                <Meter meter/>
+            </div>
+        </div>
+    }
+}
 
 #[component]
 pub fn ChannelEditor() -> impl IntoView {
+    let synth = use_synth();
+    let send = use_send_command();
+    let state = move || synth.state.get().unwrap_or_default();
+
+    let master = Signal::derive(move || state().master);
+    let set_limiter = move |ev| send(UiToBackend::SetLimiter(event_target_checked(&ev)));
+
     view! {
-        <p> "its a synth bro..." </p>
-        <div> "under construction check back later" </div>
+        <div class="flex flex-col w-full h-full p-4">
+            {(0..N_CHANNELS as u8).map(|channel| view! { <ChannelRow channel/> }).collect_view()}
+            <div class="flex flex-row items-center gap-4 h-[20%]">
+                <p class="w-[5%] text-center">"Out"</p>
+                <label class="w-[75%]">
+                    <input type="checkbox" prop:checked=move || state().limiter on:change=set_limiter/>
+                    " Limiter"
+                </label>
+                <div class="w-[20%]">
+                    <Meter meter=master/>
+                </div>
+            </div>
+        </div>
     }
 }
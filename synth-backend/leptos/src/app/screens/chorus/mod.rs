@@ -0,0 +1,54 @@
+use super::KnobSlider;
+use crate::app::api::use_send_command;
+use leptos::prelude::*;
+use synth_common::{ChannelState, EffectType, UiToBackend};
+
+/// the chorus in one of the channel's effect slots.
+#[component]
+pub fn ChorusDisplay(channel: u8, slot: u8, chan: Signal<ChannelState>) -> impl IntoView {
+    let send = use_send_command();
+    let effect = move || chan.get().effects[slot as usize];
+
+    let controls: Vec<_> = EffectType::Chorus
+        .param_names()
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let value = Signal::derive(move || effect().map(|e| e.params[i]).unwrap_or_default());
+            let set_param = move |value| {
+                send(UiToBackend::SetEffectParam {
+                    channel,
+                    slot,
+                    param: i as u8,
+                    value,
+                })
+            };
+
+            view! { <KnobSlider name=*name value steps=1000 on_change=set_param/> }
+        })
+        .collect();
+    let set_power = move |ev| {
+        send(UiToBackend::SetEffectPower {
+            channel,
+            slot,
+            on: event_target_checked(&ev),
+        })
+    };
+
+    view! {
+        <div class="flex flex-row gap-4 items-center">
+            <h1> "Chorus" </h1>
+            <label>
+                <input
+                    type="checkbox"
+                    prop:checked=move || effect().is_some_and(|e| e.on)
+                    on:change=set_power
+                />
+                " On"
+            </label>
+        </div>
+        <div class="flex flex-row gap-4">
+            { controls }
+        </div>
+    }
+}
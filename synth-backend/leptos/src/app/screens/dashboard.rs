@@ -0,0 +1,77 @@
+use crate::app::{keyboard::Keyboard, socket::use_synth};
+use leptos::prelude::*;
+use synth_common::{ChannelState, CHANNEL_NAMES, N_CHANNELS};
+
+/// a channel's engine, effects and activity, linking to its screen.
+#[component]
+fn ChannelSummary(channel: usize, chan: Signal<ChannelState>) -> impl IntoView {
+    let effects = move || {
+        let effects: Vec<_> = chan
+            .get()
+            .effects
+            .iter()
+            .flatten()
+            .map(|effect| {
+                if effect.on {
+                    effect.effect.to_string()
+                } else {
+                    format!("({})", effect.effect)
+                }
+            })
+            .collect();
+
+        if effects.is_empty() {
+            "no effects".to_string()
+        } else {
+            effects.join(" > ")
+        }
+    };
+
+    view! {
+        <a
+            class="flex flex-col gap-1 p-4 w-[25%] bg-ctp-surface0"
+            href=format!("/channel/{}", CHANNEL_NAMES[channel])
+        >
+            <h2 class="text-xl">{CHANNEL_NAMES[channel]}</h2>
+            <p>{move || chan.get().engine.to_string()}</p>
+            <p class="text-sm">{effects}</p>
+            <div class="flex flex-row gap-2">
+                <p class:text-ctp-green=move || chan.get().held_notes > 0>"●"</p>
+                <p>{move || format!("{} voices", chan.get().active_voices)}</p>
+                <p class:text-ctp-red=move || chan.get().mute>"M"</p>
+                <p>{move || format!("{:.0} dB", chan.get().meter.peak_db)}</p>
+            </div>
+        </a>
+    }
+}
+
+/// the home page, a summary of every channel and the transport.
+#[component]
+pub fn Dashboard() -> impl IntoView {
+    let synth = use_synth();
+    let state = Signal::derive(move || synth.state.get().unwrap_or_default());
+    let song = move || state.get().song;
+
+    view! {
+        <div class="flex flex-col gap-4 p-4">
+            <div class="flex flex-row gap-4">
+                <p>{move || if synth.connected() { "connected" } else { "connecting..." }}</p>
+                <p>{move || format!("{:.0} bpm", song().tempo)}</p>
+                <p class:text-ctp-green=move || song().playing>
+                    {move || if song().playing { "▶ playing" } else { "stopped" }}
+                </p>
+                <p class="text-ctp-red" class:invisible=move || !song().recording>"● recording"</p>
+            </div>
+            <div class="flex flex-row gap-4">
+                {(0..N_CHANNELS)
+                    .map(|channel| {
+                        let chan = Signal::derive(move || state.get().channels[channel].clone());
+
+                        view! { <ChannelSummary channel chan/> }
+                    })
+                    .collect_view()}
+            </div>
+            <Keyboard/>
+        </div>
+    }
+}
@@ -0,0 +1,97 @@
+use crate::app::api::use_send_command;
+use leptos::prelude::*;
+use synth_common::{BendRange, ChannelState, UiToBackend, MAX_BEND_RANGE};
+
+/// a labeled bar for one of the expressive controllers. `bipolar` bars grow out from the middle.
+#[component]
+fn Controller(
+    name: &'static str,
+    value: Signal<f32>,
+    #[prop(optional)] bipolar: bool,
+) -> impl IntoView {
+    let style = move || {
+        let value = value.get();
+
+        if bipolar {
+            let width = value.abs() * 50.0;
+            let left = if value < 0.0 { 50.0 - width } else { 50.0 };
+
+            format!("margin-left: {left}%; width: {width}%")
+        } else {
+            format!("width: {}%", value.clamp(0.0, 1.0) * 100.0)
+        }
+    };
+
+    view! {
+        <div class="flex flex-row gap-2 items-center">
+            <p class="w-24">{name}</p>
+            <div class="h-2 w-full bg-gray-700">
+                <div class="h-full bg-blue-500" style=style></div>
+            </div>
+            <p class="w-12 text-xs">{move || format!("{:.2}", value.get())}</p>
+        </div>
+    }
+}
+
+/// the channel's bend range, and where its pitch bend, mod wheel and aftertouch are.
+#[component]
+pub fn ExpressionDisplay(channel: u8, chan: Signal<ChannelState>) -> impl IntoView {
+    let send = use_send_command();
+    let range = Memo::new(move |_| chan.get().bend_range);
+    let set = move |range: BendRange| send(UiToBackend::SetBendRange { channel, range });
+    let set_up = move |ev| {
+        if let Ok(up) = event_target_value(&ev).parse::<u8>() {
+            set(BendRange {
+                up,
+                ..range.get_untracked()
+            })
+        }
+    };
+    let set_down = move |ev| {
+        if let Ok(down) = event_target_value(&ev).parse::<u8>() {
+            set(BendRange {
+                down,
+                ..range.get_untracked()
+            })
+        }
+    };
+    let expression =
+        move |read: fn(&ChannelState) -> f32| Signal::derive(move || read(&chan.get()));
+
+    view! {
+        <div class="flex flex-row gap-4">
+            <div class="flex flex-col items-center">
+                <p>"Bend Up"</p>
+                <input
+                    class="horizontal-slider"
+                    type="range"
+                    min=0
+                    max=MAX_BEND_RANGE
+                    step=1
+                    prop:value=move || range.get().up
+                    on:input=set_up
+                />
+                <p>{move || format!("{} st", range.get().up)}</p>
+            </div>
+            <div class="flex flex-col items-center">
+                <p>"Bend Down"</p>
+                <input
+                    class="horizontal-slider"
+                    type="range"
+                    min=0
+                    max=MAX_BEND_RANGE
+                    step=1
+                    prop:value=move || range.get().down
+                    on:input=set_down
+                />
+                <p>{move || format!("{} st", range.get().down)}</p>
+            </div>
+            <div class="flex flex-col gap-1 w-full">
+                <Controller name="Bend" value=expression(|chan| chan.expression.bend) bipolar=true/>
+                <Controller name="Mod Wheel" value=expression(|chan| chan.expression.mod_wheel)/>
+                <Controller name="Pressure" value=expression(|chan| chan.expression.pressure)/>
+                <Controller name="Poly AT" value=expression(|chan| chan.expression.poly_pressure)/>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,197 @@
+use crate::app::{api::use_send_command, socket::use_synth};
+use leptos::prelude::*;
+use synth_common::{
+    LfoSettings, LfoShape, LfoState, LfoSync, UiToBackend, LFO_RATES, MAX_FADE_IN, N_LFOS,
+};
+
+/// the shape, rate or sync, retrigger, phase and fade in of one LFO, and where it is.
+#[component]
+fn LfoPanel(lfo: u8, state: Signal<LfoState>, tempo: Signal<f32>) -> impl IntoView {
+    let send = use_send_command();
+    let settings = Memo::new(move |_| state.get().settings);
+    let set = move |f: &dyn Fn(LfoSettings) -> LfoSettings| {
+        send(UiToBackend::SetLfo {
+            lfo,
+            settings: f(settings.get_untracked()),
+        })
+    };
+
+    // the options are numbered by their place in `ALL`.
+    let set_shape = move |ev| {
+        if let Some(shape) = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| LfoShape::ALL.get(i).copied())
+        {
+            set(&|settings| LfoSettings { shape, ..settings })
+        }
+    };
+    let set_sync = move |ev| {
+        if let Some(sync) = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| LfoSync::ALL.get(i).copied())
+        {
+            set(&|settings| LfoSettings { sync, ..settings })
+        }
+    };
+    let set_rate = move |ev| {
+        if let Ok(rate) = event_target_value(&ev).parse::<f32>() {
+            set(&|settings| LfoSettings { rate, ..settings })
+        }
+    };
+    let set_phase = move |ev| {
+        if let Ok(phase) = event_target_value(&ev).parse::<f32>() {
+            set(&|settings| LfoSettings { phase, ..settings })
+        }
+    };
+    let set_fade_in = move |ev| {
+        if let Ok(fade_in) = event_target_value(&ev).parse::<f32>() {
+            set(&|settings| LfoSettings {
+                fade_in,
+                ..settings
+            })
+        }
+    };
+    let set_retrigger = move |ev| {
+        let retrigger = event_target_checked(&ev);
+        set(&|settings| LfoSettings {
+            retrigger,
+            ..settings
+        })
+    };
+    let synced = move || settings.get().sync != LfoSync::Off;
+    // the bar grows out from the middle.
+    let value_style = move || {
+        let value = state.get().value;
+        let width = value.abs() * 50.0;
+        let left = if value < 0.0 { 50.0 - width } else { 50.0 };
+
+        format!("margin-left: {left}%; width: {width}%")
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 p-4 bg-ctp-surface0">
+            <div class="flex flex-row gap-4 items-center">
+                <h2 class="text-xl">{format!("LFO {}", lfo + 1)}</h2>
+                <div class="h-2 w-full bg-gray-700">
+                    <div class="h-full bg-blue-500" style=value_style></div>
+                </div>
+            </div>
+            <div class="flex flex-row gap-4 items-center">
+                <p>"Shape"</p>
+                <select on:change=set_shape>
+                    {LfoShape::ALL
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, shape)| {
+                            view! {
+                                <option value=i.to_string() selected=move || settings.get().shape == shape>
+                                    {shape.to_string()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+                <p>"Sync"</p>
+                <select on:change=set_sync>
+                    {LfoSync::ALL
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, sync)| {
+                            view! {
+                                <option value=i.to_string() selected=move || settings.get().sync == sync>
+                                    {sync.to_string()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+                <label class="flex flex-row gap-2 items-center">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || settings.get().retrigger
+                        on:change=set_retrigger
+                    />
+                    "Retrigger"
+                </label>
+            </div>
+            // a synced LFO's rate comes from the tempo.
+            <div class="flex flex-row gap-4 items-center">
+                <p class="w-16">"Rate"</p>
+                <input
+                    class="horizontal-slider w-full"
+                    type="range"
+                    min=*LFO_RATES.start()
+                    max=*LFO_RATES.end()
+                    step=0.01
+                    disabled=synced
+                    prop:value=move || settings.get().rate
+                    on:input=set_rate
+                />
+                <p class="w-40 text-xs">
+                    {move || {
+                        let settings = settings.get();
+                        let tempo = tempo.get();
+
+                        if settings.sync == LfoSync::Off {
+                            format!("{:.2} Hz", settings.rate)
+                        } else {
+                            format!("{:.2} Hz at {tempo:.0} bpm", settings.frequency(tempo))
+                        }
+                    }}
+                </p>
+            </div>
+            <div class="flex flex-row gap-4 items-center">
+                <p class="w-16">"Phase"</p>
+                <input
+                    class="horizontal-slider w-full"
+                    type="range"
+                    min=0.0
+                    max=1.0
+                    step=0.01
+                    prop:value=move || settings.get().phase
+                    on:input=set_phase
+                />
+                <p class="w-40 text-xs">{move || format!("{:.2}", settings.get().phase)}</p>
+            </div>
+            <div class="flex flex-row gap-4 items-center">
+                <p class="w-16">"Fade In"</p>
+                <input
+                    class="horizontal-slider w-full"
+                    type="range"
+                    min=0.0
+                    max=MAX_FADE_IN
+                    step=0.01
+                    prop:value=move || settings.get().fade_in
+                    on:input=set_fade_in
+                />
+                <p class="w-40 text-xs">{move || format!("{:.2} s", settings.get().fade_in)}</p>
+            </div>
+        </div>
+    }
+}
+
+/// the global LFOs the channels' mod matrices read.
+#[component]
+pub fn Lfos() -> impl IntoView {
+    let synth = use_synth();
+    let state = Signal::derive(move || synth.state.get().unwrap_or_default());
+    let tempo = Signal::derive(move || state.get().song.tempo);
+
+    view! {
+        <div class="flex flex-col gap-4 p-4">
+            {(0..N_LFOS)
+                .map(|lfo| {
+                    view! {
+                        <LfoPanel
+                            lfo=lfo as u8
+                            state=Signal::derive(move || state.get().lfos[lfo])
+                            tempo
+                        />
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
@@ -1,71 +1,163 @@
+use crate::app::{api::use_send_command, keyboard::Keyboard, socket::use_synth};
 use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+use synth_common::{ChannelState, EffectType, EngineType, UiToBackend, CHANNEL_NAMES};
 
 pub mod channel_editor;
-// pub mod organ;
-// pub mod reverb;
-// pub mod sub_synth;
-// pub mod wurlitzer;
+pub mod chorus;
+pub mod dashboard;
+pub mod effect;
+pub mod expression;
+pub mod lfos;
+pub mod mod_matrix;
+pub mod organ;
+pub mod patches;
+pub mod reverb;
+pub mod sequencer;
+pub mod settings;
+pub mod stepper;
+pub mod sub_synth;
+pub mod voices;
+pub mod wave_table;
+pub mod wurlitzer;
 
-// struct SynthScreen;
-//
-// impl ApiPage for SynthScreen {
-//     fn show(b64_state: ReadSignal<String>) -> impl IntoView {
-//         let synth_state = move || -> SynthEngineState {
-//             bincode::serde::decode_from_slice(
-//                 &BASE64_STANDARD_NO_PAD
-//                     .decode(b64_state.get().as_bytes())
-//                     .unwrap(),
-//                 bincode::config::standard(),
-//             )
-//             .unwrap()
-//             .0
-//         };
-//
-//         // let display_state = move || format!("{}", synth_state().engine);
-//
-//         view! {
-//             { move ||
-//                 match synth_state().engine {
-//                     // SynthEngineType::B3Organ => view! { <organ::OrganDisplay get_state=synth_state/> }.into_any(),
-//                     // SynthEngineType::SubSynth => view! { <sub_synth::SubSynthDisplay get_state=synth_state/> }.into_any(),
-//                     // SynthEngineType::Wurlitzer => view! { <wurlitzer::WurlitzerDisplay get_state=synth_state/> }.into_any(),
-//                     // SynthEngineType::WaveTable => view! { <div> "TODO" </div> }.into_any(),
-//                     // SynthEngineType::MidiOut => view! { <div> "TODO" </div> }.into_any(),
-//                     _ => view! { <div> "TODO" </div> }.into_any(),
-//                 }
-//             }
-//         }
-//     }
-// }
+/// the channel a `/channel/:channel` path names, `A` through `D`.
+fn channel_index(name: &str) -> Option<u8> {
+    CHANNEL_NAMES
+        .iter()
+        .position(|channel| channel.eq_ignore_ascii_case(name))
+        .map(|channel| channel as u8)
+}
+
+/// a labeled slider for a knob that goes from `0.0` to `1.0` in `steps` steps.
+#[component]
+pub fn KnobSlider(
+    #[prop(into)] name: String,
+    value: Signal<f32>,
+    steps: u32,
+    on_change: impl Fn(f32) + 'static,
+    /// draws the slider standing up, like an organ's draw bar.
+    #[prop(optional)]
+    vertical: bool,
+) -> impl IntoView {
+    let set_value = move |ev| {
+        if let Ok(step) = event_target_value(&ev).parse::<f32>() {
+            on_change(step / steps as f32);
+        }
+    };
+
+    view! {
+        <div class="flex flex-col items-center">
+            <p>{name}</p>
+            <input
+                class=if vertical { "vert-slider" } else { "horizontal-slider" }
+                type="range"
+                min=0
+                max=steps
+                step=1
+                prop:value=move || (value.get() * steps as f32).round()
+                on:input=set_value
+            />
+            <p>{move || format!("{:.0}", value.get() * steps as f32)}</p>
+        </div>
+    }
+}
+
+/// the channel's voice settings and expression, the controls of its engine and the effects in its
+/// slots.
+#[component]
+fn ChannelScreen(channel: u8) -> impl IntoView {
+    let synth = use_synth();
+    let chan = Signal::derive(move || {
+        synth
+            .state
+            .get()
+            .map(|state| state.channels[channel as usize].clone())
+            .unwrap_or_default()
+    });
+    let engine = Memo::new(move |_| chan.get().engine);
+    let effect = move |slot: usize| Memo::new(move |_| chan.get().effects[slot].map(|e| e.effect));
+
+    let effects = (0..2)
+        .map(|slot| {
+            let effect = effect(slot);
+
+            move || match effect.get() {
+                Some(EffectType::Reverb) => {
+                    view! { <reverb::ReverbDisplay channel slot={slot as u8} chan/> }.into_any()
+                }
+                Some(EffectType::Chorus) => {
+                    view! { <chorus::ChorusDisplay channel slot={slot as u8} chan/> }.into_any()
+                }
+                Some(effect) => {
+                    view! { <effect::EffectDisplay channel slot={slot as u8} effect chan/> }
+                        .into_any()
+                }
+                None => ().into_any(),
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class="flex flex-col w-full h-full p-4 gap-4">
+            <patches::PatchPanel channel/>
+            <voices::VoiceDisplay channel chan/>
+            <expression::ExpressionDisplay channel chan/>
+            <mod_matrix::ModMatrixDisplay channel chan/>
+            {move || match engine.get() {
+                EngineType::B3Organ => view! { <organ::OrganDisplay channel chan/> }.into_any(),
+                EngineType::SubSynth => {
+                    view! { <sub_synth::SubSynthDisplay channel chan/> }.into_any()
+                }
+                EngineType::Wurlitzer => {
+                    view! { <wurlitzer::WurlitzerDisplay channel chan/> }.into_any()
+                }
+                EngineType::WaveTable => {
+                    view! { <wave_table::WaveTableDisplay channel chan/> }.into_any()
+                }
+                EngineType::MidiOut => view! { <UnderConstruction/> }.into_any(),
+            }}
+            {effects}
+            <Keyboard channel/>
+        </div>
+    }
+}
+
+/// the `/channel/:channel` page.
+#[component]
+pub fn Channel() -> impl IntoView {
+    let params = use_params_map();
+    let channel = move || {
+        params
+            .read()
+            .get("channel")
+            .as_deref()
+            .and_then(channel_index)
+    };
+
+    move || match channel() {
+        Some(channel) => view! { <ChannelScreen channel/> }.into_any(),
+        None => view! { <p>"there are only channels A, B, C and D."</p> }.into_any(),
+    }
+}
 
-// struct EffectScreen;
-//
-// impl ApiPage for EffectScreen {
-//     fn show(b64_state: ReadSignal<String>) -> impl IntoView {
-//         let synth_state = move || -> crate::SynthEffectState {
-//             bincode::serde::decode_from_slice(
-//                 &BASE64_STANDARD_NO_PAD
-//                     .decode(b64_state.get().as_bytes())
-//                     .unwrap(),
-//                 bincode::config::standard(),
-//             )
-//             .unwrap()
-//             .0
-//         };
-//
-//         // let display_state = move || format!("{}", synth_state().engine);
-//
-//         view! {
-//             { move ||
-//                 match synth_state().effect {
-//                     // EffectType::Reverb => view! { <reverb::ReverbDisplay get_state=synth_state/> }.into_any(),
-//                     // EffectType::Chorus => view! { <UnderConstruction/> }.into_any(),
-//                     _ => view! { <UnderConstruction/> }.into_any(),
-//                 }
-//             }
-//         }
-//     }
-// }
+/// sets knob `knob` of the channel's engine.
+pub fn send_knob(channel: u8, knob: u8) -> impl Fn(f32) + Copy {
+    let send = use_send_command();
+
+    move |value| {
+        send(UiToBackend::SetKnob {
+            channel,
+            knob,
+            value,
+        })
+    }
+}
+
+/// the value of knob `knob` of the channel's engine.
+pub fn knob(chan: Signal<ChannelState>, knob: usize) -> Signal<f32> {
+    Signal::derive(move || chan.get().knobs[knob])
+}
 
 #[component]
 pub fn UnderConstruction() -> impl IntoView {
@@ -74,28 +166,3 @@ pub fn UnderConstruction() -> impl IntoView {
         <div> "under construction check back later" </div>
     }
 }
-
-// #[component]
-// pub fn SynthPage() -> impl IntoView {
-//     let UseEventSourceReturn { data, .. } =
-//         // use_event_source::<String, FromToStringCodec>("http://127.0.0.1:3000/synth-state/engine");
-//         use_event_source::<String, FromToStringCodec>("/synth-state/engine");
-//
-//     view! {
-//         {
-//             SynthScreen::display(data)
-//         }
-//     }
-// }
-//
-// #[component]
-// pub fn EffectPage() -> impl IntoView {
-//     let UseEventSourceReturn { data, .. } =
-//         use_event_source::<String, FromToStringCodec>("/synth-state/effect");
-//
-//     view! {
-//         {
-//             EffectScreen::display(data)
-//         }
-//     }
-// }
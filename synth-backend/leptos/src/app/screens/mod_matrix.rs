@@ -0,0 +1,146 @@
+use crate::app::api::use_send_command;
+use leptos::prelude::*;
+use synth_common::{ChannelState, ModDest, ModSlot, ModSrc, UiToBackend, MAX_MOD_SLOTS};
+
+/// one connection of the mod matrix, its source, destination and bipolar amount.
+#[component]
+fn MatrixRow(
+    channel: u8,
+    index: usize,
+    slot: Signal<Option<ModSlot>>,
+    dests: Memo<Vec<ModDest>>,
+) -> impl IntoView {
+    let send = use_send_command();
+    let set = move |f: &dyn Fn(ModSlot) -> ModSlot| {
+        if let Some(slot) = slot.get_untracked() {
+            send(UiToBackend::SetModSlot {
+                channel,
+                index,
+                slot: f(slot),
+            })
+        }
+    };
+
+    // the options are numbered by their place in `ModSrc::all` and `dests`.
+    let set_src = move |ev| {
+        if let Some(src) = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| ModSrc::all().get(i).copied())
+        {
+            set(&|slot| ModSlot { src, ..slot })
+        }
+    };
+    let set_dest = move |ev| {
+        if let Some(dest) = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| dests.get_untracked().get(i).copied())
+        {
+            set(&|slot| ModSlot { dest, ..slot })
+        }
+    };
+    let set_amount = move |ev| {
+        if let Ok(amount) = event_target_value(&ev).parse::<f32>() {
+            set(&|slot| ModSlot { amount, ..slot })
+        }
+    };
+    let remove = move |_| {
+        if let Some(ModSlot { src, dest, .. }) = slot.get_untracked() {
+            send(UiToBackend::ModDisconnect { channel, src, dest })
+        }
+    };
+    let amount = move || slot.get().map(|slot| slot.amount).unwrap_or_default();
+
+    view! {
+        <div class="flex flex-row gap-2 items-center">
+            <select on:change=set_src>
+                {ModSrc::all()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, src)| {
+                        view! {
+                            <option
+                                value=i.to_string()
+                                selected=move || slot.get().is_some_and(|slot| slot.src == src)
+                            >
+                                {src.to_string()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+            <select on:change=set_dest>
+                {move || {
+                    dests
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, dest)| {
+                            view! {
+                                <option
+                                    value=i.to_string()
+                                    selected=move || {
+                                        slot.get().is_some_and(|slot| slot.dest == dest)
+                                    }
+                                >
+                                    {dest.to_string()}
+                                </option>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </select>
+            <input
+                class="horizontal-slider w-full"
+                type="range"
+                min=-1.0
+                max=1.0
+                step=0.01
+                prop:value=amount
+                on:input=set_amount
+            />
+            <p class="w-12 text-xs">{move || format!("{:+.2}", amount())}</p>
+            <button class="px-2" on:click=remove>
+                "X"
+            </button>
+        </div>
+    }
+}
+
+/// the channel's mod matrix, a row per connection.
+#[component]
+pub fn ModMatrixDisplay(channel: u8, chan: Signal<ChannelState>) -> impl IntoView {
+    let send = use_send_command();
+    let matrix = Memo::new(move |_| chan.get().mod_matrix);
+    let dests = Memo::new(move |_| chan.get().mod_dests());
+    let full = move || matrix.get().slots.len() >= MAX_MOD_SLOTS;
+    // new connections start at no amount, to be rewired and turned up from their row.
+    let add = move |_| {
+        if let Some(slot) = matrix.get_untracked().unused(&dests.get_untracked()) {
+            send(UiToBackend::ModConnect { channel, slot })
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-1">
+            <div class="flex flex-row gap-4 items-center">
+                <p>"Mod Matrix"</p>
+                <p class="text-xs">
+                    {move || format!("{} / {MAX_MOD_SLOTS}", matrix.get().slots.len())}
+                </p>
+                <button class="px-2" on:click=add disabled=full>
+                    "Add"
+                </button>
+            </div>
+            <For each=move || 0..matrix.get().slots.len() key=|index| *index let:index>
+                <MatrixRow
+                    channel
+                    index
+                    slot=Signal::derive(move || matrix.get().slots.get(index).copied())
+                    dests
+                />
+            </For>
+        </div>
+    }
+}
@@ -0,0 +1,72 @@
+use crate::app::api::{list_patches, LoadPatch, SavePatch};
+use leptos::prelude::*;
+
+/// saves the channel's sound under a name, or loads a saved one onto it.
+#[component]
+pub fn PatchPanel(channel: u8) -> impl IntoView {
+    let save = ServerAction::<SavePatch>::new();
+    let load = ServerAction::<LoadPatch>::new();
+    // listed again after every save.
+    let patches = Resource::new(move || save.version().get(), |_| list_patches());
+    let (name, set_name) = signal(String::new());
+
+    let set_patch = move |ev| {
+        let patch = event_target_value(&ev);
+
+        if !patch.is_empty() {
+            set_name.set(patch.clone());
+            load.dispatch(LoadPatch {
+                channel,
+                name: patch,
+            });
+        }
+    };
+    let error = move || {
+        [
+            save.value().get().and_then(Result::err),
+            load.value().get().and_then(Result::err),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|e| e.to_string())
+        .next()
+    };
+
+    view! {
+        <div class="flex flex-row gap-4 items-center">
+            <p>"Patch"</p>
+            <input
+                type="text"
+                placeholder="name"
+                prop:value=name
+                on:input=move |ev| set_name.set(event_target_value(&ev))
+            />
+            <button
+                disabled=move || name.get().trim().is_empty()
+                on:click=move |_| {
+                    save.dispatch(SavePatch {
+                        channel,
+                        name: name.get_untracked(),
+                    });
+                }
+            >
+                "Save"
+            </button>
+            <select on:change=set_patch>
+                <option value="" selected>"Load"</option>
+                <Transition>
+                    {move || {
+                        patches
+                            .get()
+                            .and_then(Result::ok)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|patch| view! { <option value=patch.clone()>{patch}</option> })
+                            .collect_view()
+                    }}
+                </Transition>
+            </select>
+            <p class="text-xs text-ctp-red">{error}</p>
+        </div>
+    }
+}
@@ -1,57 +1,54 @@
-use crate::SynthEffectState;
-use leptos::{prelude::*, task::spawn_local};
+use super::KnobSlider;
+use crate::app::api::use_send_command;
+use leptos::prelude::*;
+use synth_common::{ChannelState, EffectType, UiToBackend};
 
+/// the reverb in one of the channel's effect slots.
 #[component]
-pub fn ReverbDisplay(get_state: impl Fn() -> SynthEffectState) -> impl IntoView {
-    let send_effect_val = move |param: &str, set_to: f32| {
-        let param = param.to_string();
+pub fn ReverbDisplay(channel: u8, slot: u8, chan: Signal<ChannelState>) -> impl IntoView {
+    let send = use_send_command();
+    let effect = move || chan.get().effects[slot as usize];
 
-        spawn_local(async move {
-            let send = move || reqwest::get(format!(
-                "http://127.0.0.1:3000/synth-state/effect/set/reverb/{param}/{set_to}"
-            ));
+    let controls: Vec<_> = EffectType::Reverb
+        .param_names()
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let value = Signal::derive(move || effect().map(|e| e.params[i]).unwrap_or_default());
+            let set_param = move |value| {
+                send(UiToBackend::SetEffectParam {
+                    channel,
+                    slot,
+                    param: i as u8,
+                    value,
+                })
+            };
 
-            for _ in 0..8 {
-                if send().await.is_ok() {
-                    break;
-                }
-            }
-        });
+            view! { <KnobSlider name=*name value steps=1000 on_change=set_param/> }
+        })
+        .collect();
+    let set_power = move |ev| {
+        send(UiToBackend::SetEffectPower {
+            channel,
+            slot,
+            on: event_target_checked(&ev),
+        })
     };
 
-    let slider_change = move |param: String| {
-        move |ev| {
-            // send the server a request to set the value of draw_bar
-            if let Ok(val) = event_target_value(&ev).parse::<usize>() {
-                send_effect_val(&param, val as f32 / 1000.0)
-            }
-        }
-    };
-    let params = get_state().params;
-
-
-    let get_controls: Vec<_> =
-        params.into_iter()
-            .map(|(param, value)| 
-                {
-                    view! {
-                        <div class="flex-row">
-                            <p class="flex-col"> { param.clone() } </p>
-                            <p> { "=>" } </p>
-                            <p class="flex-col"> { value } </p>
-                        </div>
-                        <div class="flex-row">
-                            <input class="horizontal-slider" type="range" min=0 max=1000 step=0.1 prop:value=move || value * 1000.0 on:input=slider_change(param)/>
-                        </div>
-                    }
-                }).collect();
-
-    // TODO: add on/off button 
-    
     view! {
-        <h1> "Reverb" </h1>
-        <div>
-            { get_controls }
+        <div class="flex flex-row gap-4 items-center">
+            <h1> "Reverb" </h1>
+            <label>
+                <input
+                    type="checkbox"
+                    prop:checked=move || effect().is_some_and(|e| e.on)
+                    on:change=set_power
+                />
+                " On"
+            </label>
+        </div>
+        <div class="flex flex-row gap-4">
+            { controls }
         </div>
     }
 }
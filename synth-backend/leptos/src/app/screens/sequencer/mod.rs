@@ -0,0 +1,253 @@
+use crate::app::{api::use_send_command, socket::use_synth};
+use leptos::prelude::*;
+use synth_common::{
+    SongCmd, SongState, UiToBackend, CHANNEL_NAMES, N_CHANNELS, N_SEQUENCES, PATTERN_LENS,
+    SONG_LEN, TEMPOS,
+};
+
+/// a sequence number counted from one, `--` for an empty slot.
+fn seq_name(seq: Option<u8>) -> String {
+    match seq {
+        Some(seq) => (seq + 1).to_string(),
+        None => "--".to_string(),
+    }
+}
+
+/// sends a command that edits or drives the song.
+fn send_song() -> impl Fn(SongCmd) + Copy {
+    let send = use_send_command();
+
+    move |cmd| send(UiToBackend::Song(cmd))
+}
+
+/// the song's tempo and a slider that sets it.
+#[component]
+pub fn TempoSlider(tempo: Signal<f32>) -> impl IntoView {
+    let send = send_song();
+    let set_tempo = move |ev| {
+        if let Ok(tempo) = event_target_value(&ev).parse::<f32>() {
+            send(SongCmd::SetTempo(tempo.round()))
+        }
+    };
+
+    view! {
+        <input
+            class="horizontal-slider"
+            type="range"
+            min=*TEMPOS.start()
+            max=*TEMPOS.end()
+            step=1
+            prop:value=move || tempo.get()
+            on:input=set_tempo
+        />
+        <p>{move || format!("{:.0} bpm", tempo.get())}</p>
+    }
+}
+
+/// a `<select>` of the song's bars, counted from one.
+#[component]
+fn BarPicker(bar: Signal<usize>, on_pick: impl Fn(usize) + 'static) -> impl IntoView {
+    let pick = move |ev| {
+        if let Ok(bar) = event_target_value(&ev).parse() {
+            on_pick(bar)
+        }
+    };
+
+    view! {
+        <select on:change=pick>
+            {(0..SONG_LEN)
+                .map(|i| {
+                    view! {
+                        <option value=i.to_string() selected=move || bar.get() == i>
+                            {i + 1}
+                        </option>
+                    }
+                })
+                .collect_view()}
+        </select>
+    }
+}
+
+/// one channel's row of the arrangement.
+#[component]
+fn Track(
+    channel: usize,
+    song: Signal<SongState>,
+    cursor: RwSignal<(usize, usize)>,
+) -> impl IntoView {
+    let send = send_song();
+    let track = move || song.get().tracks[channel];
+    let slots = (0..SONG_LEN)
+        .map(|bar| {
+            let selected = move || cursor.get() == (channel, bar);
+            let playing = move || {
+                let song = song.get();
+                song.playing && song.position == bar
+            };
+            let in_loop = move || {
+                let song = song.get();
+                (song.loop_start..=song.loop_end).contains(&bar)
+            };
+
+            view! {
+                <button
+                    class="w-full h-full"
+                    class:bg-ctp-blue=selected
+                    class:bg-ctp-green=move || !selected() && playing()
+                    class:bg-ctp-surface0=move || !selected() && !playing() && in_loop()
+                    on:click=move |_| cursor.set((channel, bar))
+                >
+                    {move || seq_name(track().slots[bar])}
+                </button>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class="flex flex-row gap-1 h-full">
+            <p class="w-[4%]">{CHANNEL_NAMES[channel]}</p>
+            <button
+                class="w-[4%]"
+                class:text-ctp-red=move || track().mute
+                on:click=move |_| {
+                    send(SongCmd::SetMute {
+                        channel: channel as u8,
+                        mute: !track().mute,
+                    })
+                }
+            >
+                "M"
+            </button>
+            <button
+                class="w-[4%]"
+                class:text-ctp-green=move || track().solo
+                on:click=move |_| {
+                    send(SongCmd::SetSolo {
+                        channel: channel as u8,
+                        solo: !track().solo,
+                    })
+                }
+            >
+                "S"
+            </button>
+            <div class="flex flex-row gap-1 w-[88%]">{slots}</div>
+        </div>
+    }
+}
+
+/// chains the numbered sequences into a song that the backend plays, like the iced sequencer
+/// screen.
+#[component]
+pub fn MidiSequencer() -> impl IntoView {
+    let synth = use_synth();
+    let send = send_song();
+    let state = Signal::derive(move || synth.state.get().unwrap_or_default());
+    let song = Signal::derive(move || state.get().song);
+    // the slot being edited, as (channel, bar).
+    let cursor = RwSignal::new((0, 0));
+    let slot = move || {
+        let (channel, bar) = cursor.get();
+        song.get().tracks[channel].slots[bar]
+    };
+
+    let set_slot = move |ev| {
+        let (channel, bar) = cursor.get_untracked();
+
+        send(SongCmd::SetSlot {
+            channel: channel as u8,
+            bar: bar as u8,
+            seq: event_target_value(&ev).parse().ok(),
+        });
+    };
+    let set_pattern_len = move |ev| {
+        if let Ok(len) = event_target_value(&ev).parse() {
+            send(SongCmd::SetPatternLen(len));
+        }
+    };
+    let set_loop = move |start: usize, end: usize| {
+        send(SongCmd::SetLoop {
+            start: start as u8,
+            end: end as u8,
+        })
+    };
+
+    view! {
+        <div class="flex flex-col w-full h-full p-2 gap-2">
+            <div class="flex flex-row gap-4 items-center">
+                {move || {
+                    if song.get().playing {
+                        view! { <button on:click=move |_| send(SongCmd::Stop)>"Stop"</button> }
+                            .into_any()
+                    } else {
+                        view! { <button on:click=move |_| send(SongCmd::Play)>"Play"</button> }
+                            .into_any()
+                    }
+                }}
+                <button
+                    class:text-ctp-red=move || song.get().recording
+                    on:click=move |_| send(SongCmd::Record(!song.get_untracked().recording))
+                >
+                    "Rec"
+                </button>
+                <p>{move || format!("bar {} step {}", song.get().position + 1, song.get().step + 1)}</p>
+                <TempoSlider tempo=Signal::derive(move || song.get().tempo)/>
+                <p>"loop"</p>
+                <BarPicker
+                    bar=Signal::derive(move || song.get().loop_start)
+                    on_pick=move |bar| set_loop(bar, song.get_untracked().loop_end.max(bar))
+                />
+                <p>"to"</p>
+                <BarPicker
+                    bar=Signal::derive(move || song.get().loop_end)
+                    on_pick=move |bar| set_loop(song.get_untracked().loop_start.min(bar), bar)
+                />
+                <p>"steps"</p>
+                <select on:change=set_pattern_len>
+                    {PATTERN_LENS
+                        .map(|len| {
+                            view! {
+                                <option value=len.to_string() selected=move || song.get().pattern_len == len>
+                                    {len}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+            <div class="flex flex-col gap-1 h-full">
+                {(0..N_CHANNELS).map(|channel| view! { <Track channel song cursor/> }).collect_view()}
+            </div>
+            <div class="flex flex-row gap-4 items-center">
+                <p>
+                    {move || {
+                        let (channel, bar) = cursor.get();
+                        format!("{} bar {} sequence", CHANNEL_NAMES[channel], bar + 1)
+                    }}
+                </p>
+                <select on:change=set_slot>
+                    <option value="" selected=move || slot().is_none()>"--"</option>
+                    {(0..N_SEQUENCES as u8)
+                        .map(|seq| {
+                            view! {
+                                <option value=seq.to_string() selected=move || slot() == Some(seq)>
+                                    {seq_name(Some(seq))}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+                <button
+                    class="text-ctp-red"
+                    disabled=move || slot().is_none()
+                    on:click=move |_| {
+                        if let Some(seq) = slot() {
+                            send(SongCmd::ClearSequence(seq));
+                        }
+                    }
+                >
+                    "Clear"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,175 @@
+use crate::app::{
+    socket::use_synth,
+    web_midi::{use_web_midi, WebMidiPort},
+};
+use leptos::prelude::*;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThemeChoice {
+    Latte,
+    Frappe,
+    Macchiato,
+    #[default]
+    Mocha,
+}
+
+impl ThemeChoice {
+    pub const ALL: [Self; 4] = [Self::Latte, Self::Frappe, Self::Macchiato, Self::Mocha];
+
+    /// the catppuccin class that switches the page to this flavour.
+    pub fn class(self) -> &'static str {
+        match self {
+            Self::Latte => "latte",
+            Self::Frappe => "frappe",
+            Self::Macchiato => "macchiato",
+            Self::Mocha => "mocha",
+        }
+    }
+}
+
+impl Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Latte => write!(f, "Latte"),
+            Self::Frappe => write!(f, "Frappé"),
+            Self::Macchiato => write!(f, "Macchiato"),
+            Self::Mocha => write!(f, "Mocha"),
+        }
+    }
+}
+
+/// the channels a browser MIDI input can be routed to, `None` leaves messages on the channel they
+/// were sent.
+const ROUTES: [(Option<u8>, &str); 5] = [
+    (None, "as sent"),
+    (Some(0), "A"),
+    (Some(1), "B"),
+    (Some(2), "C"),
+    (Some(3), "D"),
+];
+
+/// the enable switch and channel route of a MIDI controller attached to the browser.
+#[component]
+fn WebMidiInput(name: String) -> impl IntoView {
+    let web_midi = use_web_midi();
+    let label = name.clone();
+    let name = StoredValue::new(name);
+    let port = move || name.with_value(|name| web_midi.port(name));
+    let set_port = move |port| web_midi.set_port(name.get_value(), port);
+
+    let set_enabled = move |ev| {
+        set_port(WebMidiPort {
+            enabled: event_target_checked(&ev),
+            ..port()
+        })
+    };
+    let set_route = move |ev| {
+        let route = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| ROUTES.get(i));
+
+        if let Some((route, _)) = route {
+            set_port(WebMidiPort {
+                route: *route,
+                ..port()
+            })
+        }
+    };
+
+    view! {
+        <div class="flex flex-row gap-4">
+            <p class="w-[240px]">{label}</p>
+            <input type="checkbox" prop:checked=move || port().enabled on:change=set_enabled/>
+            <select on:change=set_route>
+                {ROUTES
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (route, label))| {
+                        view! {
+                            <option value=i.to_string() selected=move || port().route == route>
+                                {label}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+        </div>
+    }
+}
+
+/// the page's theme, set from the settings page.
+pub fn provide_theme() -> RwSignal<ThemeChoice> {
+    let theme = RwSignal::new(ThemeChoice::default());
+    provide_context(theme);
+
+    theme
+}
+
+/// audio and the backend's MIDI are handled by the backend, so this shows the connection, the
+/// controllers attached to the browser and the theme.
+#[component]
+pub fn Settings() -> impl IntoView {
+    let synth = use_synth();
+    let web_midi = use_web_midi();
+    let theme = expect_context::<RwSignal<ThemeChoice>>();
+
+    let set_theme = move |ev| {
+        let choice = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| ThemeChoice::ALL.get(i).copied());
+
+        if let Some(choice) = choice {
+            theme.set(choice);
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-4 p-4 items-start">
+            <h1 class="text-2xl">"Backend"</h1>
+            <div class="flex flex-row gap-4">
+                <p class="w-[120px]">"Status"</p>
+                <p>{move || if synth.connected() { "connected" } else { "connecting..." }}</p>
+            </div>
+            <div class="flex flex-row gap-4">
+                <p class="w-[120px]">"Latency"</p>
+                <p>
+                    {move || match synth.latency.get() {
+                        Some(ms) => format!("{ms:.0} ms round trip"),
+                        None => "--".to_string(),
+                    }}
+                </p>
+            </div>
+            <h1 class="text-2xl">"Browser MIDI Inputs"</h1>
+            {move || match web_midi.error.get() {
+                Some(e) => view! { <p>{e}</p> }.into_any(),
+                None if web_midi.inputs.read().is_empty() => {
+                    view! { <p>"no MIDI inputs connected to this browser"</p> }.into_any()
+                }
+                None => web_midi
+                    .inputs
+                    .get()
+                    .into_iter()
+                    .map(|name| view! { <WebMidiInput name/> })
+                    .collect_view()
+                    .into_any(),
+            }}
+            <h1 class="text-2xl">"Theme"</h1>
+            <select on:change=set_theme>
+                {ThemeChoice::ALL
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, choice)| {
+                        view! {
+                            <option value=i.to_string() selected=move || theme.get() == choice>
+                                {choice.to_string()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+        </div>
+    }
+}
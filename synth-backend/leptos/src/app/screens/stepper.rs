@@ -0,0 +1,180 @@
+use super::sequencer::TempoSlider;
+use crate::app::{api::use_send_command, socket::use_synth};
+use leptos::prelude::*;
+use synth_common::{
+    note_name, SongCmd, SongState, UiToBackend, CHANNEL_NAMES, N_CHANNELS, N_SEQUENCES,
+};
+
+/// one channel's steps in the bar that is playing, lit where a note starts.
+#[component]
+fn StepRow(channel: usize, song: Signal<SongState>, held: Signal<u32>) -> impl IntoView {
+    let steps = move || {
+        let song = song.get();
+
+        (0..song.pattern_len)
+            .map(|step| {
+                let note = song.has_note(channel, step);
+                let now = song.playing && song.step == step;
+
+                view! {
+                    <div
+                        class="w-full h-full"
+                        class:bg-ctp-green=now
+                        class:bg-ctp-blue=!now && note
+                        class:bg-ctp-surface0=!now && !note
+                    ></div>
+                }
+            })
+            .collect_view()
+    };
+    let seq = move || match song.get().current(channel) {
+        Some(seq) => format!("seq {}", seq + 1),
+        None => "--".to_string(),
+    };
+
+    view! {
+        <div class="flex flex-row gap-1 items-center h-full">
+            <p class="w-[4%]">{CHANNEL_NAMES[channel]}</p>
+            <p class="w-[8%]" class:text-ctp-red=move || !song.get().audible(channel)>{seq}</p>
+            <p class="w-[4%]" class:text-ctp-green=move || held.get() > 0>"●"</p>
+            <div class="flex flex-row gap-1 w-[84%] h-full">{steps}</div>
+        </div>
+    }
+}
+
+/// the steps of the sequence being stepped in, clicking one adds or takes out `note` there.
+#[component]
+fn StepGrid(song: Signal<SongState>, seq: RwSignal<u8>, note: RwSignal<u8>) -> impl IntoView {
+    let send = use_send_command();
+    let steps = move || {
+        let song = song.get();
+        let (seq, note) = (seq.get(), note.get());
+
+        (0..song.pattern_len)
+            .map(|step| {
+                let notes = song
+                    .step_notes(seq)
+                    .filter(|seq_note| seq_note.step as usize == step);
+                let (has_note, has_other) = notes.fold((false, false), |(has, other), seq_note| {
+                    (has || seq_note.note == note, other || seq_note.note != note)
+                });
+                let now = song.playing && song.step == step;
+                let toggle = move |_| {
+                    send(UiToBackend::Song(SongCmd::ToggleStep {
+                        seq,
+                        step: step as u8,
+                        note,
+                    }))
+                };
+
+                view! {
+                    <button
+                        class="w-full h-full text-xs"
+                        class:bg-ctp-blue=has_note
+                        class:bg-ctp-green=!has_note && now
+                        class:bg-ctp-surface1=!has_note && !now && has_other
+                        class:bg-ctp-surface0=!has_note && !now && !has_other
+                        on:click=toggle
+                    >
+                        {step + 1}
+                    </button>
+                }
+            })
+            .collect_view()
+    };
+
+    view! { <div class="flex flex-row gap-1 h-full">{steps}</div> }
+}
+
+/// the song's transport, the steps each channel plays in the bar under the playhead, and the
+/// numbered sequence being stepped in, the ones the sequencer screen chains together. like the
+/// iced stepper screen.
+#[component]
+pub fn MidiStepper() -> impl IntoView {
+    let synth = use_synth();
+    let send = use_send_command();
+    let state = Signal::derive(move || synth.state.get().unwrap_or_default());
+    let song = Signal::derive(move || state.get().song);
+    let send = move |cmd| send(UiToBackend::Song(cmd));
+    // the sequence the stepper edits and the pitch it adds.
+    let seq = RwSignal::new(0u8);
+    let note = RwSignal::new(60u8);
+    let pick_seq = move |ev| {
+        if let Ok(picked) = event_target_value(&ev).parse::<u8>() {
+            seq.set(picked);
+        }
+    };
+    let pick_note = move |ev| {
+        if let Ok(picked) = event_target_value(&ev).parse::<u8>() {
+            note.set(picked);
+        }
+    };
+
+    view! {
+        <div class="flex flex-col w-full h-full p-2 gap-2">
+            <div class="flex flex-row gap-4 items-center">
+                {move || {
+                    if song.get().playing {
+                        view! { <button on:click=move |_| send(SongCmd::Stop)>"Stop"</button> }
+                            .into_any()
+                    } else {
+                        view! { <button on:click=move |_| send(SongCmd::Play)>"Play"</button> }
+                            .into_any()
+                    }
+                }}
+                <button
+                    class:text-ctp-red=move || song.get().recording
+                    on:click=move |_| send(SongCmd::Record(!song.get_untracked().recording))
+                >
+                    "Rec"
+                </button>
+                <p>{move || format!("bar {} step {}", song.get().position + 1, song.get().step + 1)}</p>
+                <TempoSlider tempo=Signal::derive(move || song.get().tempo)/>
+            </div>
+            <div class="flex flex-col gap-1 h-full">
+                {(0..N_CHANNELS)
+                    .map(|channel| {
+                        let held = Signal::derive(move || state.get().channels[channel].held_notes);
+
+                        view! { <StepRow channel song held/> }
+                    })
+                    .collect_view()}
+            </div>
+            <div class="flex flex-row gap-4 items-center">
+                <p>"sequence"</p>
+                <select on:change=pick_seq>
+                    {(0..N_SEQUENCES as u8)
+                        .map(|i| {
+                            view! {
+                                <option value=i.to_string() selected=move || seq.get() == i>
+                                    {i + 1}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+                <p>"note"</p>
+                <select on:change=pick_note>
+                    {(0..128u8)
+                        .map(|i| {
+                            view! {
+                                <option value=i.to_string() selected=move || note.get() == i>
+                                    {note_name(i)}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+                <button
+                    class="text-ctp-red"
+                    on:click=move |_| send(SongCmd::ClearSequence(seq.get_untracked()))
+                >
+                    "Clear"
+                </button>
+            </div>
+            <div class="h-full">
+                <StepGrid song seq note/>
+            </div>
+        </div>
+    }
+}
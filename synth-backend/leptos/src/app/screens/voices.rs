@@ -0,0 +1,117 @@
+use crate::app::api::use_send_command;
+use leptos::prelude::*;
+use synth_common::{
+    ChannelState, Steal, UiToBackend, VoiceMode, VoiceSettings, MAX_GLIDE, MAX_POLYPHONY,
+};
+
+/// the channel's polyphony, voice stealing, mono modes and glide.
+#[component]
+pub fn VoiceDisplay(channel: u8, chan: Signal<ChannelState>) -> impl IntoView {
+    let send = use_send_command();
+    let settings = Memo::new(move |_| chan.get().voices);
+    let set = move |settings: VoiceSettings| send(UiToBackend::SetVoices { channel, settings });
+
+    // the options are numbered by their place in `ALL`.
+    let set_mode = move |ev| {
+        if let Some(mode) = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| VoiceMode::ALL.get(i).copied())
+        {
+            set(VoiceSettings {
+                mode,
+                ..settings.get_untracked()
+            })
+        }
+    };
+    let set_steal = move |ev| {
+        if let Some(steal) = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| Steal::ALL.get(i).copied())
+        {
+            set(VoiceSettings {
+                steal,
+                ..settings.get_untracked()
+            })
+        }
+    };
+    let set_polyphony = move |ev| {
+        if let Ok(polyphony) = event_target_value(&ev).parse::<u8>() {
+            set(VoiceSettings {
+                polyphony,
+                ..settings.get_untracked()
+            })
+        }
+    };
+    let set_glide = move |ev| {
+        if let Ok(glide) = event_target_value(&ev).parse::<f32>() {
+            set(VoiceSettings {
+                glide,
+                ..settings.get_untracked()
+            })
+        }
+    };
+    let poly = move || settings.get().mode == VoiceMode::Poly;
+
+    view! {
+        <div class="flex flex-row gap-4 items-center">
+            <p>"Voices"</p>
+            <select on:change=set_mode>
+                {VoiceMode::ALL
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, mode)| {
+                        view! {
+                            <option value=i.to_string() selected=move || settings.get().mode == mode>
+                                {mode.to_string()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+            <p>"Steal"</p>
+            <select on:change=set_steal disabled=move || !poly()>
+                {Steal::ALL
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, steal)| {
+                        view! {
+                            <option value=i.to_string() selected=move || settings.get().steal == steal>
+                                {steal.to_string()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+            <div class="flex flex-col items-center" class:hidden=move || !poly()>
+                <p>"Polyphony"</p>
+                <input
+                    class="horizontal-slider"
+                    type="range"
+                    min=1
+                    max=MAX_POLYPHONY
+                    step=1
+                    prop:value=move || settings.get().polyphony
+                    on:input=set_polyphony
+                />
+                <p>{move || settings.get().polyphony}</p>
+            </div>
+            // glide only slides between the notes of the mono modes.
+            <div class="flex flex-col items-center" class:hidden=poly>
+                <p>"Glide"</p>
+                <input
+                    class="horizontal-slider"
+                    type="range"
+                    min=0
+                    max=MAX_GLIDE
+                    step=0.01
+                    prop:value=move || settings.get().glide
+                    on:input=set_glide
+                />
+                <p>{move || format!("{:.2} s", settings.get().glide)}</p>
+            </div>
+            <p>{move || format!("{} sounding", chan.get().active_voices)}</p>
+        </div>
+    }
+}
@@ -0,0 +1,157 @@
+use crate::app::{
+    api::{import_wave_table, use_send_command},
+    socket::use_synth,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use leptos::prelude::*;
+use synth_common::{ChannelState, OscState, TableInfo, UiToBackend, WaveTableCmd};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::HtmlInputElement;
+
+/// the table one oscillator plays and where in it.
+#[component]
+fn OscTablePicker(
+    channel: u8,
+    osc: u8,
+    state: Signal<OscState>,
+    tables: Signal<Vec<TableInfo>>,
+) -> impl IntoView {
+    let send = use_send_command();
+    let wave_table = move |cmd| send(UiToBackend::WaveTable { channel, cmd });
+
+    // the options are numbered by their place in the list of tables.
+    let set_table = move |ev| {
+        if let Some(table) = event_target_value(&ev)
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| tables.get_untracked().get(i).map(|table| table.choice))
+        {
+            wave_table(WaveTableCmd::SetOscTable { osc, table })
+        }
+    };
+    let set_position = move |ev| {
+        if let Ok(position) = event_target_value(&ev).parse::<f32>() {
+            wave_table(WaveTableCmd::SetOscPosition { osc, position })
+        }
+    };
+    // a table with one frame has nowhere to move.
+    let frames = move || {
+        tables
+            .get()
+            .iter()
+            .find(|table| table.choice == state.get().table)
+            .map_or(1, |table| table.frames)
+    };
+
+    view! {
+        <div class="flex flex-row gap-4 items-center">
+            <p class="w-16">{format!("Osc {}", osc + 1)}</p>
+            <select on:change=set_table>
+                {move || {
+                    tables
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, table)| {
+                            let choice = table.choice;
+
+                            view! {
+                                <option
+                                    value=i.to_string()
+                                    selected=move || state.get().table == choice
+                                >
+                                    {format!("{} ({})", table.name, table.frames)}
+                                </option>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </select>
+            <p>"Position"</p>
+            <input
+                class="horizontal-slider w-full"
+                type="range"
+                min=0.0
+                max=1.0
+                step=0.01
+                disabled=move || frames() < 2
+                prop:value=move || state.get().position
+                on:input=set_position
+            />
+            <p class="w-16 text-xs">{move || format!("{:.2}", state.get().position)}</p>
+        </div>
+    }
+}
+
+/// picks the wavetable each of the channel's oscillators plays, and imports WAV files as new
+/// ones.
+#[component]
+pub fn WaveTableDisplay(channel: u8, chan: Signal<ChannelState>) -> impl IntoView {
+    let synth = use_synth();
+    let tables = Signal::derive(move || {
+        synth
+            .state
+            .get()
+            .map(|state| state.wave_tables)
+            .unwrap_or_default()
+    });
+    let oscs = Memo::new(move |_| {
+        chan.get()
+            .wave_table
+            .map(|wt| wt.oscs.len())
+            .unwrap_or_default()
+    });
+    let (status, set_status) = signal(None::<String>);
+
+    // the file is read in the browser and sent to the backend, which keeps a copy.
+    let import = move |ev| {
+        let input = event_target::<HtmlInputElement>(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+
+        spawn_local(async move {
+            let name = file.name();
+            let status = match JsFuture::from(file.array_buffer()).await {
+                Ok(buf) => {
+                    let wav = BASE64_STANDARD.encode(js_sys::Uint8Array::new(&buf).to_vec());
+
+                    match import_wave_table(name.clone(), wav).await {
+                        Ok(_) => format!("imported {name}"),
+                        Err(e) => format!("{name} was not imported. {e}"),
+                    }
+                }
+                Err(e) => format!("{name} could not be read. {e:?}"),
+            };
+
+            set_status.set(Some(status));
+        });
+    };
+
+    view! {
+        <div class="flex flex-col gap-2">
+            <div class="flex flex-row gap-4 items-center">
+                <h1>"Wave Table"</h1>
+                <label class="flex flex-row gap-2 items-center">
+                    "Import"
+                    <input type="file" accept=".wav,audio/wav" on:change=import/>
+                </label>
+                <p class="text-xs">{move || status.get()}</p>
+            </div>
+            {move || {
+                (0..oscs.get())
+                    .map(|osc| {
+                        let state = Signal::derive(move || {
+                            chan.get()
+                                .wave_table
+                                .and_then(|wt| wt.oscs.get(osc).copied())
+                                .unwrap_or_default()
+                        });
+
+                        view! { <OscTablePicker channel osc=osc as u8 state tables/> }
+                    })
+                    .collect_view()
+            }}
+        </div>
+    }
+}
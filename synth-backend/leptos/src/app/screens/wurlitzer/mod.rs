@@ -1,36 +1,18 @@
-use leptos::{prelude::*, task::spawn_local};
-use stepper_synth_backend::pygame_coms::{Knob, SynthEngineState};
+use super::{knob, send_knob, KnobSlider};
+use leptos::prelude::*;
+use synth_common::ChannelState;
 
 #[component]
-pub fn WurlitzerDisplay(get_state: impl Fn() -> SynthEngineState) -> impl IntoView {
-    let get_param = move |knob| get_state().knob_params.get(&knob).unwrap().clone();
-    let knob = signal(get_param(Knob::One));
-
-    let send_trem = move |set_to: f32| {
-        spawn_local(async move {
-            let send = || {
-                reqwest::get(format!(
-                    "http://127.0.0.1:3000/synth-state/engine/set/wurlitzer/trem/{set_to}"
-                ))
-            };
-
-            for _ in 0..8 {
-                if send().await.is_ok() {
-                    break;
-                }
-            }
-        });
-    };
-
+pub fn WurlitzerDisplay(channel: u8, chan: Signal<ChannelState>) -> impl IntoView {
     view! {
         <h1> "Wurlitzer" </h1>
-        <div class="flex-row">
-            <input class="vert-slider" type="range" min=0 max=1000 step=1 prop:value=move || knob.0.get() * 1000.0 on:input=move |ev| {
-                    // send the server a request to set the value of draw_bar
-                    if let Ok(val) = event_target_value(&ev).parse::<usize>() {
-            send_trem(val as f32 / 1000.0)
-                    }
-                }
+        <div class="flex flex-row">
+            <KnobSlider
+                name="Tremolo"
+                value=knob(chan, 0)
+                steps=1000
+                on_change=send_knob(channel, 0)
+                vertical=true
             />
         </div>
     }
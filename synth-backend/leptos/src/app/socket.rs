@@ -0,0 +1,76 @@
+use codee::string::JsonSerdeCodec;
+use leptos::prelude::*;
+use leptos_use::{core::ConnectionReadyState, use_websocket, UseWebSocketReturn};
+use std::time::Duration;
+use synth_common::{BackendToUi, SynthState, UiToBackend};
+
+/// how often the connection's round trip is measured.
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// the page's connection to the backend's `/ws` endpoint, shared by every screen through the
+/// context.
+#[derive(Clone, Copy)]
+pub struct SynthSocket {
+    /// the last state the backend sent, `None` until the first one arrives.
+    pub state: Signal<Option<SynthState>>,
+    pub ready_state: Signal<ConnectionReadyState>,
+    /// the round trip to the backend in milliseconds, `None` until the first pong.
+    pub latency: Signal<Option<f64>>,
+    send: Callback<UiToBackend>,
+}
+
+impl SynthSocket {
+    pub fn send(&self, cmd: UiToBackend) {
+        self.send.run(cmd);
+    }
+
+    pub fn connected(&self) -> bool {
+        self.ready_state.get() == ConnectionReadyState::Open
+    }
+}
+
+/// opens the connection to the backend and makes it available with `use_synth`.
+pub fn provide_synth_socket() {
+    let UseWebSocketReturn {
+        message,
+        send,
+        ready_state,
+        ..
+    } = use_websocket::<UiToBackend, BackendToUi, JsonSerdeCodec>("/ws");
+
+    let state = RwSignal::new(None);
+    let latency = RwSignal::new(None);
+
+    Effect::new(move |_| match message.get() {
+        Some(BackendToUi::State(new_state)) => state.set(Some(*new_state)),
+        Some(BackendToUi::Pong(stamp)) => latency.set(Some(js_sys::Date::now() - stamp)),
+        _ => {}
+    });
+
+    let send = Callback::new(move |cmd: UiToBackend| send(&cmd));
+
+    // effects only run in the browser, so the pings never start on the server.
+    Effect::new(move |_| {
+        let ping = move || {
+            if ready_state.get_untracked() == ConnectionReadyState::Open {
+                send.run(UiToBackend::Ping(js_sys::Date::now()));
+            }
+        };
+
+        match set_interval_with_handle(ping, PING_INTERVAL) {
+            Ok(handle) => on_cleanup(move || handle.clear()),
+            Err(e) => leptos::logging::error!("could not start pinging the backend. {e:?}"),
+        }
+    });
+
+    provide_context(SynthSocket {
+        state: state.into(),
+        ready_state,
+        latency: latency.into(),
+        send,
+    });
+}
+
+pub fn use_synth() -> SynthSocket {
+    expect_context::<SynthSocket>()
+}
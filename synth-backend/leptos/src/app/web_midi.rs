@@ -0,0 +1,153 @@
+use crate::app::socket::{use_synth, SynthSocket};
+use leptos::prelude::*;
+use std::collections::HashMap;
+use synth_common::{MidiToBackend, UiToBackend};
+use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{MidiAccess, MidiInput, MidiMessageEvent};
+
+/// how a browser MIDI input is forwarded to the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebMidiPort {
+    pub enabled: bool,
+    /// the channel messages are moved to, `None` leaves them on the channel they were sent.
+    pub route: Option<u8>,
+}
+
+impl Default for WebMidiPort {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            route: None,
+        }
+    }
+}
+
+/// the MIDI controllers attached to the browser, forwarded to the backend over the websocket.
+#[derive(Clone, Copy)]
+pub struct WebMidi {
+    /// the names of the connected inputs.
+    pub inputs: RwSignal<Vec<String>>,
+    pub ports: RwSignal<HashMap<String, WebMidiPort>>,
+    /// why the browser's MIDI can't be used, if it can't.
+    pub error: RwSignal<Option<String>>,
+}
+
+impl WebMidi {
+    pub fn port(&self, name: &str) -> WebMidiPort {
+        self.ports.read().get(name).copied().unwrap_or_default()
+    }
+
+    pub fn set_port(&self, name: String, port: WebMidiPort) {
+        self.ports.update(|ports| {
+            ports.insert(name, port);
+        });
+    }
+}
+
+/// sends one message from a browser input to the backend, if the input is enabled.
+fn forward(synth: SynthSocket, web_midi: WebMidi, name: &str, bytes: &[u8]) {
+    let port = web_midi
+        .ports
+        .read_untracked()
+        .get(name)
+        .copied()
+        .unwrap_or_default();
+
+    if !port.enabled {
+        return;
+    }
+
+    if let Some(msg) = MidiToBackend::from_bytes(bytes) {
+        let msg = match port.route {
+            Some(channel) => msg.on_channel(channel),
+            None => msg,
+        };
+
+        synth.send(UiToBackend::Midi(msg));
+    }
+}
+
+/// points every input's `onmidimessage` at the backend and lists them.
+fn connect_inputs(
+    access: &MidiAccess,
+    synth: SynthSocket,
+    web_midi: WebMidi,
+    handlers: StoredValue<Vec<Closure<dyn FnMut(MidiMessageEvent)>>, LocalStorage>,
+) {
+    let mut names = Vec::new();
+    let mut new_handlers = Vec::new();
+
+    for input in access.inputs().values().into_iter().flatten() {
+        let input: MidiInput = input.unchecked_into();
+        let name = input.name().unwrap_or_else(|| input.id());
+
+        let handler = Closure::<dyn FnMut(MidiMessageEvent)>::new({
+            let name = name.clone();
+
+            move |ev: MidiMessageEvent| {
+                if let Ok(bytes) = ev.data() {
+                    forward(synth, web_midi, &name, &bytes);
+                }
+            }
+        });
+        input.set_onmidimessage(Some(handler.as_ref().unchecked_ref()));
+
+        names.push(name);
+        new_handlers.push(handler);
+    }
+
+    // the old handlers are only dropped once every input points at a new one.
+    handlers.set_value(new_handlers);
+    web_midi.inputs.set(names);
+}
+
+/// asks the browser for its MIDI inputs and keeps forwarding them, as they come and go, for as
+/// long as the app is open.
+pub fn provide_web_midi() {
+    let web_midi = WebMidi {
+        inputs: RwSignal::new(Vec::new()),
+        ports: RwSignal::new(HashMap::new()),
+        error: RwSignal::new(None),
+    };
+    provide_context(web_midi);
+
+    let synth = use_synth();
+    let handlers = StoredValue::new_local(Vec::new());
+    let on_state_change = StoredValue::new_local(None::<Closure<dyn FnMut()>>);
+
+    // effects only run in the browser.
+    Effect::new(move |_| {
+        spawn_local(async move {
+            let request = window().navigator().request_midi_access();
+            let access = match request {
+                Ok(promise) => JsFuture::from(promise).await,
+                Err(e) => Err(e),
+            };
+
+            let access: MidiAccess = match access {
+                Ok(access) => access.unchecked_into(),
+                Err(e) => {
+                    leptos::logging::warn!("web MIDI is not available. {e:?}");
+                    web_midi
+                        .error
+                        .set(Some("this browser does not allow MIDI access".into()));
+                    return;
+                }
+            };
+
+            connect_inputs(&access, synth, web_midi, handlers);
+
+            let rescan = Closure::<dyn FnMut()>::new({
+                let access = access.clone();
+                move || connect_inputs(&access, synth, web_midi, handlers)
+            });
+            access.set_onstatechange(Some(rescan.as_ref().unchecked_ref()));
+            on_state_change.set_value(Some(rescan));
+        });
+    });
+}
+
+pub fn use_web_midi() -> WebMidi {
+    expect_context::<WebMidi>()
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use stepper_synth_backend::{effects::EffectType, HashMap};
 
 pub mod app;
+#[cfg(feature = "ssr")]
+pub mod synth_state;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SynthEffectState {
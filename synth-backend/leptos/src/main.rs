@@ -1,12 +1,5 @@
 #![feature(impl_trait_in_bindings)]
 
-#[cfg(feature = "ssr")]
-mod consts {
-    // pub const API_DIR: &str = "/tmp/synth/";
-    // pub const SEQ_SOCKET: &str = "sequencer.sock";
-    pub const API_SOCKET: &str = "/tmp/synth/backend.sock";
-}
-
 #[cfg(feature = "ssr")]
 mod synth_helpers;
 
@@ -14,7 +7,7 @@ mod synth_helpers;
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     use actix_files::Files;
-    use actix_web::middleware::Logger;
+    use actix_web::middleware::from_fn;
     use actix_web::*;
     use leptos::config::get_configuration;
     use leptos::prelude::*;
@@ -22,119 +15,250 @@ async fn main() -> std::io::Result<()> {
     use leptos_meta::MetaTags;
     use log::*;
     use std::{
-        fs::create_dir,
-        path::PathBuf,
-        sync::{atomic::AtomicBool, Arc, Mutex},
-        usize,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread, usize,
     };
     use stepper_synth_backend::SampleGen;
-    use stepper_synth_backend::{sequencer::SequencerIntake, synth_engines::Synth, SAMPLE_RATE};
+    use stepper_synth_backend::SAMPLE_RATE;
     use synth_backend::app::*;
-    use synth_helpers::run_midi;
+    use synth_backend::synth_state::{panic, synth_ws};
+    use synth_common::{
+        hash_password, load_auth, load_song, request_logger, require_auth, save_song, shutdown,
+        socket_path, wave_table_dir, Shutdown, SocketLock, FADE_OUT, N_CHANNELS,
+    };
+    use synth_engine::{all_notes_off, apply_tables, new_synth};
+    use synth_helpers::{run_midi, run_modulation};
+    use synth_lib::{
+        mixer::{mono, write_frame, Mixer},
+        notes::HeldNotes,
+        scope::ScopeRing,
+        voices::Voices,
+        wavetable::WaveTables,
+    };
     use tinyaudio::{run_output_device, OutputDeviceParameters};
-    use tokio::task::spawn;
 
+    // `hash-password PASSWORD` prints the `password_hash` for a user in the auth config.
+    if let [cmd, password] = &std::env::args().skip(1).collect::<Vec<_>>()[..] {
+        if cmd == "hash-password" {
+            println!("{}", hash_password(password));
+            return Ok(());
+        }
+    }
+
+    let auth = web::Data::new(load_auth()?);
     let conf = get_configuration(None).unwrap();
     let addr = conf.leptos_options.site_addr;
-    let socket_parent = PathBuf::from(consts::API_SOCKET);
-    let socket_parent = socket_parent.parent().expect("this exists");
+    // held until the server stops, dropping it removes the socket.
+    let socket = SocketLock::acquire(socket_path()).inspect_err(|e| {
+        error!("can't use the API socket. {e}");
+    })?;
+    info!("listening on {}", socket.socket().display());
+
+    let synth = web::Data::new(std::sync::Mutex::new(new_synth()));
+    // per channel volume and mute, set by remote UIs.
+    let mixer = web::Data::new(Mutex::new(Mixer::default()));
+    // the notes held on each channel, shown by the UIs.
+    let held = web::Data::new(Mutex::new(HeldNotes::default()));
+    // which notes are sounding, within each channel's polyphony, and the song.
+    let mut voices = Voices::default();
 
-    if !socket_parent.exists() {
-        _ = create_dir(socket_parent);
+    match load_song() {
+        Ok(Some(song)) => *voices.song_mut() = song,
+        Ok(None) => {}
+        Err(e) => warn!("the saved song was not loaded. {e}"),
     }
 
-    // TODO: enable midi sequencer
-    let seq = web::Data::new(Mutex::new(SequencerIntake::new()));
-    let synth = web::Data::new(std::sync::Mutex::new(Synth::new()));
+    let voices = web::Data::new(Mutex::new(voices));
+    // the built in and imported wavetables, and which ones the oscillators play.
+    let (tables, problems) = WaveTables::load(&wave_table_dir());
+
+    for (path, e) in problems {
+        warn!("the wavetable {} was not loaded. {e}", path.display());
+    }
+
+    // engines that start as wavetable synths play the picked tables too.
+    for channel in 0..N_CHANNELS {
+        apply_tables(
+            &mut synth.lock().unwrap().get_channel_engine(channel).engine,
+            &tables,
+            channel,
+        );
+    }
+
+    let tables = web::Data::new(Mutex::new(tables));
+    // the decimated output, streamed to UIs that show a scope.
+    let scope = Arc::new(ScopeRing::default());
     // synth.lock().unwrap().set_engine(SynthEngineType::SubSynth);
     let exit: Arc<AtomicBool> = Arc::new(false.into());
+    // set by the `/shutdown` route.
+    let shutdown_request = web::Data::new(Shutdown::default());
+
+    let midi = {
+        let synth = synth.clone();
+        let held = held.clone();
+        let voices = voices.clone();
+        let exit = exit.clone();
 
-    let _jh = {
-        let seq = seq.clone();
+        thread::spawn(move || run_midi(synth, held, voices, exit))
+    };
+    let modulation = {
         let synth = synth.clone();
+        let mixer = mixer.clone();
+        let voices = voices.clone();
+        let tables = tables.clone();
+        let exit = exit.clone();
 
-        spawn(async move { run_midi(seq, synth, exit).await });
+        thread::spawn(move || run_modulation(synth, mixer, voices, tables, exit))
     };
     let params = OutputDeviceParameters {
-        channels_count: 1,
+        channels_count: 2,
         sample_rate: SAMPLE_RATE as usize,
         // channel_sample_count: 2048,
         channel_sample_count: 1024,
     };
     let device = run_output_device(params, {
         let synth = synth.clone();
+        let mixer = mixer.clone();
+        let mut scope = scope.writer();
 
         move |data| {
             for samples in data.chunks_mut(params.channels_count) {
-                let value = synth.lock().unwrap().get_sample();
-
-                for sample in samples {
-                    *sample = value;
-                }
+                // NOTE: always lock the synth before the mixer.
+                let mut synth = synth.lock().unwrap();
+                let frame = mixer
+                    .lock()
+                    .unwrap()
+                    .mix(synth.channels.iter_mut().map(|chan| chan.get_sample()));
+                scope.push(mono(frame));
+                write_frame(frame, samples);
             }
         }
     });
 
-    if let Err(e) = device {
+    if let Err(e) = &device {
         error!("starting audio playback caused error: {e}");
     }
 
-    HttpServer::new(move || {
-        // Generate the list of routes in your Leptos App
-        let routes = generate_route_list(App);
-        let leptos_options = &conf.leptos_options;
-        let site_root = leptos_options.site_root.clone().to_string();
-
-        App::new()
-            .wrap(Logger::default())
-            // serve JS/WASM/CSS from `pkg`
-            .service(Files::new("/pkg", format!("{site_root}/pkg")))
-            // serve other assets from the `assets` directory
-            .service(Files::new("/assets", &site_root))
-            // serve the favicon from /favicon.ico
-            .service(favicon)
-            // .service(tailwind_config)
-            // .service(synth_state)
-            // .service(synth_engine_state)
-            // .service(synth_effect_state)
-            // .service(set_synth_engine)
-            // .service(set_organ_draw_bars)
-            // .service(set_wurli_trem)
-            // .service(set_reverb_params)
-            // .service(set_effect)
-            // .service(set_effect_power)
-            // .route("/synth-state", web::get().to(synth_state))
-            .leptos_routes(routes, {
-                let leptos_options = leptos_options.clone();
-
-                move || {
-                    view! {
-                        <!DOCTYPE html>
-                        <html lang="en">
-                            <head>
-                                <meta charset="utf-8"/>
-                                <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                                <AutoReload options=leptos_options.clone()/>
-                                <HydrationScripts options=leptos_options.clone()/>
-                                <MetaTags/>
-                            </head>
-                            <body class="bg-ctp-base">
-                                <App/>
-                            </body>
-                        </html>
+    let server = HttpServer::new({
+        // the originals are kept to silence the synth once the server stops.
+        let synth = synth.clone();
+        let mixer = mixer.clone();
+        let held = held.clone();
+        let voices = voices.clone();
+        let shutdown_request = shutdown_request.clone();
+
+        move || {
+            // Generate the list of routes in your Leptos App
+            let routes = generate_route_list(App);
+            let leptos_options = &conf.leptos_options;
+            let site_root = leptos_options.site_root.clone().to_string();
+
+            App::new()
+                .wrap(from_fn(require_auth))
+                .wrap(request_logger())
+                // serve JS/WASM/CSS from `pkg`
+                .service(Files::new("/pkg", format!("{site_root}/pkg")))
+                // serve other assets from the `assets` directory
+                .service(Files::new("/assets", &site_root))
+                // serve the favicon from /favicon.ico
+                .service(favicon)
+                .service(synth_ws)
+                .service(panic)
+                .service(shutdown)
+                // .service(tailwind_config)
+                // .service(synth_state)
+                // .service(synth_engine_state)
+                // .service(synth_effect_state)
+                // .service(set_synth_engine)
+                // .service(set_organ_draw_bars)
+                // .service(set_wurli_trem)
+                // .service(set_reverb_params)
+                // .service(set_effect)
+                // .service(set_effect_power)
+                // .route("/synth-state", web::get().to(synth_state))
+                .leptos_routes(routes, {
+                    let leptos_options = leptos_options.clone();
+
+                    move || {
+                        view! {
+                            <!DOCTYPE html>
+                            <html lang="en">
+                                <head>
+                                    <meta charset="utf-8"/>
+                                    <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                                    <AutoReload options=leptos_options.clone()/>
+                                    <HydrationScripts options=leptos_options.clone()/>
+                                    <MetaTags/>
+                                </head>
+                                <body class="bg-ctp-base">
+                                    <App/>
+                                </body>
+                            </html>
+                        }
                     }
-                }
-            })
-            .app_data(web::Data::new(leptos_options.to_owned()))
-            .app_data(synth.clone())
-            .app_data(seq.clone())
-        //.wrap(middleware::Compress::default())
+                })
+                .app_data(web::Data::new(leptos_options.to_owned()))
+                .app_data(synth.clone())
+                .app_data(mixer.clone())
+                .app_data(held.clone())
+                .app_data(voices.clone())
+                .app_data(tables.clone())
+                .app_data(web::Data::from(scope.clone()))
+                .app_data(auth.clone())
+                .app_data(shutdown_request.clone())
+            //.wrap(middleware::Compress::default())
+        }
     })
-        .workers(6)
-    .bind(&addr)?
-    .bind_uds(&consts::API_SOCKET)?
-    .run()
-    .await
+    .workers(6)
+    .bind(&addr)?;
+    // the unix socket is trusted, so it is bound private to this user.
+    let server = socket.bind_private(|path| server.bind_uds(path))?;
+
+    // actix stops the server on SIGINT and SIGTERM, `/shutdown` stops it the same way.
+    let server = server.run();
+    let handle = server.handle();
+    actix_web::rt::spawn(async move {
+        shutdown_request.requested().await;
+        handle.stop(true).await;
+    });
+
+    let res = server.await;
+    info!("shutting down");
+
+    // no new notes once the MIDI ports are closed.
+    exit.store(true, Ordering::Relaxed);
+    match midi.join() {
+        Ok(Err(e)) => error!("MIDI input stopped with an error. {e}"),
+        Err(_) => error!("the MIDI input thread panicked"),
+        Ok(Ok(())) => {}
+    }
+
+    if modulation.join().is_err() {
+        error!("the modulation thread panicked");
+    }
+
+    mixer
+        .lock()
+        .unwrap()
+        .fade_out((SAMPLE_RATE as f32 * FADE_OUT.as_secs_f32()) as usize);
+    actix_web::rt::time::sleep(FADE_OUT).await;
+    all_notes_off(
+        &mut synth.lock().unwrap(),
+        &mut held.lock().unwrap(),
+        &mut voices.lock().unwrap(),
+    );
+    drop(device);
+
+    // notes recorded since the song was last edited.
+    if let Err(e) = save_song(voices.lock().unwrap().song()) {
+        error!("failed to save the song. {e}");
+    }
+
+    // the socket is removed when its lock drops.
+    res
 }
 
 #[cfg(feature = "ssr")]
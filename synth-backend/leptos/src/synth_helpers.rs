@@ -1,18 +1,35 @@
 use anyhow::Result;
 use log::*;
-use midi_control::MidiMessage;
-use midir::{Ignore, MidiInput, PortInfoError};
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
-};
-use stepper_synth_backend::{
-    sequencer::SequencerIntake, synth_engines::Synth, HashMap, MidiControlled,
+use midir::{ConnectError, Ignore, MidiInput, MidiInputConnection, PortInfoError};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::sleep,
+    time::{Duration, Instant},
 };
+use stepper_synth_backend::{synth_engines::Synth, HashMap};
+use synth_engine::{modulate, play, release_notes};
+use synth_lib::{mixer::Mixer, notes::HeldNotes, voices::Voices, wavetable::WaveTables};
+
+/// how often new MIDI ports are looked for.
+const RESCAN_INTERVAL: Duration = Duration::from_millis(500);
+/// how often the mod matrices move the params they modulate.
+const MOD_INTERVAL: Duration = Duration::from_millis(5);
+
+/// a connected MIDI input and the notes it is holding down.
+struct Port {
+    connection: std::result::Result<MidiInputConnection<()>, ConnectError<MidiInput>>,
+    held: Arc<Mutex<HeldNotes>>,
+}
 
-pub async fn run_midi(
-    seq: actix_web::web::Data<Mutex<SequencerIntake>>,
+/// connects every MIDI input, as they are plugged in, to the synth until `exit` is set, then
+/// closes them. blocks, so it runs on its own thread.
+pub fn run_midi(
     synth: actix_web::web::Data<Mutex<Synth>>,
+    held: actix_web::web::Data<Mutex<HeldNotes>>,
+    voices: actix_web::web::Data<Mutex<Voices>>,
     // updated: Arc<Mutex<bool>>,
     exit: Arc<AtomicBool>,
     // effect_midi: Arc<AtomicBool>,
@@ -36,7 +53,23 @@ pub async fn run_midi(
             .iter()
             .map(|port| midi_in.port_name(port))
             .collect();
-        registered_ports.retain(|k: &String, _| port_names.contains(&Ok(k.clone())));
+        registered_ports.retain(|k: &String, port: &mut Port| {
+            let connected = port_names.contains(&Ok(k.clone()));
+
+            // a controller unplugged mid-note never sends the note offs.
+            if !connected {
+                info!("port {k} disconnected");
+                let notes = *port.held.lock().unwrap();
+                release_notes(
+                    &mut synth.lock().unwrap(),
+                    &mut held.lock().unwrap(),
+                    &mut voices.lock().unwrap(),
+                    &notes,
+                );
+            }
+
+            connected
+        });
 
         for in_port in in_ports.iter() {
             let Ok(port_name) = midi_in.port_name(in_port) else {
@@ -55,52 +88,89 @@ pub async fn run_midi(
             let mut midi_in = MidiInput::new("midir reading input")?;
             midi_in.ignore(Ignore::None);
             let synth = synth.clone();
+            let held = held.clone();
+            let voices = voices.clone();
+            let port_held = Arc::new(Mutex::new(HeldNotes::default()));
             // let tx = tx.clone();
             // let updated = updated.clone();
             // let effect = effect_midi.clone();
-            let seq = seq.clone();
             // let wurli = wurli.clone();
             // let name = port_name.clone();
 
             registered_ports.insert(
                 port_name,
-                midi_in.connect(
-                    in_port,
-                    "midir-read-input",
-                    move |_stamp, msg, _| {
-                        let message = MidiMessage::from(msg);
-                        // let wurli_focused =
-                        //     synth.lock().unwrap().engine_type == SynthEngineType::Wurlitzer;
-
-                        // match message {
-                        //     MidiMessage::NoteOn(channel, _)
-                        //     | MidiMessage::NoteOff(channel, _)
-                        //     | MidiMessage::PitchBend(channel, _, _)
-                        //     | MidiMessage::ControlChange(channel, _)
-                        //         // if channel == WURLITZER_CHANNEL || wurli_focused
-                        //         =>
-                        //     {
-                        //         // println!("sending midi message {message:?} on port {name}");
-                        //         _ = wurli.lock().unwrap().send(msg);
-                        //     }
-                        //     _ => {
-                        //         synth.lock().unwrap().midi_input(&message);
-                        //     }
-                        // }
-
-                        synth.lock().unwrap().midi_input(&message);
-
-                        let mut seq = seq.lock().unwrap();
-
-                        if seq.state.recording {
-                            seq.midi_input(&message);
-                        }
-                    },
-                    (),
-                ),
+                Port {
+                    held: port_held.clone(),
+                    connection: midi_in.connect(
+                        in_port,
+                        "midir-read-input",
+                        move |_stamp, msg, _| {
+                            // let wurli_focused =
+                            //     synth.lock().unwrap().engine_type == SynthEngineType::Wurlitzer;
+
+                            // match message {
+                            //     MidiMessage::NoteOn(channel, _)
+                            //     | MidiMessage::NoteOff(channel, _)
+                            //     | MidiMessage::PitchBend(channel, _, _)
+                            //     | MidiMessage::ControlChange(channel, _)
+                            //         // if channel == WURLITZER_CHANNEL || wurli_focused
+                            //         =>
+                            //     {
+                            //         // println!("sending midi message {message:?} on port {name}");
+                            //         _ = wurli.lock().unwrap().send(msg);
+                            //     }
+                            //     _ => {
+                            //         synth.lock().unwrap().midi_input(&message);
+                            //     }
+                            // }
+
+                            port_held.lock().unwrap().midi_input(msg);
+                            held.lock().unwrap().midi_input(msg);
+                            // the song records what is played while it plays.
+                            play(&mut synth.lock().unwrap(), &mut voices.lock().unwrap(), msg);
+                        },
+                        (),
+                    ),
+                },
             );
         }
+
+        sleep(RESCAN_INTERVAL);
+    }
+
+    for (port_name, port) in registered_ports.drain() {
+        if let Ok(connection) = port.connection {
+            info!("closing port {port_name}");
+            connection.close();
+        }
     }
 
     Ok(())
 }
+
+/// runs the LFOs and mod matrices until `exit` is set. blocks, so it runs on its own thread.
+pub fn run_modulation(
+    synth: actix_web::web::Data<Mutex<Synth>>,
+    mixer: actix_web::web::Data<Mutex<Mixer>>,
+    voices: actix_web::web::Data<Mutex<Voices>>,
+    tables: actix_web::web::Data<Mutex<WaveTables>>,
+    exit: Arc<AtomicBool>,
+) {
+    let mut last = Instant::now();
+
+    while !exit.load(Ordering::Relaxed) {
+        sleep(MOD_INTERVAL);
+        let now = Instant::now();
+
+        // NOTE: always lock the synth before the mixer, the mixer before the voices, and the
+        // voices before the tables.
+        modulate(
+            &mut synth.lock().unwrap(),
+            &mut mixer.lock().unwrap(),
+            &mut voices.lock().unwrap(),
+            &tables.lock().unwrap(),
+            (now - last).as_secs_f32(),
+        );
+        last = now;
+    }
+}
@@ -0,0 +1,212 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_ws::{Message, MessageStream, Session};
+use log::*;
+use std::{sync::Mutex, time::Duration};
+use stepper_synth_backend::{synth_engines::Synth, SAMPLE_RATE};
+use synth_common::{
+    request_role, save_song, BackendToUi, CommandError, Role, ScopeFrame, SynthState, UiToBackend,
+};
+use synth_engine::{apply, synth_state};
+use synth_lib::{
+    mixer::Mixer,
+    notes::HeldNotes,
+    scope::{ScopeRing, SCOPE_LEN},
+    voices::Voices,
+    wavetable::WaveTables,
+};
+
+/// how often the state is checked for changes to send to the UIs.
+const STATE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// everything a websocket session reads or changes.
+struct SessionData {
+    synth: web::Data<Mutex<Synth>>,
+    mixer: web::Data<Mutex<Mixer>>,
+    held: web::Data<Mutex<HeldNotes>>,
+    voices: web::Data<Mutex<Voices>>,
+    tables: web::Data<Mutex<WaveTables>>,
+    scope: web::Data<ScopeRing>,
+    /// what the client that opened the session may do.
+    role: Role,
+}
+
+impl SessionData {
+    fn state(&self) -> SynthState {
+        // NOTE: always lock the synth before the mixer, the audio thread does the same.
+        let synth = self.synth.lock().unwrap();
+        let mixer = self.mixer.lock().unwrap();
+
+        synth_state(
+            &synth,
+            &mixer,
+            &self.held.lock().unwrap(),
+            &self.voices.lock().unwrap(),
+            &self.tables.lock().unwrap(),
+        )
+    }
+
+    fn scope(&self) -> ScopeFrame {
+        ScopeFrame {
+            samples: self.scope.latest(SCOPE_LEN),
+            sample_rate: ScopeRing::sample_rate(SAMPLE_RATE as f32),
+        }
+    }
+
+    fn apply(&self, cmd: UiToBackend) {
+        if let Err(e) = run_command(
+            &self.synth,
+            &self.mixer,
+            &self.held,
+            &self.voices,
+            &self.tables,
+            cmd,
+        ) {
+            warn!("ignoring {cmd:?}. {e}");
+        }
+    }
+}
+
+/// locks what a command needs and applies it, used by the websocket and the server functions.
+/// commands with channels, slots or MIDI data the synth doesn't have are refused before
+/// anything is locked.
+pub fn run_command(
+    synth: &Mutex<Synth>,
+    mixer: &Mutex<Mixer>,
+    held: &Mutex<HeldNotes>,
+    voices: &Mutex<Voices>,
+    tables: &Mutex<WaveTables>,
+    cmd: UiToBackend,
+) -> Result<(), CommandError> {
+    cmd.validate()?;
+
+    match cmd {
+        UiToBackend::Midi(midi) => held.lock().unwrap().midi_input(&midi.to_bytes()),
+        UiToBackend::Panic => *held.lock().unwrap() = HeldNotes::default(),
+        _ => {}
+    }
+
+    let song = {
+        let mut synth = synth.lock().unwrap();
+        let mut mixer = mixer.lock().unwrap();
+        let mut voices = voices.lock().unwrap();
+        let mut tables = tables.lock().unwrap();
+
+        apply(&mut synth, &mut mixer, &mut voices, &mut tables, cmd);
+
+        matches!(cmd, UiToBackend::Song(_)).then(|| voices.song().clone())
+    };
+
+    // the song is saved whenever it is edited, once the synth is let go.
+    if let Some(song) = song {
+        if let Err(e) = save_song(&song) {
+            warn!("failed to save the song. {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// streams the synth's state to a remote UI as JSON text frames, whenever it changes, and
+/// applies the `UiToBackend` commands the UI sends back. the UI can also ask for the output's
+/// scope frames.
+#[actix_web::get("/ws")]
+pub async fn synth_ws(
+    req: HttpRequest,
+    body: web::Payload,
+    synth: web::Data<Mutex<Synth>>,
+    mixer: web::Data<Mutex<Mixer>>,
+    held: web::Data<Mutex<HeldNotes>>,
+    voices: web::Data<Mutex<Voices>>,
+    tables: web::Data<Mutex<WaveTables>>,
+    scope: web::Data<ScopeRing>,
+) -> actix_web::Result<HttpResponse> {
+    let (response, session, msg_stream) = actix_ws::handle(&req, body)?;
+    let data = SessionData {
+        synth,
+        mixer,
+        held,
+        voices,
+        tables,
+        scope,
+        role: request_role(&req),
+    };
+
+    actix_web::rt::spawn(ws_session(session, msg_stream, data));
+
+    Ok(response)
+}
+
+/// the panic button, for clients that aren't connected to the websocket.
+#[actix_web::post("/panic")]
+pub async fn panic(
+    synth: web::Data<Mutex<Synth>>,
+    mixer: web::Data<Mutex<Mixer>>,
+    held: web::Data<Mutex<HeldNotes>>,
+    voices: web::Data<Mutex<Voices>>,
+    tables: web::Data<Mutex<WaveTables>>,
+) -> HttpResponse {
+    // a panic has no numbers to check, it can't be refused.
+    let _ = run_command(&synth, &mixer, &held, &voices, &tables, UiToBackend::Panic);
+
+    HttpResponse::Ok().finish()
+}
+
+async fn send(session: &mut Session, msg: &BackendToUi) -> bool {
+    match serde_json::to_string(msg) {
+        Ok(msg) => session.text(msg).await.is_ok(),
+        Err(e) => {
+            error!("failed to serialize {msg:?}. {e}");
+            true
+        }
+    }
+}
+
+async fn ws_session(mut session: Session, mut msg_stream: MessageStream, data: SessionData) {
+    let mut interval = actix_web::rt::time::interval(STATE_INTERVAL);
+    let mut last_state = None;
+    let mut stream_scope = false;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let state = data.state();
+
+                if last_state.as_ref() != Some(&state) {
+                    if !send(&mut session, &BackendToUi::State(Box::new(state.clone()))).await {
+                        break;
+                    }
+
+                    last_state = Some(state);
+                }
+
+                if stream_scope && !send(&mut session, &BackendToUi::Scope(data.scope())).await {
+                    break;
+                }
+            }
+            msg = msg_stream.recv() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<UiToBackend>(&text) {
+                    Ok(cmd) if data.role < Role::Control && !cmd.read_only() => {
+                        warn!("ignoring {cmd:?} from a read-only connection");
+                    }
+                    Ok(UiToBackend::StreamScope(on)) => stream_scope = on,
+                    Ok(UiToBackend::Ping(stamp)) => {
+                        if !send(&mut session, &BackendToUi::Pong(stamp)).await {
+                            break;
+                        }
+                    }
+                    Ok(cmd) => data.apply(cmd),
+                    Err(e) => warn!("ignoring malformed command {text}. {e}"),
+                },
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = session.close(None).await;
+}
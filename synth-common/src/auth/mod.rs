@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "actix")]
+pub use server::*;
+
+#[cfg(feature = "actix")]
+pub mod server;
+
+/// what a client of the network API is allowed to do. connections over the unix socket are
+/// trusted and always get `Control`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    /// can watch the synth's state but not change it.
+    ReadOnly,
+    Control,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct User {
+    pub name: String,
+    /// an argon2 PHC string made with `hash_password`, so the config never holds the password
+    /// itself.
+    pub password_hash: String,
+    pub role: Role,
+}
+
+/// who may use the network API. when neither a token nor any users are set, auth is off and
+/// everyone gets `Control`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// a shared secret that grants `Control`.
+    pub token: Option<String>,
+    pub users: Vec<User>,
+}
+
+/// what a client sent to prove who it is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Credentials {
+    Token(String),
+    Password { user: String, password: String },
+}
+
+impl AuthConfig {
+    pub fn enabled(&self) -> bool {
+        self.token.is_some() || !self.users.is_empty()
+    }
+}
@@ -0,0 +1,175 @@
+use super::{AuthConfig, Credentials, Role};
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse,
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{Method, header},
+    middleware::{Logger, Next},
+    web,
+};
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use log::*;
+use std::{fs, path::PathBuf};
+
+/// an argon2 hash of the password with a random salt, as a PHC string for the auth config.
+pub fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        // only fails for params or salts out of argon2's range, the defaults are in it.
+        .expect("argon2 can hash with its default params")
+}
+
+/// whether the password matches a hash from `hash_password`. a hash that can't be read never
+/// matches.
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// compares without returning early, so the time taken doesn't give away how much matched.
+fn same(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+impl AuthConfig {
+    /// the role the credentials grant, `None` when they are wrong.
+    pub fn role(&self, credentials: &Credentials) -> Option<Role> {
+        match credentials {
+            Credentials::Token(token) => self
+                .token
+                .as_ref()
+                .is_some_and(|expected| same(expected, token))
+                .then_some(Role::Control),
+            Credentials::Password { user, password } => self
+                .users
+                .iter()
+                .find(|u| u.name == *user && verify_password(password, &u.password_hash))
+                .map(|u| u.role)
+                // a token can also be given as the password of any user name.
+                .or_else(|| self.role(&Credentials::Token(password.clone()))),
+        }
+    }
+}
+
+/// logs each request without its query string, which can hold the `token` a browser's
+/// websocket has to send there. the referer is left out for the same reason.
+pub fn request_logger() -> Logger {
+    Logger::new("%a \"%{method}xi %U\" %s %b \"%{User-Agent}i\" %T")
+        .custom_request_replace("method", |req| req.method().to_string())
+}
+
+/// `$XDG_CONFIG_HOME/synth-backend/auth.toml`, falling back to `~/.config`.
+pub fn auth_path() -> PathBuf {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default();
+
+    config_dir.join("synth-backend").join("auth.toml")
+}
+
+/// reads the auth config, a missing file turns auth off. a file that can't be read is an error
+/// rather than leaving the API open by mistake.
+pub fn load_auth() -> std::io::Result<AuthConfig> {
+    let path = auth_path();
+
+    match fs::read_to_string(&path) {
+        Ok(config) => toml::from_str(&config).map_err(|e| {
+            std::io::Error::other(format!(
+                "{} is not a valid auth config. {e}",
+                path.display()
+            ))
+        }),
+        Err(_) => {
+            warn!(
+                "no auth config at {}, the network API is open to anyone who can reach it",
+                path.display()
+            );
+
+            Ok(AuthConfig::default())
+        }
+    }
+}
+
+/// the credentials in an `Authorization` header (bearer token or basic auth) or a `token` query
+/// param, the websocket API in browsers can't set headers.
+fn credentials(req: &ServiceRequest) -> Option<Credentials> {
+    let auth = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    if let Some(token) = auth.and_then(|auth| auth.strip_prefix("Bearer ")) {
+        return Some(Credentials::Token(token.trim().to_string()));
+    }
+
+    if let Some(basic) = auth.and_then(|auth| auth.strip_prefix("Basic ")) {
+        let decoded = BASE64_STANDARD.decode(basic.trim()).ok()?;
+        let (user, password) = std::str::from_utf8(&decoded).ok()?.split_once(':')?;
+
+        return Some(Credentials::Password {
+            user: user.to_string(),
+            password: password.to_string(),
+        });
+    }
+
+    web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.get("token").cloned())
+        .map(Credentials::Token)
+}
+
+/// the role the auth middleware gave the request.
+pub fn request_role(req: &HttpRequest) -> Role {
+    req.extensions()
+        .get::<Role>()
+        .copied()
+        .unwrap_or(Role::ReadOnly)
+}
+
+/// checks every request against the auth config and records the client's `Role` on it.
+/// read-only clients can only make requests that don't change anything.
+pub async fn require_auth(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let auth = req.app_data::<web::Data<AuthConfig>>();
+    // unix socket connections have no peer address, the socket's permissions guard them.
+    let trusted = req.peer_addr().is_none() || auth.is_none_or(|auth| !auth.enabled());
+
+    let role = if trusted {
+        Some(Role::Control)
+    } else {
+        credentials(&req).and_then(|creds| auth.and_then(|auth| auth.role(&creds)))
+    };
+
+    let Some(role) = role else {
+        let res = HttpResponse::Unauthorized()
+            .insert_header((header::WWW_AUTHENTICATE, "Basic realm=\"synth\""))
+            .finish();
+
+        return Ok(req.into_response(res));
+    };
+
+    if role < Role::Control && !matches!(*req.method(), Method::GET | Method::HEAD) {
+        return Ok(req.into_response(HttpResponse::Forbidden().finish()));
+    }
+
+    req.extensions_mut().insert(role);
+
+    Ok(next.call(req).await?.map_into_boxed_body())
+}
@@ -0,0 +1,652 @@
+use crate::{
+    BendRange, EffectType, EngineType, LfoSettings, MAX_PATTERN_LEN, MidiToBackend, ModDest,
+    ModSlot, ModSrc, N_CHANNELS, N_LFOS, N_SEQUENCES, PATTERN_LENS, SONG_LEN, TableChoice,
+    VoiceSettings,
+};
+#[cfg(feature = "actix")]
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use synth_lib::modulation::{N_MOD_ENVS, N_MOD_FILTERS, N_MOD_OSCS};
+
+/// how many effect slots each channel has.
+pub const N_EFFECT_SLOTS: u8 = 2;
+/// how many knobs a knob controlled engine has.
+pub const N_KNOBS: u8 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WaveTableCmd {
+    SetOscLevel {
+        osc: u8,
+        level: f32,
+    },
+    SetOscOffset {
+        osc: u8,
+        offset: i16,
+    },
+    SetOscDetune {
+        osc: u8,
+        detune: f32,
+    },
+    /// a true value will turn on the oscilator
+    SetOscPower {
+        osc: u8,
+        on: bool,
+    },
+    /// picks the wavetable an oscillator plays.
+    SetOscTable {
+        osc: u8,
+        table: TableChoice,
+    },
+    /// where in its wavetable an oscillator plays, from 0.0 (the first frame) to 1.0 (the last).
+    SetOscPosition {
+        osc: u8,
+        position: f32,
+    },
+    SetEnvAtk {
+        env: u8,
+        value: f32,
+    },
+    SetEnvDcy {
+        env: u8,
+        value: f32,
+    },
+    SetEnvSus {
+        env: u8,
+        value: f32,
+    },
+    SetEnvRel {
+        env: u8,
+        value: f32,
+    },
+    /// sets an LFO's speed in seconds per cycle.
+    SetLfoSpeed {
+        lfo: u8,
+        speed: f32,
+    },
+    SetFilterCutoff {
+        filter: u8,
+        cutoff: f32,
+    },
+    SetFilterResonance {
+        filter: u8,
+        resonance: f32,
+    },
+    SetFilterMix {
+        filter: u8,
+        mix: f32,
+    },
+    SetFilterKeytrack {
+        filter: u8,
+        on: bool,
+    },
+}
+
+/// edits or drives the song arrangement, bars and sequences are numbered from 0.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SongCmd {
+    /// places a sequence in (or with `None` clears) a bar of a channel's arrangement.
+    SetSlot {
+        channel: u8,
+        bar: u8,
+        seq: Option<u8>,
+    },
+    /// sets the bars the song loops over, both inclusive.
+    SetLoop {
+        start: u8,
+        end: u8,
+    },
+    /// sets how many steps each bar lasts, one of `PATTERN_LENS`.
+    SetPatternLen(u8),
+    /// sets the tempo in bpm, brought into `TEMPOS` by the synth.
+    SetTempo(f32),
+    SetMute {
+        channel: u8,
+        mute: bool,
+    },
+    SetSolo {
+        channel: u8,
+        solo: bool,
+    },
+    /// starts the song from the loop start.
+    Play,
+    Stop,
+    /// records the notes played while the song plays into the sequence each channel is playing.
+    Record(bool),
+    /// wipes the notes of a sequence.
+    ClearSequence(u8),
+    /// adds `note` on a step of a sequence, or takes it out when it is already there.
+    ToggleStep {
+        seq: u8,
+        step: u8,
+        note: u8,
+    },
+}
+
+/// a command from a UI to the synth, channels are numbered from 0 (0 => A, 1 => B, ...).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "actix", derive(Message), rtype(result = "()"))]
+pub enum UiToBackend {
+    Midi(MidiToBackend),
+    SetEngine {
+        channel: u8,
+        engine: EngineType,
+    },
+    /// sets one of the eight knobs of a knob controlled engine.
+    SetKnob {
+        channel: u8,
+        knob: u8,
+        value: f32,
+    },
+    WaveTable {
+        channel: u8,
+        cmd: WaveTableCmd,
+    },
+    /// inserts, replaces or (with `None`) removes the effect in a slot. picking the effect that
+    /// is already there keeps its settings.
+    SetEffect {
+        channel: u8,
+        slot: u8,
+        effect: Option<EffectType>,
+    },
+    /// turns an effect on or bypasses it.
+    SetEffectPower {
+        channel: u8,
+        slot: u8,
+        on: bool,
+    },
+    /// sets one of the params of the effect in a slot, `param` indexes `EffectType::param_names`.
+    SetEffectParam {
+        channel: u8,
+        slot: u8,
+        param: u8,
+        value: f32,
+    },
+    /// swaps the order of the two effect slots.
+    SwapEffects {
+        channel: u8,
+    },
+    SetVolume {
+        channel: u8,
+        volume: f32,
+    },
+    SetMute {
+        channel: u8,
+        mute: bool,
+    },
+    /// sets a channel's polyphony, voice stealing, mono modes and glide.
+    SetVoices {
+        channel: u8,
+        settings: VoiceSettings,
+    },
+    /// sets how far a full pitch bend goes up and down on a channel.
+    SetBendRange {
+        channel: u8,
+        range: BendRange,
+    },
+    /// connects a source to a destination in a channel's mod matrix, or changes the amount of
+    /// the connection between them.
+    ModConnect {
+        channel: u8,
+        slot: ModSlot,
+    },
+    /// replaces the connection in one of a channel's mod matrix slots, or adds one when `index`
+    /// is past the last.
+    SetModSlot {
+        channel: u8,
+        index: usize,
+        slot: ModSlot,
+    },
+    ModDisconnect {
+        channel: u8,
+        src: ModSrc,
+        dest: ModDest,
+    },
+    /// sets the shape, rate, sync, retrigger, phase and fade in of one of the global LFOs.
+    SetLfo {
+        lfo: u8,
+        settings: LfoSettings,
+    },
+    /// turns the soft limiter on the output on or off.
+    SetLimiter(bool),
+    Song(SongCmd),
+    /// stops every voice and resets the controllers on every channel, for stuck notes.
+    Panic,
+    /// starts or stops the `BackendToUi::Scope` frames on this connection.
+    StreamScope(bool),
+    /// answered straight away with a `BackendToUi::Pong` carrying the same stamp, for measuring
+    /// the connection's round trip.
+    Ping(f64),
+}
+
+impl UiToBackend {
+    /// true for commands that only affect the connection they are sent on, which read-only
+    /// clients may send.
+    pub fn read_only(&self) -> bool {
+        matches!(self, Self::StreamScope(_) | Self::Ping(_))
+    }
+
+    /// the channel the command is for, `None` for the commands that aren't for one.
+    pub fn channel(&self) -> Option<u8> {
+        match *self {
+            Self::Midi(midi) => Some(midi.channel()),
+            Self::SetEngine { channel, .. }
+            | Self::SetKnob { channel, .. }
+            | Self::WaveTable { channel, .. }
+            | Self::SetEffect { channel, .. }
+            | Self::SetEffectPower { channel, .. }
+            | Self::SetEffectParam { channel, .. }
+            | Self::SwapEffects { channel }
+            | Self::SetVolume { channel, .. }
+            | Self::SetMute { channel, .. }
+            | Self::SetVoices { channel, .. }
+            | Self::SetBendRange { channel, .. }
+            | Self::ModConnect { channel, .. }
+            | Self::SetModSlot { channel, .. }
+            | Self::ModDisconnect { channel, .. }
+            | Self::Song(SongCmd::SetSlot { channel, .. })
+            | Self::Song(SongCmd::SetMute { channel, .. })
+            | Self::Song(SongCmd::SetSolo { channel, .. }) => Some(channel),
+            Self::Song(_)
+            | Self::SetLfo { .. }
+            | Self::SetLimiter(_)
+            | Self::Panic
+            | Self::StreamScope(_)
+            | Self::Ping(_) => None,
+        }
+    }
+
+    /// checks the channel, slot, knob, LFO and mod matrix numbers and the MIDI data of a command
+    /// from a UI or a patch, so a bad one is refused before anything is locked. the
+    /// oscillators, envelopes and filters of a wavetable command are checked by the engine,
+    /// which knows how many it has.
+    pub fn validate(&self) -> Result<(), CommandError> {
+        if let Some(channel) = self.channel()
+            && channel as usize >= N_CHANNELS
+        {
+            return Err(CommandError::Channel(channel));
+        }
+
+        match *self {
+            Self::Midi(midi) if !midi.valid() => Err(CommandError::Midi(midi)),
+            Self::SetKnob { knob, .. } if knob >= N_KNOBS => Err(CommandError::Knob(knob)),
+            Self::SetEffect { slot, .. }
+            | Self::SetEffectPower { slot, .. }
+            | Self::SetEffectParam { slot, .. }
+                if slot >= N_EFFECT_SLOTS =>
+            {
+                Err(CommandError::EffectSlot(slot))
+            }
+            Self::ModConnect { slot, .. } | Self::SetModSlot { slot, .. } => {
+                check_src(slot.src)?;
+                check_dest(slot.dest)
+            }
+            Self::ModDisconnect { src, dest, .. } => {
+                check_src(src)?;
+                check_dest(dest)
+            }
+            Self::SetLfo { lfo, .. } if lfo as usize >= N_LFOS => Err(CommandError::Lfo(lfo)),
+            Self::Song(cmd) => check_song(cmd),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn check_song(cmd: SongCmd) -> Result<(), CommandError> {
+    match cmd {
+        SongCmd::SetSlot { bar, .. } if bar as usize >= SONG_LEN => Err(CommandError::Bar(bar)),
+        SongCmd::SetSlot { seq: Some(seq), .. }
+        | SongCmd::ClearSequence(seq)
+        | SongCmd::ToggleStep { seq, .. }
+            if seq as usize >= N_SEQUENCES =>
+        {
+            Err(CommandError::Sequence(seq))
+        }
+        SongCmd::SetLoop { start, end } => match [start, end]
+            .into_iter()
+            .find(|bar| *bar as usize >= SONG_LEN)
+        {
+            Some(bar) => Err(CommandError::Bar(bar)),
+            None => Ok(()),
+        },
+        SongCmd::SetPatternLen(len) if !PATTERN_LENS.contains(&(len as usize)) => {
+            Err(CommandError::PatternLen(len))
+        }
+        SongCmd::ToggleStep { step, .. } if step as usize >= MAX_PATTERN_LEN => {
+            Err(CommandError::Step(step))
+        }
+        SongCmd::ToggleStep { note, .. } if note > 127 => Err(CommandError::Note(note)),
+        _ => Ok(()),
+    }
+}
+
+fn check_src(src: ModSrc) -> Result<(), CommandError> {
+    match src {
+        ModSrc::Lfo(lfo) if lfo as usize >= N_LFOS => Err(CommandError::Lfo(lfo)),
+        ModSrc::Env(env) if env >= N_MOD_ENVS => Err(CommandError::Env(env)),
+        _ => Ok(()),
+    }
+}
+
+fn check_dest(dest: ModDest) -> Result<(), CommandError> {
+    match dest {
+        ModDest::OscLevel(osc) | ModDest::OscDetune(osc) | ModDest::OscPosition(osc)
+            if osc >= N_MOD_OSCS =>
+        {
+            Err(CommandError::Osc(osc))
+        }
+        ModDest::FilterCutoff(filter)
+        | ModDest::FilterResonance(filter)
+        | ModDest::FilterMix(filter)
+            if filter >= N_MOD_FILTERS =>
+        {
+            Err(CommandError::Filter(filter))
+        }
+        ModDest::EffectParam { slot, .. } if slot >= N_EFFECT_SLOTS => {
+            Err(CommandError::EffectSlot(slot))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// why a command was refused before it reached the synth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandError {
+    Channel(u8),
+    EffectSlot(u8),
+    Knob(u8),
+    Lfo(u8),
+    Env(u8),
+    Osc(u8),
+    Filter(u8),
+    Bar(u8),
+    Sequence(u8),
+    Step(u8),
+    Note(u8),
+    /// a pattern length that isn't one of `PATTERN_LENS`.
+    PatternLen(u8),
+    /// a data byte above 127 or a pitch bend past 14 bits.
+    Midi(MidiToBackend),
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Channel(channel) => write!(f, "there is no channel {channel}"),
+            Self::EffectSlot(slot) => write!(f, "there is no effect slot {slot}"),
+            Self::Knob(knob) => write!(f, "there is no knob {knob}"),
+            Self::Lfo(lfo) => write!(f, "there is no LFO {lfo}"),
+            Self::Env(env) => write!(f, "there is no envelope {env}"),
+            Self::Osc(osc) => write!(f, "there is no oscillator {osc}"),
+            Self::Filter(filter) => write!(f, "there is no filter {filter}"),
+            Self::Bar(bar) => write!(f, "there is no bar {bar}"),
+            Self::Sequence(seq) => write!(f, "there is no sequence {seq}"),
+            Self::Step(step) => write!(f, "there is no step {step}"),
+            Self::Note(note) => write!(f, "{note} is not a MIDI note"),
+            Self::PatternLen(len) => write!(f, "a pattern can't be {len} steps long"),
+            Self::Midi(midi) => write!(f, "{midi:?} is not a valid MIDI message"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BackendToUi {
+    State(Box<crate::SynthState>),
+    Scope(crate::ScopeFrame),
+    /// the reply to a `UiToBackend::Ping`.
+    Pong(f64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn knob(channel: u8, knob: u8) -> UiToBackend {
+        UiToBackend::SetKnob {
+            channel,
+            knob,
+            value: 0.5,
+        }
+    }
+
+    fn connect(src: ModSrc, dest: ModDest) -> UiToBackend {
+        UiToBackend::ModConnect {
+            channel: 0,
+            slot: ModSlot {
+                src,
+                dest,
+                amount: 0.5,
+            },
+        }
+    }
+
+    #[test]
+    fn refuses_channels_past_the_last() {
+        let last = N_CHANNELS as u8 - 1;
+
+        assert_eq!(knob(last, 0).validate(), Ok(()));
+        assert_eq!(
+            knob(N_CHANNELS as u8, 0).validate(),
+            Err(CommandError::Channel(N_CHANNELS as u8))
+        );
+        assert_eq!(
+            UiToBackend::SwapEffects { channel: 200 }.validate(),
+            Err(CommandError::Channel(200))
+        );
+        assert_eq!(
+            UiToBackend::Song(SongCmd::SetMute {
+                channel: 9,
+                mute: true
+            })
+            .validate(),
+            Err(CommandError::Channel(9))
+        );
+        // the channel of a MIDI message is its low nibble, which can be past the synth's.
+        let midi = MidiToBackend::NodeOn {
+            note: 60,
+            vel: 100,
+            channel: 15,
+        };
+        assert_eq!(
+            UiToBackend::Midi(midi).validate(),
+            Err(CommandError::Channel(15))
+        );
+    }
+
+    #[test]
+    fn refuses_knobs_and_effect_slots_past_the_last() {
+        assert_eq!(knob(0, N_KNOBS - 1).validate(), Ok(()));
+        assert_eq!(
+            knob(0, N_KNOBS).validate(),
+            Err(CommandError::Knob(N_KNOBS))
+        );
+
+        let slot_cmds = |slot| {
+            [
+                UiToBackend::SetEffect {
+                    channel: 0,
+                    slot,
+                    effect: None,
+                },
+                UiToBackend::SetEffectPower {
+                    channel: 0,
+                    slot,
+                    on: true,
+                },
+                UiToBackend::SetEffectParam {
+                    channel: 0,
+                    slot,
+                    param: 0,
+                    value: 0.5,
+                },
+            ]
+        };
+
+        for cmd in slot_cmds(N_EFFECT_SLOTS - 1) {
+            assert_eq!(cmd.validate(), Ok(()));
+        }
+        for cmd in slot_cmds(N_EFFECT_SLOTS) {
+            assert_eq!(
+                cmd.validate(),
+                Err(CommandError::EffectSlot(N_EFFECT_SLOTS))
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_lfos_past_the_last() {
+        let set_lfo = |lfo| UiToBackend::SetLfo {
+            lfo,
+            settings: LfoSettings::default(),
+        };
+
+        assert_eq!(set_lfo(N_LFOS as u8 - 1).validate(), Ok(()));
+        assert_eq!(
+            set_lfo(N_LFOS as u8).validate(),
+            Err(CommandError::Lfo(N_LFOS as u8))
+        );
+    }
+
+    #[test]
+    fn refuses_mod_sources_and_destinations_past_the_last() {
+        let dest = ModDest::OscLevel(0);
+
+        assert_eq!(connect(ModSrc::Lfo(0), dest).validate(), Ok(()));
+        assert_eq!(connect(ModSrc::Velocity, dest).validate(), Ok(()));
+        assert_eq!(
+            connect(ModSrc::Lfo(N_LFOS as u8), dest).validate(),
+            Err(CommandError::Lfo(N_LFOS as u8))
+        );
+        assert_eq!(
+            connect(ModSrc::Env(N_MOD_ENVS), dest).validate(),
+            Err(CommandError::Env(N_MOD_ENVS))
+        );
+
+        let src = ModSrc::Env(0);
+
+        assert_eq!(
+            connect(src, ModDest::OscPosition(N_MOD_OSCS)).validate(),
+            Err(CommandError::Osc(N_MOD_OSCS))
+        );
+        assert_eq!(
+            connect(src, ModDest::FilterMix(N_MOD_FILTERS)).validate(),
+            Err(CommandError::Filter(N_MOD_FILTERS))
+        );
+        assert_eq!(
+            connect(
+                src,
+                ModDest::EffectParam {
+                    slot: N_EFFECT_SLOTS,
+                    param: 0
+                }
+            )
+            .validate(),
+            Err(CommandError::EffectSlot(N_EFFECT_SLOTS))
+        );
+        assert_eq!(
+            UiToBackend::ModDisconnect {
+                channel: 0,
+                src: ModSrc::Lfo(N_LFOS as u8),
+                dest,
+            }
+            .validate(),
+            Err(CommandError::Lfo(N_LFOS as u8))
+        );
+        assert_eq!(
+            UiToBackend::SetModSlot {
+                channel: 0,
+                index: 0,
+                slot: ModSlot {
+                    src,
+                    dest: ModDest::FilterCutoff(N_MOD_FILTERS),
+                    amount: 1.0,
+                },
+            }
+            .validate(),
+            Err(CommandError::Filter(N_MOD_FILTERS))
+        );
+    }
+
+    #[test]
+    fn refuses_midi_data_past_seven_bits() {
+        let bad = [
+            MidiToBackend::NodeOn {
+                note: 128,
+                vel: 100,
+                channel: 0,
+            },
+            MidiToBackend::NodeOn {
+                note: 60,
+                vel: 128,
+                channel: 0,
+            },
+            MidiToBackend::CC {
+                code: 1,
+                data: 200,
+                channel: 0,
+            },
+            MidiToBackend::PitchBend {
+                amt: 0x2000,
+                channel: 0,
+            },
+            MidiToBackend::PolyPressure {
+                note: 60,
+                pressure: 128,
+                channel: 0,
+            },
+        ];
+
+        for midi in bad {
+            assert_eq!(
+                UiToBackend::Midi(midi).validate(),
+                Err(CommandError::Midi(midi))
+            );
+        }
+        assert_eq!(
+            UiToBackend::Midi(MidiToBackend::PitchBend {
+                amt: -0x2000,
+                channel: 0
+            })
+            .validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn refuses_song_bars_sequences_and_pattern_lens_out_of_range() {
+        let song = |cmd| UiToBackend::Song(cmd).validate();
+
+        assert_eq!(
+            song(SongCmd::SetSlot {
+                channel: 0,
+                bar: SONG_LEN as u8,
+                seq: None
+            }),
+            Err(CommandError::Bar(SONG_LEN as u8))
+        );
+        assert_eq!(
+            song(SongCmd::SetSlot {
+                channel: 0,
+                bar: 0,
+                seq: Some(N_SEQUENCES as u8)
+            }),
+            Err(CommandError::Sequence(N_SEQUENCES as u8))
+        );
+        assert_eq!(
+            song(SongCmd::SetLoop {
+                start: 0,
+                end: SONG_LEN as u8
+            }),
+            Err(CommandError::Bar(SONG_LEN as u8))
+        );
+        assert_eq!(
+            song(SongCmd::SetPatternLen(12)),
+            Err(CommandError::PatternLen(12))
+        );
+        for len in PATTERN_LENS {
+            assert_eq!(song(SongCmd::SetPatternLen(len as u8)), Ok(()));
+        }
+    }
+}
@@ -1,6 +1,25 @@
+#[cfg(feature = "actix")]
 use actix::prelude::*;
 use serde::{Deserialize, Serialize};
 
+pub use auth::*;
+pub use commands::*;
+pub use patch::*;
+#[cfg(feature = "actix")]
+pub use shutdown::*;
+#[cfg(unix)]
+pub use socket::*;
+pub use state::*;
+
+pub mod auth;
+pub mod commands;
+pub mod patch;
+#[cfg(feature = "actix")]
+pub mod shutdown;
+#[cfg(unix)]
+pub mod socket;
+pub mod state;
+
 pub type MidiNote = u8;
 pub type Velocity = u8;
 
@@ -21,8 +40,8 @@ pub enum Screen {
     Vital,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Message)]
-#[rtype(result = "()")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "actix", derive(Message), rtype(result = "()"))]
 pub enum MidiToBackend {
     NodeOn {
         note: MidiNote,
@@ -42,4 +61,216 @@ pub enum MidiToBackend {
         amt: i16,
         channel: u8,
     },
+    /// channel aftertouch, how hard the keys are pressed as a whole.
+    ChannelPressure {
+        pressure: u8,
+        channel: u8,
+    },
+    /// poly aftertouch, how hard one held key is pressed.
+    PolyPressure {
+        note: MidiNote,
+        pressure: u8,
+        channel: u8,
+    },
+}
+
+impl MidiToBackend {
+    /// reads a channel voice message from raw MIDI bytes. messages the synth doesn't take, like
+    /// clock and sysex, are `None`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (status, data) = bytes.split_first()?;
+        let channel = status & 0x0F;
+
+        match (status & 0xF0, data) {
+            (0x90, [note, 0, ..]) | (0x80, [note, _, ..]) => Some(Self::NodeOff {
+                note: *note,
+                channel,
+            }),
+            (0x90, [note, vel, ..]) => Some(Self::NodeOn {
+                note: *note,
+                vel: *vel,
+                channel,
+            }),
+            (0xA0, [note, pressure, ..]) => Some(Self::PolyPressure {
+                note: *note,
+                pressure: *pressure,
+                channel,
+            }),
+            (0xB0, [code, data, ..]) => Some(Self::CC {
+                code: *code,
+                data: *data,
+                channel,
+            }),
+            (0xD0, [pressure, ..]) => Some(Self::ChannelPressure {
+                pressure: *pressure,
+                channel,
+            }),
+            (0xE0, [lsb, msb, ..]) => Some(Self::PitchBend {
+                amt: ((*msb as i16) << 7 | *lsb as i16) - 0x2000,
+                channel,
+            }),
+            _ => None,
+        }
+    }
+
+    /// the same message on another channel.
+    pub fn on_channel(self, channel: u8) -> Self {
+        match self {
+            Self::NodeOn { note, vel, .. } => Self::NodeOn { note, vel, channel },
+            Self::NodeOff { note, .. } => Self::NodeOff { note, channel },
+            Self::CC { code, data, .. } => Self::CC {
+                code,
+                data,
+                channel,
+            },
+            Self::PitchBend { amt, .. } => Self::PitchBend { amt, channel },
+            Self::ChannelPressure { pressure, .. } => Self::ChannelPressure { pressure, channel },
+            Self::PolyPressure { note, pressure, .. } => Self::PolyPressure {
+                note,
+                pressure,
+                channel,
+            },
+        }
+    }
+
+    /// what a panic sends to a channel. a note off for every note, for voices that don't
+    /// listen to all notes off, then all sound off, all notes off, reset all controllers and a
+    /// centred pitch bend.
+    pub fn panic(channel: u8) -> impl Iterator<Item = Self> {
+        (0..128)
+            .map(move |note| Self::NodeOff { note, channel })
+            .chain([120, 123, 121].map(|code| Self::CC {
+                code,
+                data: 0,
+                channel,
+            }))
+            .chain([Self::PitchBend { amt: 0, channel }])
+    }
+
+    pub fn channel(self) -> u8 {
+        match self {
+            Self::NodeOn { channel, .. }
+            | Self::NodeOff { channel, .. }
+            | Self::CC { channel, .. }
+            | Self::PitchBend { channel, .. }
+            | Self::ChannelPressure { channel, .. }
+            | Self::PolyPressure { channel, .. } => channel,
+        }
+    }
+
+    /// whether every data byte fits in 7 bits and the pitch bend in 14, so `to_bytes` doesn't
+    /// have to mask them.
+    pub fn valid(self) -> bool {
+        match self {
+            Self::NodeOn { note, vel, .. } => note < 128 && vel < 128,
+            Self::NodeOff { note, .. } => note < 128,
+            Self::CC { code, data, .. } => code < 128 && data < 128,
+            Self::PitchBend { amt, .. } => (-0x2000..0x2000).contains(&amt),
+            Self::ChannelPressure { pressure, .. } => pressure < 128,
+            Self::PolyPressure { note, pressure, .. } => note < 128 && pressure < 128,
+        }
+    }
+
+    /// the raw MIDI bytes of the message.
+    pub fn to_bytes(self) -> Vec<u8> {
+        match self {
+            Self::NodeOn { note, vel, channel } => vec![0x90 | (channel & 0x0F), note, vel],
+            Self::NodeOff { note, channel } => vec![0x80 | (channel & 0x0F), note, 0],
+            Self::CC {
+                code,
+                data,
+                channel,
+            } => vec![0xB0 | (channel & 0x0F), code, data],
+            Self::PitchBend { amt, channel } => {
+                // pitch bend is a 14 bit value centered on 0x2000.
+                let value = (amt as i32 + 0x2000).clamp(0, 0x3FFF) as u16;
+
+                vec![
+                    0xE0 | (channel & 0x0F),
+                    (value & 0x7F) as u8,
+                    (value >> 7) as u8,
+                ]
+            }
+            Self::ChannelPressure { pressure, channel } => {
+                vec![0xD0 | (channel & 0x0F), pressure]
+            }
+            Self::PolyPressure {
+                note,
+                pressure,
+                channel,
+            } => vec![0xA0 | (channel & 0x0F), note, pressure],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midi_round_trips_through_bytes() {
+        let messages = [
+            MidiToBackend::NodeOn {
+                note: 60,
+                vel: 100,
+                channel: 3,
+            },
+            MidiToBackend::NodeOff {
+                note: 127,
+                channel: 0,
+            },
+            MidiToBackend::CC {
+                code: 74,
+                data: 0,
+                channel: 15,
+            },
+            MidiToBackend::PitchBend {
+                amt: -0x2000,
+                channel: 1,
+            },
+            MidiToBackend::PitchBend {
+                amt: 0x1FFF,
+                channel: 1,
+            },
+            MidiToBackend::PitchBend { amt: 0, channel: 2 },
+            MidiToBackend::ChannelPressure {
+                pressure: 64,
+                channel: 0,
+            },
+            MidiToBackend::PolyPressure {
+                note: 36,
+                pressure: 127,
+                channel: 9,
+            },
+        ];
+
+        for midi in messages {
+            assert_eq!(MidiToBackend::from_bytes(&midi.to_bytes()), Some(midi));
+        }
+    }
+
+    #[test]
+    fn a_note_on_without_velocity_is_a_note_off() {
+        assert_eq!(
+            MidiToBackend::from_bytes(&[0x92, 60, 0]),
+            Some(MidiToBackend::NodeOff {
+                note: 60,
+                channel: 2
+            })
+        );
+    }
+
+    #[test]
+    fn skips_messages_the_synth_doesnt_take() {
+        // clock, sysex, a program change and a note on cut short.
+        for bytes in [
+            &[0xF8][..],
+            &[0xF0, 0x7E, 0xF7],
+            &[0xC0, 5],
+            &[0x90, 60],
+            &[],
+        ] {
+            assert_eq!(MidiToBackend::from_bytes(bytes), None);
+        }
+    }
 }
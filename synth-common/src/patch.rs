@@ -0,0 +1,395 @@
+use crate::{
+    BendRange, ChannelState, EffectState, EngineType, ModMatrix, UiToBackend, VoiceSettings,
+    WaveTableCmd, WaveTableState,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use synth_lib::song::Song;
+
+/// `$XDG_DATA_HOME/synth-os`, falling back to `~/.local/share`.
+pub fn data_dir() -> PathBuf {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+        .unwrap_or_default();
+
+    data_dir.join("synth-os")
+}
+
+/// where imported wavetables are kept, as WAV files.
+pub fn wave_table_dir() -> PathBuf {
+    data_dir().join("wavetables")
+}
+
+/// where patches are saved, one JSON file each.
+pub fn patch_dir() -> PathBuf {
+    data_dir().join("patches")
+}
+
+/// the file a patch is saved to in `dir`, the name is cut down to what is safe in a file name.
+fn patch_path(dir: &Path, name: &str) -> io::Result<PathBuf> {
+    let name: String = name
+        .trim()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+        .collect();
+
+    if name.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the patch needs a name",
+        ));
+    }
+
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// the names of the patches saved in `dir`, sorted.
+fn list_patches_in(dir: &Path) -> Vec<String> {
+    let mut patches: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    patches.sort();
+
+    patches
+}
+
+fn save_patch_in(dir: &Path, name: &str, patch: &Patch) -> io::Result<()> {
+    let path = patch_path(dir, name)?;
+
+    fs::create_dir_all(dir)?;
+    fs::write(path, serde_json::to_string_pretty(patch)?)
+}
+
+fn load_patch_in(dir: &Path, name: &str) -> io::Result<Patch> {
+    Ok(serde_json::from_str(&fs::read_to_string(patch_path(
+        dir, name,
+    )?)?)?)
+}
+
+/// the names of the saved patches, sorted.
+pub fn list_patches() -> Vec<String> {
+    list_patches_in(&patch_dir())
+}
+
+pub fn save_patch(name: &str, patch: &Patch) -> io::Result<()> {
+    save_patch_in(&patch_dir(), name, patch)
+}
+
+pub fn load_patch(name: &str) -> io::Result<Patch> {
+    load_patch_in(&patch_dir(), name)
+}
+
+/// where the song, its arrangement, tempo and the stepper's sequences, is saved beside the
+/// patches. it isn't a patch, so it is kept out of their directory.
+pub fn song_path() -> PathBuf {
+    data_dir().join("song.json")
+}
+
+fn save_song_to(path: &Path, song: &Song) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_json::to_string_pretty(song)?)
+}
+
+fn load_song_from(path: &Path) -> io::Result<Option<Song>> {
+    match fs::read_to_string(path) {
+        Ok(song) => Ok(Some(serde_json::from_str::<Song>(&song)?.clamped())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// saves the song, the backends do it whenever it is edited.
+pub fn save_song(song: &Song) -> io::Result<()> {
+    save_song_to(&song_path(), song)
+}
+
+/// the saved song, `None` before one has been saved.
+pub fn load_song() -> io::Result<Option<Song>> {
+    load_song_from(&song_path())
+}
+
+/// the sound of a channel, everything that can be set on it besides its mute. loading one
+/// replays it onto a channel as commands, so any backend can load them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Patch {
+    pub engine: EngineType,
+    pub knobs: [f32; 8],
+    pub wave_table: Option<WaveTableState>,
+    pub effects: [Option<EffectState>; 2],
+    pub volume: f32,
+    pub voices: VoiceSettings,
+    pub bend_range: BendRange,
+    pub mod_matrix: ModMatrix,
+}
+
+impl From<&ChannelState> for Patch {
+    fn from(chan: &ChannelState) -> Self {
+        Self {
+            engine: chan.engine,
+            knobs: chan.knobs,
+            wave_table: chan.wave_table.clone(),
+            effects: chan.effects,
+            volume: chan.volume,
+            voices: chan.voices,
+            bend_range: chan.bend_range,
+            mod_matrix: chan.mod_matrix.clone(),
+        }
+    }
+}
+
+impl Patch {
+    /// the commands that turn `channel`, currently `current`, into this patch.
+    pub fn commands(&self, channel: u8, current: &ChannelState) -> Vec<UiToBackend> {
+        let mut cmds = Vec::new();
+
+        // changing the engine starts it from its defaults, the params below set the rest.
+        if current.engine != self.engine {
+            cmds.push(UiToBackend::SetEngine {
+                channel,
+                engine: self.engine,
+            });
+        }
+
+        cmds.extend(
+            (0..)
+                .zip(self.knobs.iter())
+                .take(self.engine.knob_names().len())
+                .map(|(knob, value)| UiToBackend::SetKnob {
+                    channel,
+                    knob,
+                    value: *value,
+                }),
+        );
+
+        if let Some(ref wt) = self.wave_table {
+            let wave_table = |cmd| UiToBackend::WaveTable { channel, cmd };
+
+            for (osc, p) in (0..).zip(wt.oscs.iter()) {
+                cmds.extend(
+                    [
+                        WaveTableCmd::SetOscTable {
+                            osc,
+                            table: p.table,
+                        },
+                        WaveTableCmd::SetOscPosition {
+                            osc,
+                            position: p.position,
+                        },
+                        WaveTableCmd::SetOscLevel {
+                            osc,
+                            level: p.level,
+                        },
+                        WaveTableCmd::SetOscOffset {
+                            osc,
+                            offset: p.offset,
+                        },
+                        WaveTableCmd::SetOscDetune {
+                            osc,
+                            detune: p.detune,
+                        },
+                        WaveTableCmd::SetOscPower { osc, on: p.power },
+                    ]
+                    .map(wave_table),
+                );
+            }
+
+            for (env, p) in (0..).zip(wt.envs.iter()) {
+                cmds.extend(
+                    [
+                        WaveTableCmd::SetEnvAtk { env, value: p.atk },
+                        WaveTableCmd::SetEnvDcy { env, value: p.dcy },
+                        WaveTableCmd::SetEnvSus { env, value: p.sus },
+                        WaveTableCmd::SetEnvRel { env, value: p.rel },
+                    ]
+                    .map(wave_table),
+                );
+            }
+
+            cmds.extend(
+                (0..).zip(wt.lfos.iter()).map(|(lfo, speed)| {
+                    wave_table(WaveTableCmd::SetLfoSpeed { lfo, speed: *speed })
+                }),
+            );
+
+            for (filter, p) in (0..).zip(wt.filters.iter()) {
+                cmds.extend(
+                    [
+                        WaveTableCmd::SetFilterCutoff {
+                            filter,
+                            cutoff: p.cutoff,
+                        },
+                        WaveTableCmd::SetFilterResonance {
+                            filter,
+                            resonance: p.resonance,
+                        },
+                        WaveTableCmd::SetFilterMix { filter, mix: p.mix },
+                        WaveTableCmd::SetFilterKeytrack {
+                            filter,
+                            on: p.key_track,
+                        },
+                    ]
+                    .map(wave_table),
+                );
+            }
+        }
+
+        for (slot, effect) in (0..).zip(self.effects.iter()) {
+            cmds.push(UiToBackend::SetEffect {
+                channel,
+                slot,
+                effect: effect.map(|effect| effect.effect),
+            });
+
+            if let Some(effect) = effect {
+                cmds.push(UiToBackend::SetEffectPower {
+                    channel,
+                    slot,
+                    on: effect.on,
+                });
+                cmds.extend(
+                    (0..)
+                        .zip(effect.params.iter())
+                        .take(effect.effect.param_names().len())
+                        .map(|(param, value)| UiToBackend::SetEffectParam {
+                            channel,
+                            slot,
+                            param,
+                            value: *value,
+                        }),
+                );
+            }
+        }
+
+        cmds.push(UiToBackend::SetVolume {
+            channel,
+            volume: self.volume,
+        });
+        cmds.push(UiToBackend::SetVoices {
+            channel,
+            settings: self.voices,
+        });
+        cmds.push(UiToBackend::SetBendRange {
+            channel,
+            range: self.bend_range,
+        });
+
+        // the matrix is replaced, not added to.
+        cmds.extend(
+            current
+                .mod_matrix
+                .slots
+                .iter()
+                .map(|slot| UiToBackend::ModDisconnect {
+                    channel,
+                    src: slot.src,
+                    dest: slot.dest,
+                }),
+        );
+        cmds.extend(
+            self.mod_matrix
+                .slots
+                .iter()
+                .map(|slot| UiToBackend::ModConnect {
+                    channel,
+                    slot: *slot,
+                }),
+        );
+
+        cmds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TEMPOS;
+
+    /// an empty directory of its own for each test.
+    fn test_dir(test: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("synth-patch-test-{test}-{}", std::process::id()));
+        _ = fs::remove_dir_all(&dir);
+
+        dir
+    }
+
+    #[test]
+    fn names_are_cut_down_to_a_file_in_the_patch_dir() {
+        let dir = Path::new("/patches");
+
+        assert_eq!(
+            patch_path(dir, " Bass 2 ").unwrap(),
+            dir.join("Bass 2.json")
+        );
+        assert_eq!(
+            patch_path(dir, "../../etc/passwd").unwrap(),
+            dir.join("etcpasswd.json")
+        );
+        assert_eq!(
+            patch_path(dir, "/./").unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn the_song_loads_back_without_its_transport() {
+        let dir = test_dir("song");
+        let path = dir.join("song.json");
+        let mut song = Song::default();
+        song.set_tempo(96.0);
+        song.set_slot(2, 5, Some(7));
+        song.toggle_step(7, 3, 64);
+        song.play();
+
+        assert!(load_song_from(&path).unwrap().is_none());
+
+        save_song_to(&path, &song).unwrap();
+        let loaded = load_song_from(&path).unwrap().unwrap();
+
+        assert_eq!(loaded.tempo, 96.0);
+        assert_eq!(loaded.tracks, song.tracks);
+        assert_eq!(loaded.sequence(7), song.sequence(7));
+        assert!(!loaded.playing);
+
+        fs::write(&path, "{ \"tempo\": 1000.0, \"pattern_len\": 3 }").unwrap();
+        let loaded = load_song_from(&path).unwrap().unwrap();
+
+        assert_eq!(loaded.tempo, *TEMPOS.end());
+        assert_eq!(loaded.pattern_len, Song::default().pattern_len);
+
+        _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn saved_patches_load_back_and_are_listed() {
+        let dir = test_dir("round-trip");
+        let patch = Patch::from(&ChannelState {
+            volume: 0.25,
+            ..Default::default()
+        });
+
+        save_patch_in(&dir, "pad", &patch).unwrap();
+        save_patch_in(&dir, "bass", &patch).unwrap();
+        fs::write(dir.join("notes.txt"), "not a patch").unwrap();
+
+        assert_eq!(load_patch_in(&dir, "pad").unwrap(), patch);
+        assert_eq!(list_patches_in(&dir), ["bass", "pad"]);
+        assert!(load_patch_in(&dir, "lead").is_err());
+
+        _ = fs::remove_dir_all(dir);
+    }
+}
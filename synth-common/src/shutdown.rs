@@ -0,0 +1,26 @@
+use actix_web::{HttpResponse, Responder, post, web};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// how long the output takes to fade to silence when the backend stops.
+pub const FADE_OUT: Duration = Duration::from_millis(250);
+
+/// lets the `/shutdown` route stop the server the same way SIGINT and SIGTERM do.
+#[derive(Debug, Default)]
+pub struct Shutdown(Notify);
+
+impl Shutdown {
+    /// waits until a client asks the backend to stop.
+    pub async fn requested(&self) {
+        self.0.notified().await
+    }
+}
+
+/// stops the backend, for appliances with no shell to send it a signal from. it is a POST, so
+/// read-only clients can't.
+#[post("/shutdown")]
+pub async fn shutdown(shutdown: web::Data<Shutdown>) -> impl Responder {
+    shutdown.0.notify_one();
+
+    HttpResponse::Accepted().finish()
+}
@@ -0,0 +1,186 @@
+use std::{
+    fs::{self, DirBuilder, File, OpenOptions, TryLockError},
+    io::{self, Read, Seek, Write},
+    os::unix::{
+        fs::{DirBuilderExt, MetadataExt, OpenOptionsExt, PermissionsExt},
+        net::UnixStream,
+    },
+    path::{Path, PathBuf},
+};
+
+/// the environment variable that overrides where the backend's unix socket is.
+pub const SOCKET_VAR: &str = "SYNTH_SOCKET";
+
+/// where the backend listens and the UIs connect. `$SYNTH_SOCKET`, else `synth/backend.sock` in
+/// `$XDG_RUNTIME_DIR`, else a directory in the temp dir named after the user.
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SOCKET_VAR) {
+        return path.into();
+    }
+
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("synth"),
+        None => std::env::temp_dir().join(
+            std::env::var("USER").map_or_else(|_| "synth".into(), |user| format!("synth-{user}")),
+        ),
+    };
+
+    dir.join("backend.sock")
+}
+
+/// refuses a directory someone else could swap the socket or lock file in. it has to belong to
+/// this user or root, and only they may write to it unless the sticky bit keeps others from
+/// removing this user's files (like `/tmp`).
+fn check_dir(dir: &Path) -> io::Result<()> {
+    let meta = fs::metadata(dir)?;
+    // SAFETY: geteuid can't fail and only reads the process's credentials.
+    let uid = unsafe { libc::geteuid() };
+    let mode = meta.permissions().mode();
+    let owned = meta.uid() == uid || meta.uid() == 0;
+    let guarded = mode & 0o022 == 0 || mode & 0o1000 != 0;
+
+    if meta.is_dir() && owned && guarded {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not safe for the backend's socket, it has to belong to this user and not \
+                 be writable by others",
+                dir.display()
+            ),
+        ))
+    }
+}
+
+/// the lock file next to a socket.
+pub fn lock_path(socket: &Path) -> PathBuf {
+    socket.with_extension("lock")
+}
+
+/// holds the lock on a socket path for as long as the backend runs, and removes the socket when
+/// dropped. the lock file is left in place, unlinking it would let a second backend lock a new
+/// file while a third still waits on the old one.
+#[derive(Debug)]
+pub struct SocketLock {
+    socket: PathBuf,
+    _lock: File,
+}
+
+impl SocketLock {
+    /// locks `socket` for this process and clears a stale socket left by a crash, so the caller
+    /// can bind it. fails when another backend holds the lock or still answers on the socket.
+    pub fn acquire(socket: impl Into<PathBuf>) -> io::Result<Self> {
+        let socket = socket.into();
+
+        if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            // the unix socket is trusted, so only this user may reach it. a directory someone
+            // else made is left as it is, the socket itself is made private by `bind_private`.
+            if !dir.exists() {
+                DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+            } else {
+                check_dir(dir)?;
+            }
+        }
+
+        let mut lock = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .mode(0o600)
+            .open(lock_path(&socket))?;
+
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut pid = String::new();
+                _ = lock.read_to_string(&mut pid);
+
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!(
+                        "another backend (pid {}) is using {}",
+                        pid.trim(),
+                        socket.display()
+                    ),
+                ));
+            }
+            Err(TryLockError::Error(e)) => return Err(e),
+        }
+
+        lock.set_len(0)?;
+        lock.rewind()?;
+        writeln!(lock, "{}", std::process::id())?;
+
+        if socket.exists() {
+            // a backend from before the lock file could still be listening.
+            if UnixStream::connect(&socket).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("a backend is already listening on {}", socket.display()),
+                ));
+            }
+
+            fs::remove_file(&socket)?;
+        }
+
+        Ok(Self {
+            socket,
+            _lock: lock,
+        })
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// binds the socket with `bind` so only this user can ever connect to it. the umask is
+    /// narrowed for the bind, so the socket is never made with looser permissions first, then
+    /// the mode is set outright in case the directory's default ACL widened it.
+    pub fn bind_private<T>(&self, bind: impl FnOnce(&Path) -> io::Result<T>) -> io::Result<T> {
+        // SAFETY: umask can't fail. it is process wide, so files the other threads make while
+        // the socket is bound are private too, which is only stricter.
+        let old = unsafe { libc::umask(0o177) };
+        let bound = bind(&self.socket);
+        // SAFETY: as above, it puts back the mask the process had.
+        unsafe { libc::umask(old) };
+
+        let bound = bound?;
+        fs::set_permissions(&self.socket, fs::Permissions::from_mode(0o600))?;
+
+        Ok(bound)
+    }
+}
+
+impl Drop for SocketLock {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.socket);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+
+    #[test]
+    fn binds_the_socket_private_to_this_user() {
+        let dir = std::env::temp_dir().join(format!("synth-socket-test-{}", std::process::id()));
+        let lock = SocketLock::acquire(dir.join("backend.sock")).unwrap();
+        // SAFETY: umask can't fail.
+        let old = unsafe { libc::umask(0o022) };
+
+        let listener = lock.bind_private(|path| UnixListener::bind(path)).unwrap();
+        // SAFETY: as above.
+        let during = unsafe { libc::umask(old) };
+        let mode = fs::metadata(lock.socket()).unwrap().permissions().mode();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(during, 0o022, "the umask is put back after the bind");
+
+        drop(listener);
+        drop(lock);
+        _ = fs::remove_dir_all(dir);
+    }
+}
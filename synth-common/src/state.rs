@@ -0,0 +1,517 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+pub use synth_lib::N_CHANNELS;
+pub use synth_lib::effects::EffectKind;
+pub use synth_lib::expression::{BendRange, MAX_BEND_RANGE};
+pub use synth_lib::lfo::{LFO_RATES, LfoSettings, LfoShape, LfoSync, MAX_FADE_IN, N_LFOS};
+pub use synth_lib::modulation::{MAX_MOD_SLOTS, ModDest, ModMatrix, ModSlot, ModSrc};
+pub use synth_lib::song::{
+    MAX_PATTERN_LEN, N_SEQUENCES, PATTERN_LENS, SONG_LEN, STEP_VELOCITY, SeqNote, TEMPOS, Track,
+};
+pub use synth_lib::voices::{MAX_GLIDE, MAX_POLYPHONY, Steal, VoiceMode, VoiceSettings};
+pub use synth_lib::wavetable::{BuiltinTable, TableChoice, TableId, TableInfo};
+use synth_lib::{expression::Expression, lfo::Lfos, meter::Meter, song::Song};
+
+/// what the channels are called in the UIs, in channel order.
+pub const CHANNEL_NAMES: [&str; N_CHANNELS] = ["A", "B", "C", "D"];
+
+/// a MIDI note's name, middle C (60) is C4.
+pub fn note_name(note: u8) -> String {
+    const NAMES: [&str; 12] = [
+        "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+    ];
+
+    format!("{}{}", NAMES[note as usize % 12], note as i32 / 12 - 1)
+}
+
+/// the quietest level a meter shows, in dB.
+pub const METER_FLOOR_DB: f32 = -60.0;
+
+/// a meter's levels in dB relative to full scale, rounded to whole dB so a quiet or steady
+/// signal doesn't make the state change on every read.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MeterState {
+    pub peak_db: f32,
+    pub rms_db: f32,
+    /// the unrounded peak reached full scale, a peak just under it rounds up to 0 dB.
+    pub clip: bool,
+}
+
+impl Default for MeterState {
+    fn default() -> Self {
+        Self {
+            peak_db: METER_FLOOR_DB,
+            rms_db: METER_FLOOR_DB,
+            clip: false,
+        }
+    }
+}
+
+impl From<&Meter> for MeterState {
+    fn from(meter: &Meter) -> Self {
+        let db = |level: f32| (20.0 * level.log10()).round().max(METER_FLOOR_DB);
+
+        Self {
+            peak_db: db(meter.peak()),
+            rms_db: db(meter.rms()),
+            clip: meter.peak() >= 1.0,
+        }
+    }
+}
+
+impl MeterState {
+    /// true when the peak reached full scale.
+    pub fn clipping(&self) -> bool {
+        self.clip
+    }
+}
+
+/// where a channel's expressive controllers are, for the UIs to show.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpressionState {
+    /// from -1.0 (all the way down) to 1.0 (all the way up).
+    pub bend: f32,
+    pub mod_wheel: f32,
+    /// channel aftertouch.
+    pub pressure: f32,
+    /// the hardest pressed key's poly aftertouch.
+    pub poly_pressure: f32,
+}
+
+impl From<&Expression> for ExpressionState {
+    fn from(expression: &Expression) -> Self {
+        // rounded so the state only changes when the UIs would show a difference.
+        let round = |value: f32| (value * 100.0).round() / 100.0;
+
+        Self {
+            bend: round(expression.bend()),
+            mod_wheel: round(expression.mod_wheel()),
+            pressure: round(expression.pressure()),
+            poly_pressure: round(expression.poly_pressure()),
+        }
+    }
+}
+
+/// one of the global LFOs, for the UIs to show.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LfoState {
+    pub settings: LfoSettings,
+    /// the free running output, from -1.0 to 1.0.
+    pub value: f32,
+}
+
+impl LfoState {
+    /// every LFO in the bank. the outputs are rounded coarsely, they move all the time and the
+    /// state is resent whenever it changes.
+    pub fn bank(lfos: &Lfos) -> [Self; N_LFOS] {
+        std::array::from_fn(|i| Self {
+            settings: lfos.settings[i],
+            value: (lfos.free(i) * 10.0).round() / 10.0,
+        })
+    }
+}
+
+/// the engines that can be loaded onto a channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EngineType {
+    #[default]
+    B3Organ,
+    SubSynth,
+    Wurlitzer,
+    WaveTable,
+    MidiOut,
+}
+
+impl EngineType {
+    pub const ALL: [Self; 5] = [
+        Self::B3Organ,
+        Self::SubSynth,
+        Self::Wurlitzer,
+        Self::WaveTable,
+        Self::MidiOut,
+    ];
+
+    /// the names of the knobs the engine responds to, in knob order.
+    pub fn knob_names(self) -> &'static [&'static str] {
+        match self {
+            Self::B3Organ => &[
+                "Bar 1", "Bar 2", "Bar 3", "Bar 4", "Bar 5", "Bar 6", "Bar 7", "Bar 8",
+            ],
+            Self::SubSynth => &["Cutoff", "Res", "Attack", "Decay", "Sustain", "Release"],
+            Self::Wurlitzer => &["Tremolo"],
+            Self::WaveTable | Self::MidiOut => &[],
+        }
+    }
+}
+
+impl Display for EngineType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::B3Organ => write!(f, "B3 Organ"),
+            Self::SubSynth => write!(f, "Sub Synth"),
+            Self::Wurlitzer => write!(f, "Wurlitzer"),
+            Self::WaveTable => write!(f, "Wave Table"),
+            Self::MidiOut => write!(f, "MIDI Out"),
+        }
+    }
+}
+
+/// the effects that can be placed in an effect slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EffectType {
+    Reverb,
+    Chorus,
+    Delay,
+    Drive,
+    Eq,
+    Compressor,
+    Phaser,
+}
+
+impl EffectType {
+    pub const ALL: [Self; 7] = [
+        Self::Reverb,
+        Self::Chorus,
+        Self::Delay,
+        Self::Drive,
+        Self::Eq,
+        Self::Compressor,
+        Self::Phaser,
+    ];
+
+    /// the names of the effect's params, in the order of `EffectState::params`.
+    pub fn param_names(self) -> &'static [&'static str] {
+        self.kind().param_names()
+    }
+
+    /// the synth-lib effect the mixer runs for this type.
+    pub fn kind(self) -> EffectKind {
+        match self {
+            Self::Reverb => EffectKind::Reverb,
+            Self::Chorus => EffectKind::Chorus,
+            Self::Delay => EffectKind::Delay,
+            Self::Drive => EffectKind::Drive,
+            Self::Eq => EffectKind::Eq,
+            Self::Compressor => EffectKind::Compressor,
+            Self::Phaser => EffectKind::Phaser,
+        }
+    }
+}
+
+impl From<EffectKind> for EffectType {
+    fn from(kind: EffectKind) -> Self {
+        match kind {
+            EffectKind::Delay => Self::Delay,
+            EffectKind::Drive => Self::Drive,
+            EffectKind::Eq => Self::Eq,
+            EffectKind::Compressor => Self::Compressor,
+            EffectKind::Phaser => Self::Phaser,
+            EffectKind::Chorus => Self::Chorus,
+            EffectKind::Reverb => Self::Reverb,
+        }
+    }
+}
+
+impl Display for EffectType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Reverb => write!(f, "Reverb"),
+            Self::Chorus => write!(f, "Chorus"),
+            Self::Delay => write!(f, "Delay"),
+            Self::Drive => write!(f, "Drive"),
+            Self::Eq => write!(f, "EQ"),
+            Self::Compressor => write!(f, "Compressor"),
+            Self::Phaser => write!(f, "Phaser"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EffectState {
+    pub effect: EffectType,
+    /// false when the effect is bypassed.
+    pub on: bool,
+    /// the values of the effect's params, named by `EffectType::param_names`.
+    pub params: [f32; 8],
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OscState {
+    pub level: f32,
+    /// in semitones.
+    pub offset: i16,
+    pub detune: f32,
+    pub power: bool,
+    pub table: TableChoice,
+    /// where in its table the oscillator plays, from 0.0 to 1.0.
+    pub position: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvState {
+    pub atk: f32,
+    pub dcy: f32,
+    pub sus: f32,
+    pub rel: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LPFilterState {
+    pub cutoff: f32,
+    pub resonance: f32,
+    pub mix: f32,
+    pub key_track: bool,
+}
+
+/// the wavetable engine's settings. every voice shares the same settings so they are read from
+/// the first one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WaveTableState {
+    pub oscs: Vec<OscState>,
+    pub envs: Vec<EnvState>,
+    /// LFO speed in seconds per cycle.
+    pub lfos: Vec<f32>,
+    pub filters: Vec<LPFilterState>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelState {
+    pub engine: EngineType,
+    /// the eight knobs of a knob controlled engine (organ, sub-synth, wurlitzer).
+    pub knobs: [f32; 8],
+    /// set when the engine is the wavetable synth.
+    pub wave_table: Option<WaveTableState>,
+    pub effects: [Option<EffectState>; 2],
+    /// linear gain applied to the channel, 0.0 is silent and 1.0 is unity.
+    pub volume: f32,
+    pub mute: bool,
+    /// how many notes are held down on the channel.
+    pub held_notes: u32,
+    pub voices: VoiceSettings,
+    /// how many notes are sounding, at most the polyphony.
+    pub active_voices: u32,
+    pub bend_range: BendRange,
+    /// the pitch bend, mod wheel and aftertouch.
+    pub expression: ExpressionState,
+    pub mod_matrix: ModMatrix,
+    /// the channel's level after the volume and mute.
+    pub meter: MeterState,
+}
+
+impl Default for ChannelState {
+    fn default() -> Self {
+        Self {
+            engine: EngineType::default(),
+            knobs: [0.0; 8],
+            wave_table: None,
+            effects: [None; 2],
+            volume: 1.0,
+            mute: false,
+            held_notes: 0,
+            voices: VoiceSettings::default(),
+            active_voices: 0,
+            bend_range: BendRange::default(),
+            expression: ExpressionState::default(),
+            mod_matrix: ModMatrix::default(),
+            meter: MeterState::default(),
+        }
+    }
+}
+
+impl ChannelState {
+    /// the destinations the mod matrix can reach on this channel, with the params of the
+    /// effects it has.
+    pub fn mod_dests(&self) -> Vec<ModDest> {
+        ModDest::all(
+            self.effects
+                .map(|effect| effect.map_or(0, |effect| effect.effect.param_names().len())),
+        )
+    }
+
+    /// shows the params the mod matrix is moving at the values they were set to, so the UIs
+    /// don't follow the modulation.
+    pub fn unmodulated(mut self, bases: &[(ModDest, f32)]) -> Self {
+        for (dest, base) in bases.iter().copied() {
+            let wave_table = self.wave_table.as_mut();
+
+            match dest {
+                ModDest::OscLevel(osc) => {
+                    if let Some(osc) = wave_table.and_then(|wt| wt.oscs.get_mut(osc as usize)) {
+                        osc.level = base;
+                    }
+                }
+                ModDest::OscDetune(osc) => {
+                    if let Some(osc) = wave_table.and_then(|wt| wt.oscs.get_mut(osc as usize)) {
+                        osc.detune = base;
+                    }
+                }
+                ModDest::OscPosition(osc) => {
+                    if let Some(osc) = wave_table.and_then(|wt| wt.oscs.get_mut(osc as usize)) {
+                        osc.position = base;
+                    }
+                }
+                ModDest::FilterCutoff(filter) => {
+                    if let Some(filter) =
+                        wave_table.and_then(|wt| wt.filters.get_mut(filter as usize))
+                    {
+                        filter.cutoff = base;
+                    }
+                }
+                ModDest::FilterResonance(filter) => {
+                    if let Some(filter) =
+                        wave_table.and_then(|wt| wt.filters.get_mut(filter as usize))
+                    {
+                        filter.resonance = base;
+                    }
+                }
+                ModDest::FilterMix(filter) => {
+                    if let Some(filter) =
+                        wave_table.and_then(|wt| wt.filters.get_mut(filter as usize))
+                    {
+                        filter.mix = base;
+                    }
+                }
+                ModDest::EffectParam { slot, param } => {
+                    if let Some(value) = self
+                        .effects
+                        .get_mut(slot as usize)
+                        .and_then(|effect| effect.as_mut())
+                        .and_then(|effect| effect.params.get_mut(param as usize))
+                    {
+                        *value = base;
+                    }
+                }
+            }
+        }
+
+        self
+    }
+}
+
+/// a snapshot of everything a UI shows, streamed from the backend to remote UIs.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SynthState {
+    pub channels: [ChannelState; N_CHANNELS],
+    pub song: SongState,
+    /// the level of the output, after the limiter.
+    pub master: MeterState,
+    /// whether the soft limiter on the output is on.
+    pub limiter: bool,
+    pub lfos: [LfoState; N_LFOS],
+    /// the built in and imported wavetables the oscillators can play.
+    pub wave_tables: Vec<TableInfo>,
+}
+
+/// the song arrangement and where it is playing, for the UIs to show.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SongState {
+    pub tracks: [Track; N_CHANNELS],
+    /// first bar of the loop (inclusive).
+    pub loop_start: usize,
+    /// last bar of the loop (inclusive).
+    pub loop_end: usize,
+    /// how many 16th note steps each bar lasts.
+    pub pattern_len: usize,
+    /// in beats per minute, the synced LFOs and effects follow it too.
+    pub tempo: f32,
+    pub playing: bool,
+    pub recording: bool,
+    /// the bar that is playing.
+    pub position: usize,
+    /// the step of the bar that is playing.
+    pub step: usize,
+    /// the notes of each sequence.
+    pub sequences: [Vec<SeqNote>; N_SEQUENCES],
+}
+
+impl SongState {
+    /// the sequence in a channel's slot at the bar that is playing.
+    pub fn current(&self, channel: usize) -> Option<u8> {
+        self.tracks[channel].slots[self.position]
+    }
+
+    /// true when the channel is heard, taking mute and solo into account.
+    pub fn audible(&self, channel: usize) -> bool {
+        if self.tracks.iter().any(|track| track.solo) {
+            self.tracks[channel].solo
+        } else {
+            !self.tracks[channel].mute
+        }
+    }
+
+    /// whether a step of the bar starts a note on a channel.
+    pub fn has_note(&self, channel: usize, step: usize) -> bool {
+        self.current(channel)
+            .is_some_and(|seq| self.step_notes(seq).any(|note| note.step as usize == step))
+    }
+
+    /// the notes of a sequence, none for one past the last.
+    pub fn step_notes(&self, seq: u8) -> impl Iterator<Item = &SeqNote> {
+        self.sequences
+            .get(seq as usize)
+            .into_iter()
+            .flat_map(|notes| notes.iter())
+    }
+}
+
+impl Default for SongState {
+    fn default() -> Self {
+        Self::from(&Song::default())
+    }
+}
+
+impl From<&Song> for SongState {
+    fn from(song: &Song) -> Self {
+        Self {
+            tracks: song.tracks,
+            loop_start: song.loop_start,
+            loop_end: song.loop_end,
+            pattern_len: song.pattern_len,
+            tempo: song.tempo,
+            playing: song.playing,
+            recording: song.recording,
+            position: song.position,
+            step: song.step,
+            sequences: std::array::from_fn(|seq| {
+                song.sequence(seq)
+                    .map_or_else(Vec::new, |sequence| sequence.notes().to_vec())
+            }),
+        }
+    }
+}
+
+/// the latest stretch of the synth's output, for drawing a scope and a spectrum.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScopeFrame {
+    /// oldest first.
+    pub samples: Vec<f32>,
+    pub sample_rate: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn note_names_count_octaves_from_middle_c() {
+        assert_eq!(note_name(60), "C4");
+        assert_eq!(note_name(61), "C#4");
+        assert_eq!(note_name(0), "C-1");
+        assert_eq!(note_name(127), "G9");
+    }
+
+    #[test]
+    fn steps_with_notes_follow_the_playing_bar() {
+        let mut song = Song::default();
+        song.set_slot(1, 0, Some(2));
+        song.set_slot(1, 1, Some(3));
+        song.toggle_step(2, 4, 60);
+        let state = SongState::from(&song);
+
+        assert!(state.has_note(1, 4));
+        assert!(!state.has_note(1, 5));
+        assert!(!state.has_note(0, 4));
+        assert_eq!(state.step_notes(2).count(), 1);
+        assert_eq!(state.step_notes(N_SEQUENCES as u8).count(), 0);
+    }
+}
@@ -0,0 +1,801 @@
+use log::*;
+use midi_control::MidiMessage;
+use std::sync::Arc;
+use stepper_synth::{
+    KnobCtrl, MidiControlled, SAMPLE_RATE,
+    pygame_coms::{Knob, SynthEngineType},
+    synth_engines::{Synth, SynthEngine, SynthModule, wave_table::WaveTableEngine},
+};
+use synth_common::{
+    ChannelState, EffectState, EffectType, EngineType, EnvState, ExpressionState, LPFilterState,
+    LfoState, MeterState, MidiToBackend, ModDest, N_CHANNELS, OscState, SongCmd, SongState,
+    SynthState, UiToBackend, WaveTableCmd, WaveTableState,
+};
+use synth_lib::{
+    effects::Effect,
+    mixer::Mixer,
+    modulation::Adsr,
+    notes::HeldNotes,
+    voices::Voices,
+    wavetable::{OscTable, WaveTables},
+};
+
+pub const KNOBS: [Knob; 8] = [
+    Knob::One,
+    Knob::Two,
+    Knob::Three,
+    Knob::Four,
+    Knob::Five,
+    Knob::Six,
+    Knob::Seven,
+    Knob::Eight,
+];
+
+pub fn engine_type(engine: SynthEngineType) -> EngineType {
+    match engine {
+        SynthEngineType::B3Organ => EngineType::B3Organ,
+        SynthEngineType::SubSynth => EngineType::SubSynth,
+        SynthEngineType::Wurlitzer => EngineType::Wurlitzer,
+        SynthEngineType::WaveTable => EngineType::WaveTable,
+        SynthEngineType::MidiOut => EngineType::MidiOut,
+    }
+}
+
+pub fn synth_engine_type(engine: EngineType) -> SynthEngineType {
+    match engine {
+        EngineType::B3Organ => SynthEngineType::B3Organ,
+        EngineType::SubSynth => SynthEngineType::SubSynth,
+        EngineType::Wurlitzer => SynthEngineType::Wurlitzer,
+        EngineType::WaveTable => SynthEngineType::WaveTable,
+        EngineType::MidiOut => SynthEngineType::MidiOut,
+    }
+}
+
+/// a synth with the engine's own effect slots emptied. every effect runs in the mixer so a
+/// channel's effects run in slot order.
+pub fn new_synth() -> Synth {
+    let mut synth = Synth::new();
+
+    for chan in synth.channels.iter_mut() {
+        chan.effects.iter_mut().for_each(|slot| *slot = None);
+    }
+
+    synth
+}
+
+pub fn build_effect(effect: EffectType) -> Effect {
+    Effect::new(effect.kind(), SAMPLE_RATE as f32)
+}
+
+fn effect_state(effect: &Effect, on: bool) -> EffectState {
+    let mut values = [0.0; 8];
+
+    for (value, param) in values.iter_mut().zip(effect.params()) {
+        *value = *param;
+    }
+
+    EffectState {
+        effect: effect.kind().into(),
+        on,
+        params: values,
+    }
+}
+
+/// `pitch` is how far the oscillators' detunes have been moved by `shift_pitch`, the state shows
+/// them as they were set.
+fn wave_table_state(
+    wt: &WaveTableEngine,
+    tables: &WaveTables,
+    channel: usize,
+    pitch: f32,
+) -> WaveTableState {
+    let voice = &wt.synth.voices[0];
+
+    WaveTableState {
+        oscs: voice
+            .oscs
+            .iter()
+            .enumerate()
+            .map(|(i, (osc, power))| {
+                let table = tables.osc(channel, i).unwrap_or_default();
+
+                OscState {
+                    level: osc.level,
+                    offset: osc.offset,
+                    detune: osc.detune - pitch,
+                    power: *power,
+                    table: table.table,
+                    position: table.position,
+                }
+            })
+            .collect(),
+        envs: voice
+            .envs
+            .iter()
+            .map(|env| EnvState {
+                atk: env.attack,
+                dcy: env.decay,
+                sus: env.sustain,
+                rel: env.release,
+            })
+            .collect(),
+        lfos: wt
+            .synth
+            .lfos
+            .iter()
+            .map(|lfo| 1.0 / lfo.frequency)
+            .collect(),
+        filters: voice
+            .filters
+            .iter()
+            .map(|filter| LPFilterState {
+                cutoff: filter.cutoff,
+                resonance: filter.resonance,
+                mix: filter.mix,
+                key_track: filter.key_track,
+            })
+            .collect(),
+    }
+}
+
+/// reads the state the UI shows out of the synth and mixer.
+pub fn synth_state(
+    synth: &Synth,
+    mixer: &Mixer,
+    held: &HeldNotes,
+    voices: &Voices,
+    tables: &WaveTables,
+) -> SynthState {
+    SynthState {
+        channels: std::array::from_fn(|i| {
+            let chan = &synth.channels[i];
+            let params = chan.engine.get_params();
+
+            ChannelState {
+                engine: engine_type(chan.engine_type),
+                knobs: KNOBS.map(|knob| params.get(&knob).copied().unwrap_or_default()),
+                wave_table: match chan.engine {
+                    SynthModule::WaveTable(ref wt) => {
+                        Some(wave_table_state(wt, tables, i, voices.engine_pitch(i)))
+                    }
+                    _ => None,
+                },
+                effects: std::array::from_fn(|slot| {
+                    mixer.channels[i].effects[slot]
+                        .as_ref()
+                        .map(|(effect, on)| effect_state(effect, *on))
+                }),
+                volume: mixer.channels[i].volume,
+                mute: mixer.channels[i].mute,
+                held_notes: held.held(i),
+                voices: voices.settings(i),
+                active_voices: voices.active(i),
+                bend_range: voices.expression(i).bend_range,
+                expression: ExpressionState::from(&voices.expression(i)),
+                meter: MeterState::from(&mixer.channels[i].meter),
+                mod_matrix: voices
+                    .modulation(i)
+                    .map(|modulation| modulation.matrix.clone())
+                    .unwrap_or_default(),
+            }
+            .unmodulated(
+                voices
+                    .modulation(i)
+                    .map_or(&[], |modulation| modulation.bases()),
+            )
+        }),
+        song: SongState::from(voices.song()),
+        master: MeterState::from(&mixer.master),
+        limiter: mixer.limiter,
+        lfos: LfoState::bank(voices.lfos()),
+        wave_tables: tables.list(),
+    }
+}
+
+/// gives every voice's oscillator `osc` a new cycle to play.
+fn set_osc_wave(wt: &mut WaveTableEngine, osc: usize, wave: Arc<[f32]>) {
+    for voice in wt.synth.voices.iter_mut() {
+        if let Some((osc, _)) = voice.oscs.get_mut(osc) {
+            osc.wave_table = wave.clone();
+        }
+    }
+}
+
+/// loads the tables picked for a channel's oscillators into its engine, when it is a wavetable
+/// synth.
+pub fn apply_tables(engine: &mut SynthModule, tables: &WaveTables, channel: usize) {
+    let SynthModule::WaveTable(wt) = engine else {
+        return;
+    };
+
+    for osc in 0..wt.synth.voices[0].oscs.len() {
+        if let Some(wave) = tables
+            .osc(channel, osc)
+            .and_then(|table| tables.frame(channel, osc, table.position))
+        {
+            set_osc_wave(wt, osc, wave);
+        }
+    }
+}
+
+fn apply_wave_table(
+    wt: &mut WaveTableEngine,
+    tables: &mut WaveTables,
+    channel: usize,
+    pitch: f32,
+    cmd: WaveTableCmd,
+) {
+    // every voice has the same oscillators, envelopes and filters as the first.
+    let voice = &wt.synth.voices[0];
+    let (part, index, count) = match cmd {
+        WaveTableCmd::SetOscLevel { osc, .. }
+        | WaveTableCmd::SetOscOffset { osc, .. }
+        | WaveTableCmd::SetOscDetune { osc, .. }
+        | WaveTableCmd::SetOscPower { osc, .. }
+        | WaveTableCmd::SetOscTable { osc, .. }
+        | WaveTableCmd::SetOscPosition { osc, .. } => ("oscillator", osc, voice.oscs.len()),
+        WaveTableCmd::SetEnvAtk { env, .. }
+        | WaveTableCmd::SetEnvDcy { env, .. }
+        | WaveTableCmd::SetEnvSus { env, .. }
+        | WaveTableCmd::SetEnvRel { env, .. } => ("envelope", env, voice.envs.len()),
+        WaveTableCmd::SetLfoSpeed { lfo, .. } => ("LFO", lfo, wt.synth.lfos.len()),
+        WaveTableCmd::SetFilterCutoff { filter, .. }
+        | WaveTableCmd::SetFilterResonance { filter, .. }
+        | WaveTableCmd::SetFilterMix { filter, .. }
+        | WaveTableCmd::SetFilterKeytrack { filter, .. } => {
+            ("filter", filter, voice.filters.len())
+        }
+    };
+
+    if index as usize >= count {
+        warn!("the wavetable synth has no {part} {index}");
+        return;
+    }
+
+    let voices = wt.synth.voices.iter_mut();
+
+    match cmd {
+        WaveTableCmd::SetOscLevel { osc, level } => {
+            voices.for_each(|v| v.oscs[osc as usize].0.level = level)
+        }
+        WaveTableCmd::SetOscOffset { osc, offset } => {
+            voices.for_each(|v| v.oscs[osc as usize].0.offset = offset)
+        }
+        WaveTableCmd::SetOscDetune { osc, detune } => {
+            voices.for_each(|v| v.oscs[osc as usize].0.detune = detune + pitch)
+        }
+        WaveTableCmd::SetOscPower { osc, on } => voices.for_each(|v| v.oscs[osc as usize].1 = on),
+        WaveTableCmd::SetOscTable { osc, table } => {
+            if tables.get(table).is_none() {
+                warn!("there is no wavetable {table:?}");
+                return;
+            }
+
+            let position = tables
+                .osc(channel, osc as usize)
+                .unwrap_or_default()
+                .position;
+
+            if let Some(wave) = tables.set_osc(channel, osc as usize, OscTable { table, position })
+            {
+                voices.for_each(|v| v.oscs[osc as usize].0.wave_table = wave.clone())
+            }
+        }
+        WaveTableCmd::SetOscPosition { osc, position } => {
+            let table = tables.osc(channel, osc as usize).unwrap_or_default().table;
+
+            if let Some(wave) = tables.set_osc(channel, osc as usize, OscTable { table, position })
+            {
+                voices.for_each(|v| v.oscs[osc as usize].0.wave_table = wave.clone())
+            }
+        }
+        WaveTableCmd::SetEnvAtk { env, value } => {
+            voices.for_each(|v| v.envs[env as usize].set_atk(value))
+        }
+        WaveTableCmd::SetEnvDcy { env, value } => {
+            voices.for_each(|v| v.envs[env as usize].set_decay(value))
+        }
+        WaveTableCmd::SetEnvSus { env, value } => {
+            voices.for_each(|v| v.envs[env as usize].set_sus(value))
+        }
+        WaveTableCmd::SetEnvRel { env, value } => {
+            voices.for_each(|v| v.envs[env as usize].set_release(value))
+        }
+        WaveTableCmd::SetLfoSpeed { lfo, speed } => {
+            wt.synth.lfos[lfo as usize].set_frequency(1.0 / speed)
+        }
+        WaveTableCmd::SetFilterCutoff { filter, cutoff } => {
+            voices.for_each(|v| v.filters[filter as usize].set_cutoff(cutoff))
+        }
+        WaveTableCmd::SetFilterResonance { filter, resonance } => {
+            voices.for_each(|v| v.filters[filter as usize].set_resonace(resonance))
+        }
+        WaveTableCmd::SetFilterMix { filter, mix } => {
+            voices.for_each(|v| v.filters[filter as usize].mix = mix)
+        }
+        WaveTableCmd::SetFilterKeytrack { filter, on } => {
+            voices.for_each(|v| v.filters[filter as usize].key_track = on)
+        }
+    }
+}
+
+/// the mod matrix destination a wavetable command sets, and the value it sets it to.
+fn wave_table_dest(cmd: WaveTableCmd) -> Option<(ModDest, f32)> {
+    match cmd {
+        WaveTableCmd::SetOscLevel { osc, level } => Some((ModDest::OscLevel(osc), level)),
+        WaveTableCmd::SetOscDetune { osc, detune } => Some((ModDest::OscDetune(osc), detune)),
+        WaveTableCmd::SetOscPosition { osc, position } => {
+            Some((ModDest::OscPosition(osc), position.clamp(0.0, 1.0)))
+        }
+        WaveTableCmd::SetFilterCutoff { filter, cutoff } => {
+            Some((ModDest::FilterCutoff(filter), cutoff))
+        }
+        WaveTableCmd::SetFilterResonance { filter, resonance } => {
+            Some((ModDest::FilterResonance(filter), resonance))
+        }
+        WaveTableCmd::SetFilterMix { filter, mix } => Some((ModDest::FilterMix(filter), mix)),
+        _ => None,
+    }
+}
+
+/// the current value of a mod matrix destination, `None` when the engine or effect doesn't
+/// have it. `pitch` is the engine's pitch, see `wave_table_state`.
+fn read_dest(
+    engine: &SynthModule,
+    effects: &[Option<(Effect, bool)>],
+    tables: &WaveTables,
+    channel: usize,
+    pitch: f32,
+    dest: ModDest,
+) -> Option<f32> {
+    let voice = match engine {
+        SynthModule::WaveTable(wt) => wt.synth.voices.first(),
+        _ => None,
+    };
+
+    match dest {
+        ModDest::OscLevel(osc) => Some(voice?.oscs.get(osc as usize)?.0.level),
+        ModDest::OscDetune(osc) => Some(voice?.oscs.get(osc as usize)?.0.detune - pitch),
+        ModDest::OscPosition(osc) => {
+            voice?;
+            Some(tables.osc(channel, osc as usize)?.position)
+        }
+        ModDest::FilterCutoff(filter) => Some(voice?.filters.get(filter as usize)?.cutoff),
+        ModDest::FilterResonance(filter) => Some(voice?.filters.get(filter as usize)?.resonance),
+        ModDest::FilterMix(filter) => Some(voice?.filters.get(filter as usize)?.mix),
+        ModDest::EffectParam { slot, param } => {
+            let (effect, _) = effects.get(slot as usize)?.as_ref()?;
+
+            effect.param(param as usize)
+        }
+    }
+}
+
+/// sets a mod matrix destination on every voice, destinations the engine or effect doesn't have
+/// are left alone.
+fn write_dest(
+    engine: &mut SynthModule,
+    effects: &mut [Option<(Effect, bool)>],
+    tables: &WaveTables,
+    channel: usize,
+    pitch: f32,
+    dest: ModDest,
+    value: f32,
+) {
+    if let ModDest::EffectParam { slot, param } = dest {
+        if let Some(Some((effect, _))) = effects.get_mut(slot as usize) {
+            effect.set_param(param as usize, value);
+        }
+
+        return;
+    }
+
+    let SynthModule::WaveTable(wt) = engine else {
+        return;
+    };
+
+    // the position picks the cycle out of the table, the table itself keeps where it was set.
+    if let ModDest::OscPosition(osc) = dest {
+        if let Some(wave) = tables.frame(channel, osc as usize, value) {
+            set_osc_wave(wt, osc as usize, wave);
+        }
+
+        return;
+    }
+
+    for voice in wt.synth.voices.iter_mut() {
+        match dest {
+            ModDest::OscLevel(osc) => {
+                if let Some((osc, _)) = voice.oscs.get_mut(osc as usize) {
+                    osc.level = value;
+                }
+            }
+            ModDest::OscDetune(osc) => {
+                if let Some((osc, _)) = voice.oscs.get_mut(osc as usize) {
+                    osc.detune = value + pitch;
+                }
+            }
+            ModDest::FilterCutoff(filter) => {
+                if let Some(filter) = voice.filters.get_mut(filter as usize) {
+                    filter.set_cutoff(value);
+                }
+            }
+            ModDest::FilterResonance(filter) => {
+                if let Some(filter) = voice.filters.get_mut(filter as usize) {
+                    filter.set_resonace(value);
+                }
+            }
+            ModDest::FilterMix(filter) => {
+                if let Some(filter) = voice.filters.get_mut(filter as usize) {
+                    filter.mix = value;
+                }
+            }
+            ModDest::OscPosition(_) | ModDest::EffectParam { .. } => {}
+        }
+    }
+}
+
+/// plays the song, runs the global LFOs and every channel's mod matrix for `dt` seconds at the
+/// song's tempo, and moves the params the matrices modulate.
+pub fn modulate(
+    synth: &mut Synth,
+    mixer: &mut Mixer,
+    voices: &mut Voices,
+    tables: &WaveTables,
+    dt: f32,
+) {
+    for msg in voices.tick_song(dt) {
+        synth.midi_input(&MidiMessage::from(msg.as_slice()));
+    }
+
+    voices.tick_lfos(dt);
+    voices.tick_glide(dt);
+    // the synced effects follow the same tempo as the LFOs.
+    mixer.tempo = voices.song().tempo;
+
+    for channel in 0..N_CHANNELS {
+        let pitched = matches!(synth.channels[channel].engine, SynthModule::WaveTable(_));
+
+        if voices.pitched(channel) != pitched {
+            set_pitched(synth, voices, channel);
+        }
+    }
+
+    for (i, (chan, mix)) in synth
+        .channels
+        .iter_mut()
+        .zip(mixer.channels.iter_mut())
+        .enumerate()
+    {
+        let envs: Vec<Adsr> = match chan.engine {
+            SynthModule::WaveTable(ref wt) => wt.synth.voices[0]
+                .envs
+                .iter()
+                .map(|env| Adsr {
+                    atk: env.attack,
+                    dcy: env.decay,
+                    sus: env.sustain,
+                    rel: env.release,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let pitch = voices.pitch(i);
+        shift_pitch(&mut chan.engine, pitch - voices.engine_pitch(i));
+        voices.set_engine_pitch(i, pitch);
+
+        let offsets = voices.modulate(i, dt, &envs);
+        let Some(modulation) = voices.modulation_mut(i) else {
+            continue;
+        };
+
+        // disconnected params go back to where they were set.
+        for (dest, base) in modulation.take_bases(false) {
+            write_dest(
+                &mut chan.engine,
+                &mut mix.effects,
+                tables,
+                i,
+                pitch,
+                dest,
+                base,
+            );
+        }
+
+        for (dest, offset) in offsets {
+            let base = match modulation.base(dest) {
+                Some(base) => base,
+                None => match read_dest(&chan.engine, &mix.effects, tables, i, pitch, dest) {
+                    Some(base) => {
+                        modulation.set_base(dest, base);
+                        base
+                    }
+                    None => continue,
+                },
+            };
+
+            write_dest(
+                &mut chan.engine,
+                &mut mix.effects,
+                tables,
+                i,
+                pitch,
+                dest,
+                dest.modulate(base, offset),
+            );
+        }
+    }
+}
+
+/// sets the params the matrix moves on a channel back to where they were set, before the
+/// engine or effects they belong to change.
+fn unmodulate(
+    synth: &mut Synth,
+    mixer: &mut Mixer,
+    voices: &mut Voices,
+    tables: &WaveTables,
+    channel: usize,
+) {
+    let chan = synth.get_channel_engine(channel);
+    let pitch = voices.engine_pitch(channel);
+
+    if let Some(modulation) = voices.modulation_mut(channel) {
+        for (dest, base) in modulation.take_bases(true) {
+            write_dest(
+                &mut chan.engine,
+                &mut mixer.channels[channel].effects,
+                tables,
+                channel,
+                pitch,
+                dest,
+                base,
+            );
+        }
+    }
+}
+
+/// moves a wavetable synth's pitch by `semitones`, through every oscillator's detune. the other
+/// engines are bent by MIDI.
+fn shift_pitch(engine: &mut SynthModule, semitones: f32) {
+    let SynthModule::WaveTable(wt) = engine else {
+        return;
+    };
+
+    if semitones == 0.0 {
+        return;
+    }
+
+    for voice in wt.synth.voices.iter_mut() {
+        for (osc, _) in voice.oscs.iter_mut() {
+            osc.detune += semitones;
+        }
+    }
+}
+
+/// tells the voices whether a channel's engine is a wavetable synth, which the voices glide
+/// and play legato themselves, and sends any other engine the controllers it needs for them.
+fn set_pitched(synth: &mut Synth, voices: &mut Voices, channel: usize) {
+    let pitched = matches!(synth.channels[channel].engine, SynthModule::WaveTable(_));
+
+    for msg in voices.set_pitched(channel, pitched) {
+        synth.midi_input(&MidiMessage::from(msg.as_slice()));
+    }
+}
+
+/// a param the UI set is where the matrix modulates it from.
+fn rebase(voices: &mut Voices, channel: usize, dest: ModDest, value: f32) {
+    if let Some(modulation) = voices.modulation_mut(channel)
+        && modulation.base(dest).is_some()
+    {
+        modulation.set_base(dest, value);
+    }
+}
+
+/// plays a raw MIDI message through the voice allocator.
+pub fn play(synth: &mut Synth, voices: &mut Voices, msg: &[u8]) {
+    for msg in voices.midi_input(msg) {
+        synth.midi_input(&MidiMessage::from(msg.as_slice()));
+    }
+}
+
+/// applies a UI command to the synth and mixer. it has to have passed `UiToBackend::validate`,
+/// the channel and slot numbers are used as they are.
+pub fn apply(
+    synth: &mut Synth,
+    mixer: &mut Mixer,
+    voices: &mut Voices,
+    tables: &mut WaveTables,
+    cmd: UiToBackend,
+) {
+    match cmd {
+        UiToBackend::Midi(midi) => play(synth, voices, &midi.to_bytes()),
+        UiToBackend::SetEngine { channel, engine } => {
+            unmodulate(synth, mixer, voices, tables, channel as usize);
+            // the pitch comes back out of the old engine before it goes.
+            let chan = synth.get_channel_engine(channel as usize);
+            shift_pitch(&mut chan.engine, -voices.engine_pitch(channel as usize));
+            voices.set_engine_pitch(channel as usize, 0.0);
+            synth.set_channel_engine(channel as usize, synth_engine_type(engine));
+            // a new wavetable synth plays the tables picked for the channel.
+            apply_tables(
+                &mut synth.get_channel_engine(channel as usize).engine,
+                tables,
+                channel as usize,
+            );
+            set_pitched(synth, voices, channel as usize);
+        }
+        UiToBackend::SetKnob {
+            channel,
+            knob,
+            value,
+        } => {
+            let engine = &mut synth.get_channel_engine(channel as usize).engine;
+
+            match knob {
+                0 => engine.knob_1(value),
+                1 => engine.knob_2(value),
+                2 => engine.knob_3(value),
+                3 => engine.knob_4(value),
+                4 => engine.knob_5(value),
+                5 => engine.knob_6(value),
+                6 => engine.knob_7(value),
+                _ => engine.knob_8(value),
+            };
+        }
+        UiToBackend::WaveTable { channel, cmd } => {
+            let chan = synth.get_channel_engine(channel as usize);
+
+            if let SynthModule::WaveTable(ref mut wt) = chan.engine {
+                let pitch = voices.engine_pitch(channel as usize);
+                apply_wave_table(wt, tables, channel as usize, pitch, cmd);
+
+                if let Some((dest, value)) = wave_table_dest(cmd) {
+                    rebase(voices, channel as usize, dest, value);
+                }
+            } else {
+                error!(
+                    "channel {channel} is a \"{}\". it was expected to be a \"{}\".",
+                    chan.engine_type,
+                    SynthEngineType::WaveTable
+                )
+            }
+        }
+        UiToBackend::SetEffect {
+            channel,
+            slot,
+            effect,
+        } => {
+            unmodulate(synth, mixer, voices, tables, channel as usize);
+            let slot = &mut mixer.channels[channel as usize].effects[slot as usize];
+            let current = slot.as_ref().map(|effect| effect.0.kind().into());
+
+            // picking the effect that is already there keeps its settings.
+            if current != effect {
+                *slot = effect.map(|effect| (build_effect(effect), true));
+            }
+        }
+        UiToBackend::SetEffectPower { channel, slot, on } => {
+            if let Some(ref mut effect) = mixer.channels[channel as usize].effects[slot as usize] {
+                effect.1 = on;
+            }
+        }
+        UiToBackend::SetEffectParam {
+            channel,
+            slot,
+            param,
+            value,
+        } => {
+            if let Some((ref mut effect, _)) =
+                mixer.channels[channel as usize].effects[slot as usize]
+            {
+                match effect.param(param as usize) {
+                    Some(_) => effect.set_param(param as usize, value),
+                    None => error!("{} has no param {param}", EffectType::from(effect.kind())),
+                }
+            }
+
+            rebase(
+                voices,
+                channel as usize,
+                ModDest::EffectParam { slot, param },
+                value,
+            );
+        }
+        UiToBackend::SwapEffects { channel } => {
+            unmodulate(synth, mixer, voices, tables, channel as usize);
+            mixer.channels[channel as usize].effects.swap(0, 1)
+        }
+        UiToBackend::SetVolume { channel, volume } => {
+            mixer.channels[channel as usize].volume = volume
+        }
+        UiToBackend::SetMute { channel, mute } => mixer.channels[channel as usize].mute = mute,
+        UiToBackend::SetVoices { channel, settings } => {
+            for msg in voices.set_settings(channel as usize, settings) {
+                synth.midi_input(&MidiMessage::from(msg.as_slice()));
+            }
+        }
+        UiToBackend::SetBendRange { channel, range } => {
+            for msg in voices.set_bend_range(channel as usize, range) {
+                synth.midi_input(&MidiMessage::from(msg.as_slice()));
+            }
+        }
+        UiToBackend::ModConnect { channel, slot } => {
+            if let Some(modulation) = voices.modulation_mut(channel as usize)
+                && !modulation.matrix.connect(slot)
+            {
+                warn!("channel {channel}'s mod matrix is full, {slot:?} was not connected");
+            }
+        }
+        // the param goes back to where it was set on the next modulation tick.
+        UiToBackend::SetModSlot {
+            channel,
+            index,
+            slot,
+        } => {
+            if let Some(modulation) = voices.modulation_mut(channel as usize) {
+                modulation.matrix.set(index, slot);
+            }
+        }
+        UiToBackend::ModDisconnect { channel, src, dest } => {
+            if let Some(modulation) = voices.modulation_mut(channel as usize) {
+                modulation.matrix.disconnect(src, dest);
+            }
+        }
+        UiToBackend::SetLfo { lfo, settings } => voices.set_lfo(lfo as usize, settings),
+        UiToBackend::SetLimiter(on) => mixer.limiter = on,
+        // the notes the song starts and stops are sent on the next modulation tick.
+        UiToBackend::Song(cmd) => {
+            let song = voices.song_mut();
+
+            match cmd {
+                SongCmd::SetSlot { channel, bar, seq } => {
+                    song.set_slot(channel as usize, bar as usize, seq)
+                }
+                SongCmd::SetLoop { start, end } => song.set_loop(start as usize, end as usize),
+                SongCmd::SetPatternLen(len) => song.set_pattern_len(len as usize),
+                SongCmd::SetTempo(tempo) => song.set_tempo(tempo),
+                SongCmd::SetMute { channel, mute } => song.set_mute(channel as usize, mute),
+                SongCmd::SetSolo { channel, solo } => song.set_solo(channel as usize, solo),
+                SongCmd::Play => song.play(),
+                SongCmd::Stop => song.stop(),
+                SongCmd::Record(on) => song.set_recording(on),
+                SongCmd::ClearSequence(seq) => song.clear_sequence(seq as usize),
+                SongCmd::ToggleStep { seq, step, note } => {
+                    song.toggle_step(seq as usize, step as usize, note)
+                }
+            }
+        }
+        UiToBackend::Panic => {
+            // straight to the engines, the voices may have lost track of what is stuck.
+            for channel in 0..N_CHANNELS as u8 {
+                for msg in MidiToBackend::panic(channel) {
+                    synth.midi_input(&MidiMessage::from(msg.to_bytes().as_slice()));
+                }
+            }
+
+            voices.clear();
+        }
+        // only mean something to a connection, whoever holds it handles them.
+        UiToBackend::StreamScope(_) | UiToBackend::Ping(_) => {}
+    }
+}
+
+/// sends a note off for each of `notes`, one MIDI port's notes or all of them, and takes them
+/// out of `held`.
+pub fn release_notes(
+    synth: &mut Synth,
+    held: &mut HeldNotes,
+    voices: &mut Voices,
+    notes: &HeldNotes,
+) {
+    for msg in notes.note_offs() {
+        held.midi_input(&msg);
+        play(synth, voices, &msg);
+    }
+}
+
+/// releases every held note on every channel, so nothing hangs when the notes' senders go away.
+pub fn all_notes_off(synth: &mut Synth, held: &mut HeldNotes, voices: &mut Voices) {
+    let notes = *held;
+    release_notes(synth, held, voices, &notes);
+}
@@ -0,0 +1,127 @@
+use cpal::{
+    BufferSize, Device, SampleRate, Stream, StreamConfig,
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+};
+use std::{
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use stepper_synth::{SAMPLE_RATE, SampleGen, synth_engines::Synth};
+use synth_lib::{
+    mixer::{Mixer, mono, write_frame},
+    scope::ScopeRing,
+};
+use tracing::*;
+
+/// how busy the audio callback is, written by the audio thread and read by the UI.
+#[derive(Debug, Default)]
+pub struct AudioStats {
+    /// fraction of the buffer's duration spent rendering it, stored as `f32` bits.
+    load: AtomicU32,
+    /// how many buffers took longer to render than they last.
+    xruns: AtomicUsize,
+}
+
+impl AudioStats {
+    pub fn load(&self) -> f32 {
+        f32::from_bits(self.load.load(Ordering::Relaxed))
+    }
+
+    pub fn xruns(&self) -> usize {
+        self.xruns.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.load.store(0.0_f32.to_bits(), Ordering::Relaxed);
+        self.xruns.store(0, Ordering::Relaxed);
+    }
+
+    fn record(&self, took: Duration, budget: Duration) {
+        let load = took.as_secs_f32() / budget.as_secs_f32();
+        self.load.store(load.to_bits(), Ordering::Relaxed);
+
+        if took > budget {
+            self.xruns.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// the names of the output devices that can be played through, as the audio host lists them.
+/// under pulse or pipewire these include their own devices next to the hardware.
+pub fn list_cards() -> Vec<String> {
+    let Ok(devices) = cpal::default_host().output_devices() else {
+        return Vec::new();
+    };
+
+    devices.filter_map(|device| device.name().ok()).collect()
+}
+
+/// the output device with the given name, or the host's default one.
+fn output_device(card: Option<&str>) -> Result<Device, Box<dyn std::error::Error>> {
+    let host = cpal::default_host();
+
+    match card {
+        Some(card) => host
+            .output_devices()?
+            .find(|device| device.name().is_ok_and(|name| name == card))
+            .ok_or_else(|| format!("there is no output device named {card}").into()),
+        None => host
+            .default_output_device()
+            .ok_or_else(|| "there is no default output device".into()),
+    }
+}
+
+/// starts the audio output, the synth stops playing once the returned stream is dropped.
+pub fn start_audio(
+    synth: Arc<RwLock<Synth>>,
+    mixer: Arc<RwLock<Mixer>>,
+    stats: Arc<AudioStats>,
+    scope: Arc<ScopeRing>,
+    card: Option<&str>,
+    buffer_size: usize,
+) -> Result<Stream, Box<dyn std::error::Error>> {
+    let device = output_device(card)?;
+    // the mixer is stereo, a mono device gets it mixed down and wider ones left and right in turn.
+    let channels = device.default_output_config()?.channels();
+    let config = StreamConfig {
+        channels,
+        sample_rate: SampleRate(SAMPLE_RATE as u32),
+        buffer_size: BufferSize::Fixed(buffer_size as u32),
+    };
+    let budget = Duration::from_secs_f32(buffer_size as f32 / SAMPLE_RATE as f32);
+    stats.reset();
+    let mut scope = scope.writer();
+    info!(
+        "starting audio on {:?} with a buffer of {buffer_size} samples",
+        device.name()
+    );
+
+    let stream = device.build_output_stream(
+        &config,
+        move |data: &mut [f32], _| {
+            let start = Instant::now();
+
+            for samples in data.chunks_mut(channels as usize) {
+                // NOTE: always lock the synth before the mixer, the UI thread does the same.
+                if let (Ok(mut synth), Ok(mut mixer)) = (synth.write(), mixer.write()) {
+                    // muted channels are still run so their envelopes and effect tails keep time.
+                    let frame = mixer.mix(synth.channels.iter_mut().map(|chan| chan.get_sample()));
+                    scope.push(mono(frame));
+                    write_frame(frame, samples);
+                } else {
+                    error!("failled to lock synth as mutable")
+                }
+            }
+
+            stats.record(start.elapsed(), budget);
+        },
+        |e| error!("audio output error: {e}"),
+        None,
+    )?;
+    stream.play()?;
+
+    Ok(stream)
+}
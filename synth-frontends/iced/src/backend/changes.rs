@@ -0,0 +1,98 @@
+use crate::Message;
+use futures_util::{Stream, stream};
+use std::time::Duration;
+use synth_common::{MeterState, N_CHANNELS, N_LFOS, SynthState};
+use tokio::sync::watch;
+
+/// the shortest time between two redraws caused by synth changes.
+pub const FRAME: Duration = Duration::from_millis(16);
+
+/// the parts of the state that move on their own while the synth plays. the thread running the
+/// synth publishes them, so the UI can draw them without locking the synth.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Live {
+    /// the LFO outputs, rounded like `LfoState::value`.
+    pub lfos: [f32; N_LFOS],
+    /// the song's bar and step.
+    pub position: usize,
+    pub step: usize,
+    pub meters: [MeterState; N_CHANNELS],
+    pub master: MeterState,
+}
+
+impl Live {
+    /// writes the live values over a state read earlier.
+    pub fn apply(&self, state: &mut SynthState) {
+        for (lfo, value) in state.lfos.iter_mut().zip(self.lfos) {
+            lfo.value = value;
+        }
+
+        for (chan, meter) in state.channels.iter_mut().zip(self.meters) {
+            chan.meter = meter;
+        }
+
+        state.song.position = self.position;
+        state.song.step = self.step;
+        state.master = self.master;
+    }
+}
+
+/// what the watch channel carries.
+#[derive(Debug, Clone, Copy, Default)]
+struct Pulse {
+    /// bumped by every `notify`.
+    changes: u64,
+    live: Live,
+}
+
+/// tells the UI that the synth's state changed, whoever changes the synth (the MIDI thread, the
+/// backend connection, ...) calls `notify` and the UI redraws once per frame at most.
+#[derive(Debug, Clone)]
+pub struct Changes {
+    tx: watch::Sender<Pulse>,
+}
+
+impl Default for Changes {
+    fn default() -> Self {
+        Self {
+            tx: watch::Sender::new(Pulse::default()),
+        }
+    }
+}
+
+impl Changes {
+    pub fn notify(&self) {
+        self.tx
+            .send_modify(|pulse| pulse.changes = pulse.changes.wrapping_add(1));
+    }
+
+    /// hands the UI the latest live values, it only wakes when they differ from the last ones.
+    pub fn publish(&self, live: Live) {
+        self.tx.send_if_modified(|pulse| {
+            let changed = pulse.live != live;
+            pulse.live = live;
+
+            changed
+        });
+    }
+
+    /// a message for every frame in which the synth changed. a `Message::Refresh` when the
+    /// state has to be read again, a `Message::Live` when only the live values moved.
+    pub fn refreshes(&self) -> impl Stream<Item = Message> + use<> {
+        let seen = self.tx.borrow().changes;
+
+        stream::unfold((self.tx.subscribe(), seen), |(mut rx, seen)| async move {
+            rx.changed().await.ok()?;
+            // lets a burst of changes (like a CC sweep) land before redrawing.
+            tokio::time::sleep(FRAME).await;
+            let pulse = *rx.borrow_and_update();
+            let message = if pulse.changes != seen {
+                Message::Refresh
+            } else {
+                Message::Live(pulse.live)
+            };
+
+            Some((message, (rx, pulse.changes)))
+        })
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    audio::{AudioStats, start_audio},
+    backend::{Changes, Live},
+    midi::{MidiRouting, run_midi},
+    settings::Config,
+};
+use cpal::Stream;
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+    thread::{JoinHandle, sleep, spawn},
+    time::{Duration, Instant},
+};
+use stepper_synth::{SAMPLE_RATE, synth_engines::Synth};
+use synth_common::{
+    LfoState, MeterState, N_CHANNELS, ScopeFrame, SynthState, TableChoice, UiToBackend, load_song,
+    save_song, wave_table_dir,
+};
+use synth_engine::{apply, apply_tables, modulate, new_synth, synth_state};
+use synth_lib::{
+    mixer::Mixer,
+    notes::HeldNotes,
+    scope::{SCOPE_LEN, ScopeRing},
+    voices::Voices,
+    wavetable::{TableError, WaveTables},
+};
+use tracing::*;
+
+/// how often the mod matrices move the params they modulate.
+const MOD_INTERVAL: Duration = Duration::from_millis(5);
+
+/// runs the mod matrices for as long as the synth exists, and publishes the LFOs, the song's
+/// playhead and the meters to the UI while the synth is locked anyway.
+fn run_modulation(
+    synth: Arc<RwLock<Synth>>,
+    mixer: Arc<RwLock<Mixer>>,
+    voices: Arc<RwLock<Voices>>,
+    tables: Arc<RwLock<WaveTables>>,
+    changes: Changes,
+) {
+    let mut last = Instant::now();
+
+    loop {
+        sleep(MOD_INTERVAL);
+        let now = Instant::now();
+
+        // NOTE: always lock the synth before the mixer, the mixer before the voices, and the
+        // voices before the tables.
+        if let (Ok(mut synth), Ok(mut mixer), Ok(mut voices), Ok(tables)) =
+            (synth.write(), mixer.write(), voices.write(), tables.read())
+        {
+            modulate(
+                &mut synth,
+                &mut mixer,
+                &mut voices,
+                &tables,
+                (now - last).as_secs_f32(),
+            );
+
+            let song = voices.song();
+            changes.publish(Live {
+                lfos: LfoState::bank(voices.lfos()).map(|lfo| lfo.value),
+                position: song.position,
+                step: song.step,
+                meters: std::array::from_fn(|i| MeterState::from(&mixer.channels[i].meter)),
+                master: MeterState::from(&mixer.master),
+            });
+        }
+
+        last = now;
+    }
+}
+
+/// a synth running in this process, with its own audio output and MIDI inputs.
+pub struct LocalSynth {
+    /// the state of the synth
+    pub synth: Arc<RwLock<Synth>>,
+    /// per channel volume and mute, applied when the channels are summed.
+    pub mixer: Arc<RwLock<Mixer>>,
+    /// CPU load and xruns of the audio callback.
+    pub stats: Arc<AudioStats>,
+    /// connected MIDI inputs and how they are routed.
+    pub routing: Arc<RwLock<MidiRouting>>,
+    /// the notes held on each channel, for the activity indicators.
+    pub held: Arc<RwLock<HeldNotes>>,
+    /// which notes are sounding, within each channel's polyphony.
+    pub voices: Arc<RwLock<Voices>>,
+    /// the built in and imported wavetables, and which ones the oscillators play.
+    pub tables: Arc<RwLock<WaveTables>>,
+    pub changes: Changes,
+    /// the decimated output, written by the audio callback.
+    pub scope: Arc<ScopeRing>,
+    /// audio device
+    device: Result<Stream, Box<dyn std::error::Error>>,
+    _midi_jh: JoinHandle<()>,
+    _mod_jh: JoinHandle<()>,
+}
+
+impl LocalSynth {
+    pub fn new(config: &Config) -> Self {
+        let synth = Arc::new(RwLock::new(new_synth()));
+        let mixer = Arc::new(RwLock::new(Mixer::default()));
+        let stats = Arc::new(AudioStats::default());
+        let routing = Arc::new(RwLock::new(MidiRouting {
+            ports: config.midi_ports.clone(),
+            ..Default::default()
+        }));
+        let held = Arc::new(RwLock::new(HeldNotes::default()));
+        let mut voices = Voices::default();
+
+        match load_song() {
+            Ok(Some(song)) => *voices.song_mut() = song,
+            Ok(None) => {}
+            Err(e) => warn!("the saved song was not loaded. {e}"),
+        }
+
+        let voices = Arc::new(RwLock::new(voices));
+        let (tables, problems) = WaveTables::load(&wave_table_dir());
+
+        for (path, e) in problems {
+            warn!("the wavetable {} was not loaded. {e}", path.display());
+        }
+
+        // engines that start as wavetable synths play the picked tables too.
+        if let Ok(mut synth) = synth.write() {
+            for channel in 0..N_CHANNELS {
+                apply_tables(
+                    &mut synth.get_channel_engine(channel).engine,
+                    &tables,
+                    channel,
+                );
+            }
+        }
+
+        let tables = Arc::new(RwLock::new(tables));
+        let changes = Changes::default();
+        let scope = Arc::new(ScopeRing::default());
+        // NOTE: must stay in this thread so that it stays in scope
+        let device = start_audio(
+            synth.clone(),
+            mixer.clone(),
+            stats.clone(),
+            scope.clone(),
+            config.audio_card.as_deref(),
+            config.buffer_size,
+        );
+
+        if let Err(ref e) = device {
+            error!("starting audio playback caused error: {e}");
+        }
+
+        let _midi_jh = spawn({
+            let synth = synth.clone();
+            let routing = routing.clone();
+            let held = held.clone();
+            let voices = voices.clone();
+            let changes = changes.clone();
+
+            move || {
+                if let Err(e) = run_midi(synth, routing, held, voices, changes) {
+                    error!("{e}");
+                }
+            }
+        });
+
+        let _mod_jh = spawn({
+            let synth = synth.clone();
+            let mixer = mixer.clone();
+            let voices = voices.clone();
+            let tables = tables.clone();
+            let changes = changes.clone();
+
+            move || run_modulation(synth, mixer, voices, tables, changes)
+        });
+
+        Self {
+            synth,
+            mixer,
+            stats,
+            routing,
+            held,
+            voices,
+            tables,
+            changes,
+            scope,
+            device,
+            _midi_jh,
+            _mod_jh,
+        }
+    }
+
+    /// reopens the audio device with the card and buffer size from the config.
+    pub fn restart_audio(&mut self, config: &Config) {
+        // the old device has to be closed before the new one can open the card.
+        self.device = Err("restarting".into());
+        self.device = start_audio(
+            self.synth.clone(),
+            self.mixer.clone(),
+            self.stats.clone(),
+            self.scope.clone(),
+            config.audio_card.as_deref(),
+            config.buffer_size,
+        );
+
+        if let Err(ref e) = self.device {
+            error!("restarting audio playback caused error: {e}");
+        }
+    }
+
+    pub fn state(&self) -> SynthState {
+        // NOTE: always lock the synth before the mixer, the audio thread does the same.
+        if let (Ok(synth), Ok(mixer), Ok(held), Ok(voices), Ok(tables)) = (
+            self.synth.read(),
+            self.mixer.read(),
+            self.held.read(),
+            self.voices.read(),
+            self.tables.read(),
+        ) {
+            synth_state(&synth, &mixer, &held, &voices, &tables)
+        } else {
+            error!("failed to lock synth with read access.");
+            SynthState::default()
+        }
+    }
+
+    pub fn scope(&self) -> ScopeFrame {
+        ScopeFrame {
+            samples: self.scope.latest(SCOPE_LEN),
+            sample_rate: ScopeRing::sample_rate(SAMPLE_RATE as f32),
+        }
+    }
+
+    /// applies a command. commands with channels, slots or MIDI data the synth doesn't have are
+    /// refused before anything is locked.
+    pub fn send(&self, cmd: UiToBackend) {
+        if let Err(e) = cmd.validate() {
+            warn!("ignoring {cmd:?}. {e}");
+            return;
+        }
+
+        if let Ok(mut held) = self.held.write() {
+            match cmd {
+                UiToBackend::Midi(midi) => held.midi_input(&midi.to_bytes()),
+                UiToBackend::Panic => *held = HeldNotes::default(),
+                _ => {}
+            }
+        }
+
+        let song = if let (Ok(mut synth), Ok(mut mixer), Ok(mut voices), Ok(mut tables)) = (
+            self.synth.write(),
+            self.mixer.write(),
+            self.voices.write(),
+            self.tables.write(),
+        ) {
+            apply(&mut synth, &mut mixer, &mut voices, &mut tables, cmd);
+            self.changes.notify();
+
+            matches!(cmd, UiToBackend::Song(_)).then(|| voices.song().clone())
+        } else {
+            error!("failed to lock synth with write access.");
+            None
+        };
+
+        // the song is saved whenever it is edited, once the synth is let go.
+        if let Some(song) = song
+            && let Err(e) = save_song(&song)
+        {
+            warn!("failed to save the song. {e}");
+        }
+    }
+
+    /// reads a WAV file into a user wavetable named after the file, and keeps a copy with the
+    /// other imported tables.
+    pub fn import_wave_table(&self, path: &Path) -> Result<TableChoice, TableError> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let wav = fs::read(path).map_err(|e| TableError::Io(e.to_string()))?;
+        let choice = self
+            .tables
+            .write()
+            .map_err(|e| TableError::Io(e.to_string()))?
+            .import(&name, &wav)?;
+        self.changes.notify();
+
+        Ok(choice)
+    }
+}
@@ -0,0 +1,49 @@
+use synth_common::{ScopeFrame, SynthState, UiToBackend};
+
+pub use changes::*;
+pub use local::*;
+pub use remote::*;
+
+pub mod changes;
+pub mod local;
+pub mod remote;
+
+/// where the synth the UI controls runs.
+pub enum Backend {
+    /// in this process.
+    Local(LocalSynth),
+    /// in the synth backend, reached over its websocket.
+    Remote(RemoteSynth),
+}
+
+impl Backend {
+    pub fn state(&self) -> SynthState {
+        match self {
+            Self::Local(local) => local.state(),
+            Self::Remote(remote) => remote.state(),
+        }
+    }
+
+    /// notified whenever the synth's state changes.
+    pub fn changes(&self) -> &Changes {
+        match self {
+            Self::Local(local) => &local.changes,
+            Self::Remote(remote) => remote.changes(),
+        }
+    }
+
+    /// the latest stretch of the synth's output.
+    pub fn scope(&self) -> ScopeFrame {
+        match self {
+            Self::Local(local) => local.scope(),
+            Self::Remote(remote) => remote.scope(),
+        }
+    }
+
+    pub fn send(&self, cmd: UiToBackend) {
+        match self {
+            Self::Local(local) => local.send(cmd),
+            Self::Remote(remote) => remote.send(cmd),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::backend::Changes;
+use futures_util::{SinkExt, StreamExt};
+use std::{
+    fmt::Display,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{JoinHandle, spawn},
+    time::Duration,
+};
+use synth_common::{BackendToUi, ScopeFrame, SynthState, UiToBackend, socket_path};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpStream, UnixStream},
+    sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, client_async, connect_async,
+    tungstenite::{Message as WsMessage, client::IntoClientRequest, http::header::AUTHORIZATION},
+};
+use tracing::*;
+
+/// the environment variable holding the backend's auth token.
+pub const TOKEN_VAR: &str = "SYNTH_TOKEN";
+
+/// where the synth backend listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteAddr {
+    Unix(PathBuf),
+    /// a `host:port` pair.
+    Tcp(String),
+}
+
+impl Default for RemoteAddr {
+    /// the backend's unix socket, found the same way the backend picks it.
+    fn default() -> Self {
+        Self::Unix(socket_path())
+    }
+}
+
+impl FromStr for RemoteAddr {
+    type Err = std::convert::Infallible;
+
+    /// anything that looks like a path is a unix socket, everything else is `host:port`.
+    fn from_str(addr: &str) -> Result<Self, Self::Err> {
+        Ok(if let Some(path) = addr.strip_prefix("unix:") {
+            Self::Unix(path.into())
+        } else if addr.contains('/') {
+            Self::Unix(addr.into())
+        } else {
+            Self::Tcp(addr.into())
+        })
+    }
+}
+
+impl Display for RemoteAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::Tcp(addr) => write!(f, "{addr}"),
+        }
+    }
+}
+
+/// what the connection thread shares with the UI.
+#[derive(Debug, Default)]
+struct Shared {
+    /// the last state received from the backend.
+    state: RwLock<SynthState>,
+    /// the last scope frame received from the backend.
+    scope: RwLock<ScopeFrame>,
+    connected: AtomicBool,
+    /// whether the backend should stream scope frames.
+    scope_wanted: AtomicBool,
+    changes: Changes,
+}
+
+/// a synth running in the backend, the UI renders the state the backend streams over its `/ws`
+/// endpoint and sends its commands back over the same socket.
+pub struct RemoteSynth {
+    pub addr: RemoteAddr,
+    shared: Arc<Shared>,
+    commands: UnboundedSender<UiToBackend>,
+    _jh: JoinHandle<()>,
+}
+
+impl RemoteSynth {
+    pub fn new(addr: RemoteAddr) -> Self {
+        let shared = Arc::new(Shared::default());
+        let (commands, rx) = unbounded_channel();
+
+        let _jh = spawn({
+            let addr = addr.clone();
+            let shared = shared.clone();
+
+            move || {
+                if let Err(e) = run_remote(addr, shared, rx) {
+                    error!("{e}");
+                }
+            }
+        });
+
+        Self {
+            addr,
+            shared,
+            commands,
+            _jh,
+        }
+    }
+
+    pub fn connected(&self) -> bool {
+        self.shared.connected.load(Ordering::Relaxed)
+    }
+
+    pub fn changes(&self) -> &Changes {
+        &self.shared.changes
+    }
+
+    pub fn state(&self) -> SynthState {
+        self.shared
+            .state
+            .read()
+            .map(|state| state.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn scope(&self) -> ScopeFrame {
+        self.shared
+            .scope
+            .read()
+            .map(|scope| scope.clone())
+            .unwrap_or_default()
+    }
+
+    /// asks the backend to start or stop streaming scope frames, kept across reconnects.
+    pub fn stream_scope(&self, on: bool) {
+        if self.shared.scope_wanted.swap(on, Ordering::Relaxed) != on && self.connected() {
+            self.send(UiToBackend::StreamScope(on));
+        }
+    }
+
+    pub fn send(&self, cmd: UiToBackend) {
+        if !self.connected() {
+            warn!(
+                "not connected to the backend at {}, dropping {cmd:?}",
+                self.addr
+            );
+            return;
+        }
+
+        if self.commands.send(cmd).is_err() {
+            error!("the backend connection thread has stopped");
+        }
+    }
+}
+
+/// keeps a connection to the backend open, reconnecting every second when it drops.
+fn run_remote(
+    addr: RemoteAddr,
+    shared: Arc<Shared>,
+    mut commands: UnboundedReceiver<UiToBackend>,
+) -> anyhow::Result<()> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    rt.block_on(async move {
+        loop {
+            let res = match addr {
+                RemoteAddr::Unix(ref path) => match UnixStream::connect(path).await {
+                    Ok(stream) => match client_async("ws://localhost/ws", stream).await {
+                        Ok((ws, _)) => session(ws, &addr, &shared, &mut commands).await,
+                        Err(e) => Err(e.into()),
+                    },
+                    Err(e) => Err(e.into()),
+                },
+                RemoteAddr::Tcp(ref host) => match connect_tcp(host).await {
+                    Ok(ws) => session(ws, &addr, &shared, &mut commands).await,
+                    Err(e) => Err(e),
+                },
+            };
+
+            if shared.connected.swap(false, Ordering::Relaxed) {
+                shared.changes.notify();
+            }
+
+            if let Err(e) = res {
+                debug!("backend connection to {addr} failed. {e}");
+            }
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    })
+}
+
+/// connects over the network, sending the token in `$SYNTH_TOKEN` when the backend wants auth.
+/// the unix socket is trusted and needs no token.
+async fn connect_tcp(host: &str) -> anyhow::Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    let mut request = format!("ws://{host}/ws").into_client_request()?;
+
+    if let Ok(token) = std::env::var(TOKEN_VAR) {
+        request
+            .headers_mut()
+            .insert(AUTHORIZATION, format!("Bearer {token}").parse()?);
+    }
+
+    let (ws, _) = connect_async(request).await?;
+
+    Ok(ws)
+}
+
+async fn session<S>(
+    ws: WebSocketStream<S>,
+    addr: &RemoteAddr,
+    shared: &Shared,
+    commands: &mut UnboundedReceiver<UiToBackend>,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut tx, mut rx) = ws.split();
+    info!("connected to the backend at {addr}");
+    shared.connected.store(true, Ordering::Relaxed);
+    shared.changes.notify();
+
+    // anything sent while disconnected was made against an old state.
+    while commands.try_recv().is_ok() {}
+
+    if shared.scope_wanted.load(Ordering::Relaxed) {
+        let cmd = UiToBackend::StreamScope(true);
+        tx.send(WsMessage::text(serde_json::to_string(&cmd)?))
+            .await?;
+    }
+
+    loop {
+        tokio::select! {
+            msg = rx.next() => match msg {
+                Some(Ok(WsMessage::Text(text))) => match serde_json::from_str(text.as_str())? {
+                    BackendToUi::State(new_state) => {
+                        if let Ok(mut state) = shared.state.write() {
+                            *state = *new_state;
+                        }
+
+                        shared.changes.notify();
+                    }
+                    // the scope screen redraws on its own timer.
+                    BackendToUi::Scope(frame) => {
+                        if let Ok(mut scope) = shared.scope.write() {
+                            *scope = frame;
+                        }
+                    }
+                    // this UI never pings.
+                    BackendToUi::Pong(_) => {}
+                },
+                Some(Ok(WsMessage::Close(_))) | None => {
+                    info!("the backend at {addr} closed the connection");
+                    return Ok(());
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+            },
+            cmd = commands.recv() => match cmd {
+                Some(cmd) => tx.send(WsMessage::text(serde_json::to_string(&cmd)?)).await?,
+                None => return Ok(()),
+            },
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{ChannelMessage, Message, channel::wave_table::param_slider, helpers::IndexLessThan};
+use iced::widget::{Column, column, row, text, toggler};
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::ChannelState;
+
+/// the params of the effects in the channel's two slots, empty slots are left out.
+pub fn effects_panel<'a>(chan: &ChannelState, channel: SequenceChannel) -> Column<'a, Message> {
+    let send = move |message| Message::ChannelMsg { channel, message };
+
+    let slots = IndexLessThan::<2>::all().filter_map(|slot| {
+        let effect = chan.effects[*slot]?;
+        let params = effect
+            .effect
+            .param_names()
+            .iter()
+            .enumerate()
+            .map(|(param, name)| {
+                param_slider(name, 0.0..=1.0, effect.params[param], move |value| {
+                    send(ChannelMessage::SetEffectParam {
+                        slot,
+                        param: param as u8,
+                        value,
+                    })
+                })
+                .into()
+            });
+
+        Some(
+            column![
+                row![
+                    text(format!("{}", effect.effect)),
+                    toggler(effect.on)
+                        .on_toggle(move |on| send(ChannelMessage::SetEffectPower { slot, on })),
+                ]
+                .spacing(10),
+                Column::with_children(params).spacing(5),
+            ]
+            .spacing(5)
+            .into(),
+        )
+    });
+
+    Column::with_children(slots).spacing(10)
+}
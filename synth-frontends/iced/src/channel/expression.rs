@@ -0,0 +1,52 @@
+use crate::{ChannelMessage, Message, channel::wave_table::param_slider};
+use iced::{
+    Length::Fill,
+    widget::{Column, Row, column, progress_bar, row, text},
+};
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::{BendRange, ChannelState, MAX_BEND_RANGE};
+
+/// a labeled bar for one of the expressive controllers.
+fn controller<'a>(
+    lable: &'a str,
+    range: std::ops::RangeInclusive<f32>,
+    value: f32,
+) -> Row<'a, Message> {
+    row![
+        text(lable).width(80),
+        progress_bar(range, value).height(8).width(Fill),
+        text(format!("{value:.2}")).width(60),
+    ]
+    .spacing(10)
+}
+
+/// the channel's bend range, and where its pitch bend, mod wheel and aftertouch are.
+pub fn expression_panel<'a>(chan: &ChannelState, channel: SequenceChannel) -> Column<'a, Message> {
+    let range = chan.bend_range;
+    let expression = chan.expression;
+    let send = move |range: BendRange| Message::ChannelMsg {
+        channel,
+        message: ChannelMessage::SetBendRange(range),
+    };
+    let semitones = 0.0..=MAX_BEND_RANGE as f32;
+
+    column![
+        param_slider("Bend Up", semitones.clone(), range.up as f32, move |up| {
+            send(BendRange {
+                up: up.round() as u8,
+                ..range
+            })
+        }),
+        param_slider("Bend Down", semitones, range.down as f32, move |down| {
+            send(BendRange {
+                down: down.round() as u8,
+                ..range
+            })
+        }),
+        controller("Bend", -1.0..=1.0, expression.bend),
+        controller("Mod Wheel", 0.0..=1.0, expression.mod_wheel),
+        controller("Pressure", 0.0..=1.0, expression.pressure),
+        controller("Poly AT", 0.0..=1.0, expression.poly_pressure),
+    ]
+    .spacing(5)
+}
@@ -0,0 +1,31 @@
+use crate::{ChannelMessage, Message, channel::wave_table::param_slider, helpers::IndexLessThan};
+use iced::widget::{Column, column, text};
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::EngineType;
+
+/// a panel of sliders for engines that are controlled through their eight knobs.
+pub fn knob_panel<'a>(
+    engine: EngineType,
+    knobs: &[f32; 8],
+    channel: SequenceChannel,
+) -> Column<'a, Message> {
+    let sliders = engine
+        .knob_names()
+        .iter()
+        .zip(IndexLessThan::<8>::all())
+        .map(|(name, knob)| {
+            param_slider(name, 0.0..=1.0, knobs[*knob], move |value| {
+                Message::ChannelMsg {
+                    channel,
+                    message: ChannelMessage::SetKnob { knob, value },
+                }
+            })
+            .into()
+        });
+
+    column![
+        text(engine.to_string()),
+        Column::with_children(sliders).spacing(5)
+    ]
+    .spacing(10)
+}
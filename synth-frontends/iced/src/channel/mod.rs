@@ -0,0 +1,54 @@
+use crate::{
+    Message,
+    patches::{PatchFiles, patch_panel},
+};
+use effects::effects_panel;
+use expression::expression_panel;
+use iced::{
+    Length::Fill,
+    widget::{Column, column, text},
+};
+use knobs::knob_panel;
+use mod_matrix::mod_matrix_panel;
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::{ChannelState, EngineType, TableInfo};
+use voices::voice_panel;
+use wave_table::wave_table_panel;
+
+pub mod effects;
+pub mod expression;
+pub mod knobs;
+pub mod mod_matrix;
+pub mod voices;
+pub mod wave_table;
+
+/// the editor for the sound engine loaded on one channel.
+pub fn channel_screen<'a>(
+    chan: &ChannelState,
+    channel: SequenceChannel,
+    tables: &[TableInfo],
+    table_path: &str,
+    patches: &PatchFiles,
+) -> Column<'a, Message> {
+    let panel = match (chan.engine, &chan.wave_table) {
+        (EngineType::WaveTable, Some(wt)) => wave_table_panel(wt, tables, table_path, channel),
+        (engine @ (EngineType::B3Organ | EngineType::SubSynth | EngineType::Wurlitzer), _) => {
+            knob_panel(engine, &chan.knobs, channel)
+        }
+        (engine, _) => column![text(format!("{engine} has no editable parameters"))],
+    };
+
+    column![
+        text(format!("Channel {channel:?}")).size(24),
+        patch_panel(channel, patches),
+        voice_panel(chan, channel),
+        expression_panel(chan, channel),
+        mod_matrix_panel(chan, channel),
+        panel,
+        effects_panel(chan, channel),
+    ]
+    .width(Fill)
+    .height(Fill)
+    .spacing(10)
+    .padding(10)
+}
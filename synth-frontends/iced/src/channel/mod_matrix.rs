@@ -0,0 +1,63 @@
+use crate::{ChannelMessage, Message};
+use iced::{
+    Length::Fill,
+    widget::{Column, button, column, pick_list, row, slider, text},
+};
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::{ChannelState, MAX_MOD_SLOTS, ModSlot, ModSrc};
+
+/// the channel's mod matrix, a row per connection with a bipolar amount.
+pub fn mod_matrix_panel<'a>(chan: &ChannelState, channel: SequenceChannel) -> Column<'a, Message> {
+    let send = move |message: ChannelMessage| Message::ChannelMsg { channel, message };
+    let dests = chan.mod_dests();
+    let slots = &chan.mod_matrix.slots;
+
+    let rows = slots.iter().copied().enumerate().map(|(index, slot)| {
+        let set = move |slot: ModSlot| send(ChannelMessage::SetModSlot { index, slot });
+
+        row![
+            pick_list(ModSrc::all(), Some(slot.src), move |src| set(ModSlot {
+                src,
+                ..slot
+            }))
+            .width(120),
+            pick_list(dests.clone(), Some(slot.dest), move |dest| set(ModSlot {
+                dest,
+                ..slot
+            }))
+            .width(160),
+            slider(-1.0..=1.0, slot.amount, move |amount| set(ModSlot {
+                amount,
+                ..slot
+            }))
+            .step(0.01)
+            .width(Fill),
+            text(format!("{:+.2}", slot.amount)).width(60),
+            button(text("X").center()).on_press(send(ChannelMessage::ModDisconnect {
+                src: slot.src,
+                dest: slot.dest,
+            })),
+        ]
+        .spacing(10)
+        .into()
+    });
+
+    // new connections start at no amount, to be rewired and turned up from their row.
+    let add = button(text("Add").center()).on_press_maybe(
+        (slots.len() < MAX_MOD_SLOTS)
+            .then(|| chan.mod_matrix.unused(&dests))
+            .flatten()
+            .map(|slot| send(ChannelMessage::ModConnect(slot))),
+    );
+
+    column![
+        row![
+            text("Mod Matrix"),
+            text(format!("{} / {MAX_MOD_SLOTS}", slots.len())),
+            add
+        ]
+        .spacing(10),
+        Column::with_children(rows).spacing(5),
+    ]
+    .spacing(5)
+}
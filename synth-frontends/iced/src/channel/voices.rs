@@ -0,0 +1,53 @@
+use crate::{ChannelMessage, Message, channel::wave_table::param_slider};
+use iced::widget::{Column, column, pick_list, row, text};
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::{ChannelState, MAX_GLIDE, MAX_POLYPHONY, Steal, VoiceMode, VoiceSettings};
+
+/// the channel's polyphony, voice stealing, mono modes and glide.
+pub fn voice_panel<'a>(chan: &ChannelState, channel: SequenceChannel) -> Column<'a, Message> {
+    let settings = chan.voices;
+    let send = move |settings: VoiceSettings| Message::ChannelMsg {
+        channel,
+        message: ChannelMessage::SetVoices(settings),
+    };
+    let poly = settings.mode == VoiceMode::Poly;
+
+    let mode = pick_list(VoiceMode::ALL, Some(settings.mode), move |mode| {
+        send(VoiceSettings { mode, ..settings })
+    });
+    let steal = pick_list(Steal::ALL, Some(settings.steal), move |steal| {
+        send(VoiceSettings { steal, ..settings })
+    });
+    let polyphony = param_slider(
+        "Voices",
+        1.0..=MAX_POLYPHONY as f32,
+        settings.polyphony as f32,
+        move |polyphony| {
+            send(VoiceSettings {
+                polyphony: polyphony.round() as u8,
+                ..settings
+            })
+        },
+    );
+    // glide only slides between the notes of the mono modes.
+    let glide = param_slider("Glide", 0.0..=MAX_GLIDE, settings.glide, move |glide| {
+        send(VoiceSettings { glide, ..settings })
+    });
+
+    column![
+        row![
+            text("Voices"),
+            mode,
+            text("Steal"),
+            steal,
+            text(format!(
+                "{} / {} sounding",
+                chan.active_voices,
+                if poly { settings.polyphony } else { 1 }
+            )),
+        ]
+        .spacing(10),
+        if poly { polyphony } else { glide },
+    ]
+    .spacing(5)
+}
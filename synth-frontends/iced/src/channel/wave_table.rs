@@ -0,0 +1,164 @@
+use crate::{
+    ChannelMessage, Message, WaveTableEnvMessage, WaveTableLPFilterMessage, WaveTableLfoMessage,
+    WaveTableMessage, WaveTableOscMessage, helpers::IndexLessThan,
+};
+use iced::{
+    Length::Fill,
+    widget::{Column, Row, button, column, pick_list, row, slider, text, text_input, toggler},
+};
+use stepper_synth::{
+    sequencer::SequenceChannel,
+    synth_engines::wave_table::wavetable_synth::config::{N_ENV, N_LFO, N_OSC},
+};
+use synth_common::{TableInfo, WaveTableState};
+
+/// a labeled horizontal slider.
+pub fn param_slider<'a>(
+    lable: &'a str,
+    range: std::ops::RangeInclusive<f32>,
+    value: f32,
+    on_change: impl Fn(f32) -> Message + 'a,
+) -> Row<'a, Message> {
+    row![
+        text(lable).width(80),
+        slider(range, value, on_change).step(0.01).width(Fill),
+        text(format!("{value:.2}")).width(60),
+    ]
+    .spacing(10)
+}
+
+pub fn wave_table_panel<'a>(
+    params: &WaveTableState,
+    tables: &[TableInfo],
+    table_path: &str,
+    channel: SequenceChannel,
+) -> Column<'a, Message> {
+    let send = move |msg: WaveTableMessage| Message::ChannelMsg {
+        channel,
+        message: ChannelMessage::WaveTableMessage(msg),
+    };
+
+    let oscs = IndexLessThan::<N_OSC>::all().map(|osc| {
+        let p = params.oscs.get(*osc).copied().unwrap_or_default();
+        let osc_msg = move |msg| send(WaveTableMessage::Osc { osc, msg });
+        let table = tables.iter().find(|table| table.choice == p.table).cloned();
+
+        column![
+            row![
+                text(format!("Osc {}", *osc + 1)).width(Fill),
+                toggler(p.power).on_toggle(move |on| osc_msg(WaveTableOscMessage::SetPower(on))),
+            ],
+            row![
+                text("Table").width(80),
+                pick_list(tables.to_vec(), table, move |table: TableInfo| {
+                    osc_msg(WaveTableOscMessage::SetTable(table.choice))
+                })
+                .width(Fill),
+            ]
+            .spacing(10),
+            param_slider("Position", 0.0..=1.0, p.position, move |v| {
+                osc_msg(WaveTableOscMessage::SetPosition(v))
+            }),
+            param_slider("Level", 0.0..=1.0, p.level, move |v| {
+                osc_msg(WaveTableOscMessage::SetLevel(v))
+            }),
+            param_slider("Detune", -1.0..=1.0, p.detune, move |v| {
+                osc_msg(WaveTableOscMessage::SetDetune(v))
+            }),
+            param_slider("Offset", -24.0..=24.0, p.offset as f32, move |v| {
+                osc_msg(WaveTableOscMessage::SetOffset(v.round() as i16))
+            }),
+        ]
+        .spacing(2)
+        .into()
+    });
+
+    let envs = IndexLessThan::<N_ENV>::all().map(|env| {
+        let p = params.envs.get(*env).copied().unwrap_or_default();
+        let env_msg = move |msg| send(WaveTableMessage::Env { env, msg });
+
+        column![
+            text(format!("Env {}", *env + 1)),
+            param_slider("Attack", 0.0..=2.0, p.atk, move |v| {
+                env_msg(WaveTableEnvMessage::SetAtk(v))
+            }),
+            param_slider("Decay", 0.0..=2.0, p.dcy, move |v| {
+                env_msg(WaveTableEnvMessage::SetDcy(v))
+            }),
+            param_slider("Sustain", 0.0..=1.0, p.sus, move |v| {
+                env_msg(WaveTableEnvMessage::SetSus(v))
+            }),
+            param_slider("Release", 0.0..=2.0, p.rel, move |v| {
+                env_msg(WaveTableEnvMessage::SetRel(v))
+            }),
+        ]
+        .spacing(2)
+        .into()
+    });
+
+    let lfos = IndexLessThan::<N_LFO>::all().map(|lfo| {
+        param_slider(
+            "Speed",
+            0.01..=10.0,
+            params.lfos.get(*lfo).copied().unwrap_or_default(),
+            move |v| {
+                send(WaveTableMessage::Lfo {
+                    lfo,
+                    msg: WaveTableLfoMessage::SetSpeed(v),
+                })
+            },
+        )
+        .into()
+    });
+
+    let filters = IndexLessThan::<2>::all().map(|filter| {
+        let p = params.filters.get(*filter).copied().unwrap_or_default();
+        let filter_msg = move |msg| send(WaveTableMessage::LPFilter { filter, msg });
+
+        column![
+            row![
+                text(format!("Filter {}", *filter + 1)).width(Fill),
+                toggler(p.key_track)
+                    .label("Key track")
+                    .on_toggle(move |on| filter_msg(WaveTableLPFilterMessage::SetKeytrack(on))),
+            ],
+            param_slider("Cutoff", 20.0..=20_000.0, p.cutoff, move |v| {
+                filter_msg(WaveTableLPFilterMessage::SetCutoff(v))
+            }),
+            param_slider("Res", 0.0..=1.0, p.resonance, move |v| {
+                filter_msg(WaveTableLPFilterMessage::SetResonance(v))
+            }),
+            param_slider("Mix", 0.0..=1.0, p.mix, move |v| {
+                filter_msg(WaveTableLPFilterMessage::SetMix(v))
+            }),
+        ]
+        .spacing(2)
+        .into()
+    });
+
+    // the file is read by the synth, so only a local one can import.
+    let import = row![
+        text_input("path to a .wav file", table_path)
+            .on_input(Message::WaveTablePath)
+            .width(Fill),
+        button(text("Import").center())
+            .on_press_maybe((!table_path.trim().is_empty()).then_some(Message::ImportWaveTable)),
+    ]
+    .spacing(10);
+
+    column![
+        text("Wave Table"),
+        import,
+        row![
+            Column::with_children(oscs).spacing(10).width(Fill),
+            Column::with_children(envs).spacing(10).width(Fill),
+        ]
+        .spacing(20),
+        row![
+            Column::with_children(filters).spacing(10).width(Fill),
+            column![text("LFOs"), Column::with_children(lfos).spacing(2)].width(Fill),
+        ]
+        .spacing(20),
+    ]
+    .spacing(10)
+}
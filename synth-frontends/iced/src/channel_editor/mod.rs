@@ -1,66 +1,141 @@
-use crate::Message;
+use crate::{ChannelMessage, Message, helpers::IndexLessThan};
 use iced::{
     Length::{Fill, FillPortion},
-    widget::{Column, Text, column, row},
+    widget::{Column, Row, button, column, pick_list, progress_bar, row, slider, text, toggler},
 };
-use stepper_synth::{effects::EffectsModule, sequencer::SequenceChannel, synth_engines::Synth};
+use std::fmt::Display;
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::{EffectType, EngineType, METER_FLOOR_DB, MeterState, SynthState};
 
-pub fn channel_editor<'a>(synth: &Synth) -> Column<'a, Message> {
-    let get_effect_name = |effect: &EffectsModule| match effect {
-        EffectsModule::Chorus(_) => "Chorus",
-        EffectsModule::Reverb(_) => "Reverb",
-    };
+/// what can be placed in an effect slot, `None` leaves the slot empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EffectChoice(pub Option<EffectType>);
+
+impl EffectChoice {
+    pub const ALL: [Self; 8] = [
+        Self(None),
+        Self(Some(EffectType::Reverb)),
+        Self(Some(EffectType::Chorus)),
+        Self(Some(EffectType::Delay)),
+        Self(Some(EffectType::Drive)),
+        Self(Some(EffectType::Eq)),
+        Self(Some(EffectType::Compressor)),
+        Self(Some(EffectType::Phaser)),
+    ];
+}
+
+impl Display for EffectChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(effect) => write!(f, "{effect}"),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+/// a peak bar over an RMS bar, the peak turns red when it clips.
+fn meter<'a>(meter: MeterState) -> Column<'a, Message> {
+    let peak = progress_bar(METER_FLOOR_DB..=0.0, meter.peak_db)
+        .height(6)
+        .style(if meter.clipping() {
+            progress_bar::danger
+        } else {
+            progress_bar::success
+        });
+    let rms = progress_bar(METER_FLOOR_DB..=0.0, meter.rms_db)
+        .height(6)
+        .style(progress_bar::primary);
 
+    column![
+        peak,
+        rms,
+        text(format!("{:.0} / {:.0} dB", meter.peak_db, meter.rms_db)).size(12),
+    ]
+    .spacing(2)
+}
+
+pub fn channel_editor<'a>(state: &SynthState) -> Column<'a, Message> {
     let mk_channel = |channel: SequenceChannel| {
-        let lable = Text::new(match channel {
+        let send = move |message: ChannelMessage| Message::ChannelMsg { channel, message };
+        let lable = text(match channel {
             SequenceChannel::A => "A",
             SequenceChannel::B => "B",
             SequenceChannel::C => "C",
             SequenceChannel::D => "D",
         })
         .center();
-        let chan = &synth.channels[channel as usize];
-        let sound_src = Text::new(chan.engine_type.to_string()).center();
-        let effect_1 = if let Some(ref effect) = chan.effects[0] {
-            Text::new(get_effect_name(&effect.0))
-        } else {
-            Text::new("None")
-        }
-        .center();
-        let effect_2 = if let Some(ref effect) = chan.effects[1] {
-            Text::new(get_effect_name(&effect.0))
-        } else {
-            Text::new("None")
-        }
-        .center();
+        let chan = &state.channels[channel as usize];
+        let sound_src = pick_list(EngineType::ALL, Some(chan.engine), move |engine| {
+            send(ChannelMessage::ChangeInstrument(engine))
+        });
 
-        row![
-            lable
-                .width(FillPortion(25))
-                .height(FillPortion(25))
-                .center(),
-            sound_src
-                .width(FillPortion(25))
-                .height(FillPortion(25))
-                .center(),
-            effect_1
-                .width(FillPortion(25))
-                .height(FillPortion(25))
-                .center(),
-            effect_2
+        let effects = IndexLessThan::<2>::all().map(|slot| {
+            let effect = chan.effects[*slot];
+            let choice = pick_list(
+                EffectChoice::ALL,
+                Some(EffectChoice(effect.map(|effect| effect.effect))),
+                move |effect| send(ChannelMessage::SetEffect { slot, effect }),
+            );
+            let bypass = toggler(effect.is_some_and(|effect| effect.on)).on_toggle_maybe(
+                effect.map(|_| move |on| send(ChannelMessage::SetEffectPower { slot, on })),
+            );
+
+            row![choice, bypass]
+                .spacing(5)
                 .width(FillPortion(25))
-                .height(FillPortion(25))
-                .center()
+                .into()
+        });
+        let effects = Row::with_children(effects)
+            .spacing(10)
+            .width(FillPortion(50));
+        let swap = button(text("<>").center())
+            .on_press(send(ChannelMessage::SwapEffects))
+            .width(FillPortion(5));
+
+        let mute = button(text("M").center())
+            .style(if chan.mute {
+                button::danger
+            } else {
+                button::secondary
+            })
+            .on_press(send(ChannelMessage::SetMute(!chan.mute)));
+        let volume = slider(0.0..=1.0, chan.volume, move |vol| {
+            send(ChannelMessage::SetVolume(vol))
+        })
+        .step(0.01);
+
+        row![
+            lable.width(FillPortion(5)),
+            sound_src.width(FillPortion(20)),
+            effects,
+            swap,
+            column![row![mute, volume].spacing(5), meter(chan.meter)]
+                .spacing(5)
+                .width(FillPortion(20)),
         ]
+        .spacing(10)
     };
 
+    let master = row![
+        text("Out").center().width(FillPortion(5)),
+        row![
+            text("Limiter"),
+            toggler(state.limiter).on_toggle(Message::SetLimiter),
+        ]
+        .spacing(5)
+        .width(FillPortion(75)),
+        column![meter(state.master)].width(FillPortion(20)),
+    ]
+    .spacing(10);
+
     column![
         mk_channel(SequenceChannel::A).height(Fill),
         mk_channel(SequenceChannel::B).height(Fill),
         mk_channel(SequenceChannel::C).height(Fill),
         mk_channel(SequenceChannel::D).height(Fill),
+        master.height(Fill),
     ]
     .width(Fill)
     .height(Fill)
-    // .spacing(80)
+    .padding(10)
 }
@@ -23,3 +23,10 @@ impl<const LT: usize> TryFrom<usize> for IndexLessThan<LT> {
         }
     }
 }
+
+impl<const LT: usize> IndexLessThan<LT> {
+    /// every valid index, in order.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..LT).map(Self)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{Message, channel::wave_table::param_slider};
+use iced::{
+    Length::Fill,
+    widget::{Column, column, pick_list, progress_bar, row, text, toggler},
+};
+use synth_common::{LFO_RATES, LfoSettings, LfoShape, LfoState, LfoSync, MAX_FADE_IN, SynthState};
+
+/// the shape, rate or sync, retrigger, phase and fade in of one LFO, and where it is.
+fn lfo_panel<'a>(lfo: u8, state: LfoState, tempo: f32) -> Column<'a, Message> {
+    let settings = state.settings;
+    let send = move |settings: LfoSettings| Message::SetLfo { lfo, settings };
+
+    let shape = pick_list(LfoShape::ALL, Some(settings.shape), move |shape| {
+        send(LfoSettings { shape, ..settings })
+    });
+    let sync = pick_list(LfoSync::ALL, Some(settings.sync), move |sync| {
+        send(LfoSettings { sync, ..settings })
+    });
+    // a synced LFO's rate comes from the tempo.
+    let rate = if settings.sync == LfoSync::Off {
+        param_slider("Rate", LFO_RATES, settings.rate, move |rate| {
+            send(LfoSettings { rate, ..settings })
+        })
+    } else {
+        row![text(format!(
+            "{:.2} Hz at {tempo:.0} bpm",
+            settings.frequency(tempo)
+        ))]
+    };
+
+    column![
+        row![
+            text(format!("LFO {}", lfo + 1)).size(18),
+            progress_bar(-1.0..=1.0, state.value).height(8).width(Fill),
+        ]
+        .spacing(10),
+        row![
+            text("Shape"),
+            shape,
+            text("Sync"),
+            sync,
+            toggler(settings.retrigger)
+                .label("Retrigger")
+                .on_toggle(move |retrigger| send(LfoSettings {
+                    retrigger,
+                    ..settings
+                })),
+        ]
+        .spacing(10),
+        rate,
+        param_slider("Phase", 0.0..=1.0, settings.phase, move |phase| {
+            send(LfoSettings { phase, ..settings })
+        }),
+        param_slider(
+            "Fade In",
+            0.0..=MAX_FADE_IN,
+            settings.fade_in,
+            move |fade_in| {
+                send(LfoSettings {
+                    fade_in,
+                    ..settings
+                })
+            }
+        ),
+    ]
+    .spacing(5)
+}
+
+/// the global LFOs the channels' mod matrices read.
+pub fn lfo_screen<'a>(state: &SynthState) -> Column<'a, Message> {
+    let tempo = state.song.tempo;
+
+    Column::with_children(
+        state
+            .lfos
+            .iter()
+            .enumerate()
+            .map(|(lfo, lfo_state)| lfo_panel(lfo as u8, *lfo_state, tempo).into()),
+    )
+    .width(Fill)
+    .height(Fill)
+    .spacing(20)
+    .padding(10)
+}
@@ -1,35 +1,47 @@
-use std::{
-    sync::{Arc, RwLock},
-    thread::{JoinHandle, spawn},
-};
+use std::{path::Path, time::Duration};
 
-use channel_editor::channel_editor;
+use audio::list_cards;
+use backend::{Backend, Live, LocalSynth, RemoteAddr, RemoteSynth};
+use channel::channel_screen;
+use channel_editor::{EffectChoice, channel_editor};
 use helpers::IndexLessThan;
 use iced::{
-    Task, Theme,
-    widget::{Column, Row, Text, row},
+    Subscription, Task, Theme,
+    widget::{Row, Text, row},
 };
-use midi_control::MidiMessage;
-use midir::{Ignore, MidiInput, PortInfoError};
+use lfos::lfo_screen;
+use midi_sequencer::{SongEditor, SongMessage, midi_sequencer};
+use midi_stepper::midi_stepper;
+use patches::PatchFiles;
+use scope::scope_screen;
+use settings::{Config, SettingsMessage, settings};
 use sidebar::side_bar;
 use stepper_synth::{
-    CHANNEL_SIZE, HashMap, MidiControlled, SAMPLE_RATE, SampleGen,
-    pygame_coms::SynthEngineType,
     sequencer::SequenceChannel,
-    synth_engines::{
-        Synth, SynthModule,
-        wave_table::wavetable_synth::config::{N_ENV, N_LFO, N_OSC},
-    },
+    synth_engines::wave_table::wavetable_synth::config::{N_ENV, N_LFO, N_OSC},
 };
 use strum::EnumIter;
-use tinyaudio::{OutputDevice, OutputDeviceParameters, run_output_device};
+use synth_common::{
+    BendRange, EngineType, LfoSettings, ModDest, ModSlot, ModSrc, Patch, SynthState, TableChoice,
+    UiToBackend, VoiceSettings, WaveTableCmd, list_patches, load_patch, save_patch,
+};
 use tracing::*;
 
+pub mod audio;
+pub mod backend;
+pub mod channel;
 pub mod channel_editor;
 pub mod helpers;
+pub mod lfos;
+pub mod midi;
+pub mod midi_sequencer;
+pub mod midi_stepper;
+pub mod patches;
+pub mod scope;
+pub mod settings;
 pub mod sidebar;
 
-#[derive(Debug, Clone, Copy, Default, EnumIter)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
 pub enum Screen {
     // #[default]
     // Loading,
@@ -41,6 +53,8 @@ pub enum Screen {
     ChannelB,
     ChannelC,
     ChannelD,
+    Lfos,
+    Scope,
     Settings,
 }
 
@@ -54,6 +68,8 @@ impl ToString for Screen {
             Self::ChannelB => "B",
             Self::ChannelC => "C",
             Self::ChannelD => "D",
+            Self::Lfos => "LFO",
+            Self::Scope => "Scope",
             Self::Settings => "Set",
         }
         .into()
@@ -65,8 +81,10 @@ pub enum WaveTableOscMessage {
     SetLevel(f32),
     SetOffset(i16),
     SetDetune(f32),
-    // TODO: Write wavetabe settigns in backend
-    // SetWaveTable(),
+    /// picks the built in or imported table the oscillator plays.
+    SetTable(TableChoice),
+    /// where in its table the oscillator plays.
+    SetPosition(f32),
     /// a true value will turn on the oscilator
     SetPower(bool),
 }
@@ -114,11 +132,162 @@ pub enum WaveTableMessage {
 
 #[derive(Debug, Clone, Copy)]
 pub enum ChannelMessage {
-    ChangeInstrument(SynthEngineType),
+    ChangeInstrument(EngineType),
     WaveTableMessage(WaveTableMessage),
+    /// sets one of the eight knobs of a knob controlled engine (organ, sub-synth, wurlitzer).
+    SetKnob {
+        knob: IndexLessThan<8>,
+        value: f32,
+    },
+    /// inserts, replaces or (with `EffectChoice(None)`) removes the effect in a slot.
+    SetEffect {
+        slot: IndexLessThan<2>,
+        effect: EffectChoice,
+    },
+    /// turns an effect on or bypasses it.
+    SetEffectPower {
+        slot: IndexLessThan<2>,
+        on: bool,
+    },
+    /// sets one of the params named by `EffectType::param_names`, from `0.0` to `1.0`.
+    SetEffectParam {
+        slot: IndexLessThan<2>,
+        param: u8,
+        value: f32,
+    },
+    /// swaps the order of the two effect slots.
+    SwapEffects,
+    SetVolume(f32),
+    SetMute(bool),
+    /// sets the polyphony, voice stealing, mono modes and glide.
+    SetVoices(VoiceSettings),
+    /// sets how far a full pitch bend goes up and down.
+    SetBendRange(BendRange),
+    /// connects a source to a destination in the mod matrix, or changes its amount.
+    ModConnect(ModSlot),
+    /// replaces the connection in one of the mod matrix's slots.
+    SetModSlot {
+        index: usize,
+        slot: ModSlot,
+    },
+    ModDisconnect {
+        src: ModSrc,
+        dest: ModDest,
+    },
 }
 
-#[derive(Debug, Clone, Copy)]
+impl WaveTableMessage {
+    pub fn into_cmd(self) -> WaveTableCmd {
+        match self {
+            Self::Osc { osc, msg } => {
+                let osc = *osc as u8;
+
+                match msg {
+                    WaveTableOscMessage::SetLevel(level) => {
+                        WaveTableCmd::SetOscLevel { osc, level }
+                    }
+                    WaveTableOscMessage::SetOffset(offset) => {
+                        WaveTableCmd::SetOscOffset { osc, offset }
+                    }
+                    WaveTableOscMessage::SetDetune(detune) => {
+                        WaveTableCmd::SetOscDetune { osc, detune }
+                    }
+                    WaveTableOscMessage::SetTable(table) => {
+                        WaveTableCmd::SetOscTable { osc, table }
+                    }
+                    WaveTableOscMessage::SetPosition(position) => {
+                        WaveTableCmd::SetOscPosition { osc, position }
+                    }
+                    WaveTableOscMessage::SetPower(on) => WaveTableCmd::SetOscPower { osc, on },
+                }
+            }
+            Self::Env { env, msg } => {
+                let env = *env as u8;
+
+                match msg {
+                    WaveTableEnvMessage::SetAtk(value) => WaveTableCmd::SetEnvAtk { env, value },
+                    WaveTableEnvMessage::SetDcy(value) => WaveTableCmd::SetEnvDcy { env, value },
+                    WaveTableEnvMessage::SetSus(value) => WaveTableCmd::SetEnvSus { env, value },
+                    WaveTableEnvMessage::SetRel(value) => WaveTableCmd::SetEnvRel { env, value },
+                }
+            }
+            Self::Lfo { lfo, msg } => match msg {
+                WaveTableLfoMessage::SetSpeed(speed) => WaveTableCmd::SetLfoSpeed {
+                    lfo: *lfo as u8,
+                    speed,
+                },
+            },
+            Self::LPFilter { filter, msg } => {
+                let filter = *filter as u8;
+
+                match msg {
+                    WaveTableLPFilterMessage::SetCutoff(cutoff) => {
+                        WaveTableCmd::SetFilterCutoff { filter, cutoff }
+                    }
+                    WaveTableLPFilterMessage::SetResonance(resonance) => {
+                        WaveTableCmd::SetFilterResonance { filter, resonance }
+                    }
+                    WaveTableLPFilterMessage::SetMix(mix) => {
+                        WaveTableCmd::SetFilterMix { filter, mix }
+                    }
+                    WaveTableLPFilterMessage::SetKeytrack(on) => {
+                        WaveTableCmd::SetFilterKeytrack { filter, on }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ChannelMessage {
+    /// the command that makes this change to `channel` on the backend.
+    pub fn into_cmd(self, channel: SequenceChannel) -> UiToBackend {
+        let channel = channel as u8;
+
+        match self {
+            Self::ChangeInstrument(engine) => UiToBackend::SetEngine { channel, engine },
+            Self::WaveTableMessage(msg) => UiToBackend::WaveTable {
+                channel,
+                cmd: msg.into_cmd(),
+            },
+            Self::SetKnob { knob, value } => UiToBackend::SetKnob {
+                channel,
+                knob: *knob as u8,
+                value,
+            },
+            Self::SetEffect { slot, effect } => UiToBackend::SetEffect {
+                channel,
+                slot: *slot as u8,
+                effect: effect.0,
+            },
+            Self::SetEffectPower { slot, on } => UiToBackend::SetEffectPower {
+                channel,
+                slot: *slot as u8,
+                on,
+            },
+            Self::SetEffectParam { slot, param, value } => UiToBackend::SetEffectParam {
+                channel,
+                slot: *slot as u8,
+                param,
+                value,
+            },
+            Self::SwapEffects => UiToBackend::SwapEffects { channel },
+            Self::SetVolume(volume) => UiToBackend::SetVolume { channel, volume },
+            Self::SetMute(mute) => UiToBackend::SetMute { channel, mute },
+            Self::SetVoices(settings) => UiToBackend::SetVoices { channel, settings },
+            Self::SetBendRange(range) => UiToBackend::SetBendRange { channel, range },
+            Self::ModConnect(slot) => UiToBackend::ModConnect { channel, slot },
+            Self::SetModSlot { index, slot } => UiToBackend::SetModSlot {
+                channel,
+                index,
+                slot,
+            },
+            Self::ModDisconnect { src, dest } => UiToBackend::ModDisconnect { channel, src, dest },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Message {
     /// changes wht screen the UI is set to.
     ScreenChange(Screen),
@@ -126,308 +295,291 @@ pub enum Message {
         channel: SequenceChannel,
         message: ChannelMessage,
     },
+    /// edits or drives the song arrangement.
+    Song(SongMessage),
+    Settings(SettingsMessage),
+    /// turns the soft limiter on the output on or off.
+    SetLimiter(bool),
+    /// sets one of the global LFOs.
+    SetLfo {
+        lfo: u8,
+        settings: LfoSettings,
+    },
+    /// the name the next patch is saved under.
+    PatchName(String),
+    /// saves a channel's sound under the typed name.
+    SavePatch(SequenceChannel),
+    /// replaces a channel's sound with a saved patch.
+    LoadPatch {
+        channel: SequenceChannel,
+        name: String,
+    },
+    /// the WAV file the next wavetable is imported from.
+    WaveTablePath(String),
+    ImportWaveTable,
+    /// stops every voice on every channel.
+    Panic,
+    /// reads the synth's state again and redraws the UI.
+    Refresh,
+    /// redraws the LFOs, the song's playhead and the meters, without reading the state.
+    Live(Live),
 }
 
 pub struct App {
     /// describes what screen the user is on and holds screen specific data.
     screen: Screen,
-    // /// the websocket connection that comunicates with the synth backend
-    // socket:
-    /// the state of the synth
-    synth: Arc<RwLock<Synth>>,
-    /// audio device
-    _device: Result<OutputDevice, Box<dyn std::error::Error>>,
-    _midi_jh: JoinHandle<()>,
+    /// the synth, either running in this process or in the backend.
+    backend: Backend,
+    /// the synth's state as of the last `Message::Refresh`, with the live values on top.
+    state: SynthState,
+    /// what the song and stepper screens are editing.
+    song: SongEditor,
+    /// the persistent settings.
+    config: Config,
+    /// the audio cards found on the system.
+    cards: Vec<String>,
+    /// the saved patches and the name being typed.
+    patches: PatchFiles,
+    /// the WAV file being typed, to import as a wavetable.
+    table_path: String,
 }
 
 impl Default for App {
     fn default() -> Self {
-        Self::new(Screen::default())
+        Self::new(Screen::default(), None)
     }
 }
 
 impl App {
-    fn new(screen: Screen) -> Self {
-        let synth = Arc::new(RwLock::new(Synth::new()));
-        let params = OutputDeviceParameters {
-            channels_count: 1,
-            sample_rate: SAMPLE_RATE as usize,
-            channel_sample_count: CHANNEL_SIZE,
-        };
-        // NOTE: must stay in this thread so that it stays in scope
-        let _device = run_output_device(params, {
-            let synth = synth.clone();
-
-            move |data| {
-                for samples in data.chunks_mut(params.channels_count) {
-                    if let Ok(mut synth) = synth.write() {
-                        let value = synth.get_sample();
-
-                        for sample in samples {
-                            *sample = value;
-                        }
-                    } else {
-                        error!("failled to lock synth as mutable")
-                    }
-                }
-            }
-        });
-
-        if let Err(ref e) = _device {
-            error!("starting audio playback caused error: {e}");
-        }
-
-        let _midi_jh = spawn({
-            let synth = synth.clone();
-
-            move || {
-                if let Err(e) = run_midi(synth) {
-                    error!("{e}");
-                }
+    /// runs the synth locally, unless the address of a `remote` backend is given.
+    fn new(screen: Screen, remote: Option<RemoteAddr>) -> Self {
+        let config = Config::load();
+        let backend = match remote {
+            Some(addr) => {
+                info!("controlling the synth backend at {addr}");
+                Backend::Remote(RemoteSynth::new(addr))
             }
-        });
+            None => Backend::Local(LocalSynth::new(&config)),
+        };
 
-        // (
         Self {
-            screen: screen,
-            synth,
-            _device,
-            _midi_jh,
+            screen,
+            state: backend.state(),
+            backend,
+            song: SongEditor::default(),
+            config,
+            cards: list_cards(),
+            patches: PatchFiles::load(),
+            table_path: String::new(),
         }
-        //     Task::batch([
-        //         Task::perform(echo::server::run(), |_| Message::Server),
-        //         widget::focus_next(),
-        //     ]),
-        // )
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::ScreenChange(screen) => {
                 debug!("screen set to {}", screen.to_string());
-                self.screen = screen
+                self.screen = screen;
+
+                if let Backend::Remote(ref remote) = self.backend {
+                    remote.stream_scope(screen == Screen::Scope);
+                }
             }
-            Message::ChannelMsg {
-                channel,
-                message: channel_msg,
-            } => {
-                if let Ok(ref mut synth) = self.synth.write() {
-                    match channel_msg {
-                        ChannelMessage::ChangeInstrument(instrument) => {
-                            synth.set_channel_engine(channel as usize, instrument);
-                        }
-                        ChannelMessage::WaveTableMessage(wt_msg) => {
-                            if let SynthModule::WaveTable(ref mut wt) =
-                                synth.get_channel_engine(channel as usize).engine
-                            {
-                                match wt_msg {
-                                    WaveTableMessage::Osc { osc, msg: osc_msg } => match osc_msg {
-                                        WaveTableOscMessage::SetLevel(level) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.oscs[*osc].0.level = level),
-                                        WaveTableOscMessage::SetOffset(offset) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.oscs[*osc].0.offset = offset),
-                                        WaveTableOscMessage::SetDetune(detune) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.oscs[*osc].0.detune = detune),
-                                        WaveTableOscMessage::SetPower(power) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.oscs[*osc].1 = power),
-                                    },
-                                    WaveTableMessage::Env { env, msg: env_msg } => match env_msg {
-                                        WaveTableEnvMessage::SetAtk(val) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.envs[*env].set_atk(val)),
-                                        WaveTableEnvMessage::SetDcy(val) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.envs[*env].set_decay(val)),
-                                        WaveTableEnvMessage::SetSus(val) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.envs[*env].set_sus(val)),
-                                        WaveTableEnvMessage::SetRel(val) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.envs[*env].set_release(val)),
-                                    },
-                                    WaveTableMessage::Lfo { lfo, msg: lfo_msg } => match lfo_msg {
-                                        WaveTableLfoMessage::SetSpeed(speed) => {
-                                            wt.synth.lfos[*lfo].set_frequency(1.0 / speed)
-                                        }
-                                    },
-                                    WaveTableMessage::LPFilter {
-                                        filter,
-                                        msg: lp_fitler_msg,
-                                    } => match lp_fitler_msg {
-                                        WaveTableLPFilterMessage::SetCutoff(cutoff) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.filters[*filter].set_cutoff(cutoff)),
-                                        WaveTableLPFilterMessage::SetResonance(res) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.filters[*filter].set_resonace(res)),
-                                        WaveTableLPFilterMessage::SetMix(mix) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.filters[*filter].mix = mix),
-                                        WaveTableLPFilterMessage::SetKeytrack(track) => wt
-                                            .synth
-                                            .voices
-                                            .iter_mut()
-                                            .for_each(|v| v.filters[*filter].key_track = track),
-                                    },
-                                }
-                            } else {
-                                error!(
-                                    "the channel \"{channel:?}\" is a \"{}\". it was expected to be a \"{}\".",
-                                    synth.get_channel_engine(channel as usize).engine_type,
-                                    SynthEngineType::WaveTable
-                                )
+            Message::Settings(settings_msg) => return self.update_settings(settings_msg),
+            Message::SetLimiter(on) => self.backend.send(UiToBackend::SetLimiter(on)),
+            Message::SetLfo { lfo, settings } => {
+                self.backend.send(UiToBackend::SetLfo { lfo, settings })
+            }
+            Message::PatchName(name) => self.patches.name = name,
+            Message::SavePatch(channel) => {
+                let state = self.backend.state();
+                let patch = Patch::from(&state.channels[channel as usize]);
+
+                match save_patch(&self.patches.name, &patch) {
+                    Ok(()) => self.patches.saved = list_patches(),
+                    Err(e) => error!("failed to save the patch {}. {e}", self.patches.name),
+                }
+            }
+            Message::LoadPatch { channel, name } => match load_patch(&name) {
+                Ok(patch) => {
+                    let state = self.backend.state();
+                    let cmds = patch.commands(channel as u8, &state.channels[channel as usize]);
+
+                    // a patch with a bad number in it is refused whole, rather than loaded half
+                    // way.
+                    match cmds.iter().try_for_each(UiToBackend::validate) {
+                        Ok(()) => {
+                            for cmd in cmds {
+                                self.backend.send(cmd);
                             }
+
+                            self.patches.name = name;
                         }
+                        Err(e) => error!("the patch {name} can't be loaded. {e}"),
                     }
-                } else {
-                    error!("failed to lock synth with write access.")
+                }
+                Err(e) => error!("failed to load the patch {name}. {e}"),
+            },
+            Message::WaveTablePath(path) => self.table_path = path,
+            Message::ImportWaveTable => match self.backend {
+                Backend::Local(ref local) => {
+                    match local.import_wave_table(Path::new(self.table_path.trim())) {
+                        Ok(_) => {
+                            info!("imported the wavetable {}", self.table_path);
+                            self.table_path.clear();
+                        }
+                        Err(e) => error!("failed to import {}. {e}", self.table_path),
+                    }
+                }
+                // the backend reads its tables from its own disk.
+                Backend::Remote(_) => {
+                    warn!("wavetables can only be imported into a synth running in this process")
+                }
+            },
+            Message::Panic => self.backend.send(UiToBackend::Panic),
+            Message::Refresh => self.state = self.backend.state(),
+            Message::Live(live) => live.apply(&mut self.state),
+            Message::Song(song_msg) => {
+                if let Some(cmd) = self.song.update(song_msg, &self.state.song) {
+                    self.backend.send(cmd);
                 }
             }
+            Message::ChannelMsg {
+                channel,
+                message: channel_msg,
+            } => self.backend.send(channel_msg.into_cmd(channel)),
         }
 
         Task::none()
     }
 
-    fn view(&self) -> Row<Message> {
-        let mut dis = row![side_bar(self.screen)];
-
-        // if let Some(screem) = self.screen {
-        // if let Some(screen) = match state {
-        //     // TODO: make this a per screen state that can be requested
-        //     StepperSynthState::MidiStepper {
-        //         name,
-        //         playing,
-        //         recording,
-        //         cursor,
-        //         tempo,
-        //         step,
-        //         sequence,
-        //         seq_n,
-        //         channel,
-        //     } => None::<Column<Message>>,
-        //     StepperSynthState::Synth {
-        //         engine,
-        //         knob_params,
-        //         gui_params,
-        //     } => None::<Column<Message>>,
-        //     StepperSynthState::WaveTable {
-        //         osc,
-        //         filter,
-        //         adsr,
-        //         lfo,
-        //         mod_matrix,
-        //     } => None::<Column<Message>>,
-        //     StepperSynthState::Effect {
-        //         effect: _,
-        //         effect_on: _,
-        //         params: _,
-        //     } => None::<Column<Message>>,
-        // } {
-        //     dis = dis.push(screen)
-        // }
-        if let Ok(synth) = self.synth.read() {
-            if let Some(screen) = match self.screen {
-                Screen::MidiStepper => None::<Column<Message>>,
-                Screen::MidiSequenser => None::<Column<Message>>,
-                Screen::ChannelEditor => Some(channel_editor(&synth)),
-                Screen::ChannelA => None::<Column<Message>>,
-                Screen::ChannelB => None::<Column<Message>>,
-                Screen::ChannelD => None::<Column<Message>>,
-                Screen::ChannelC => None::<Column<Message>>,
-                Screen::Settings => None::<Column<Message>>,
-            } {
-                dis = dis.push(screen);
-            } else {
-                dis = dis.push(Text::new("not implemented").center());
+    fn update_settings(&mut self, message: SettingsMessage) -> Task<Message> {
+        let mut restart_audio = false;
+
+        match message {
+            SettingsMessage::SetCard(card) => {
+                restart_audio = self.config.audio_card != card.0;
+                self.config.audio_card = card.0;
+            }
+            SettingsMessage::SetBufferSize(size) => {
+                restart_audio = self.config.buffer_size != size;
+                self.config.buffer_size = size;
+            }
+            SettingsMessage::RescanCards => self.cards = list_cards(),
+            SettingsMessage::ResetStats => {
+                if let Backend::Local(ref local) = self.backend {
+                    local.stats.reset()
+                }
+            }
+            SettingsMessage::SetTheme(theme) => self.config.theme = theme,
+            SettingsMessage::SetPortEnabled { port, enabled } => {
+                if let Backend::Local(ref local) = self.backend
+                    && let Ok(mut routing) = local.routing.write()
+                {
+                    routing.ports.entry(port).or_default().enabled = enabled;
+                    self.config.midi_ports = routing.ports.clone();
+                }
+            }
+            SettingsMessage::SetPortRoute { port, route } => {
+                if let Backend::Local(ref local) = self.backend
+                    && let Ok(mut routing) = local.routing.write()
+                {
+                    routing.ports.entry(port).or_default().route = route.0;
+                    self.config.midi_ports = routing.ports.clone();
+                }
             }
-        } else {
-            dis = dis.push(Text::new("").center());
         }
 
-        dis
+        if restart_audio && let Backend::Local(ref mut local) = self.backend {
+            local.restart_audio(&self.config);
+        }
+
+        if let Err(e) = self.config.save() {
+            error!("failed to save settings. {e}");
+        }
+
+        Task::none()
     }
-}
 
-fn run_midi(synth: Arc<RwLock<Synth>>) -> anyhow::Result<()> {
-    let mut registered_ports = HashMap::default();
+    fn theme(&self) -> Theme {
+        self.config.theme.theme()
+    }
 
-    loop {
-        let mut midi_in = MidiInput::new("midir reading input")?;
-        midi_in.ignore(Ignore::None);
+    fn view(&self) -> Row<Message> {
+        let state = &self.state;
+        let mut dis = row![side_bar(self.screen, state)];
 
-        // Get an input port (read from console if multiple are available)
-        let in_ports = midi_in.ports();
-        let port_names: Vec<std::result::Result<String, PortInfoError>> = in_ports
-            .iter()
-            .map(|port| midi_in.port_name(port))
-            .collect();
-        registered_ports.retain(|k: &String, _| port_names.contains(&Ok(k.clone())));
+        let chan = |channel: SequenceChannel| {
+            channel_screen(
+                &state.channels[channel as usize],
+                channel,
+                &state.wave_tables,
+                &self.table_path,
+                &self.patches,
+            )
+        };
 
-        for in_port in in_ports.iter() {
-            let Ok(port_name) = midi_in.port_name(in_port) else {
-                continue;
-            };
+        if let Some(screen) = match self.screen {
+            Screen::MidiStepper => Some(midi_stepper(state, &self.song)),
+            Screen::MidiSequenser => Some(midi_sequencer(&state.song, &self.song)),
+            Screen::ChannelEditor => Some(channel_editor(state)),
+            Screen::ChannelA => Some(chan(SequenceChannel::A)),
+            Screen::ChannelB => Some(chan(SequenceChannel::B)),
+            Screen::ChannelD => Some(chan(SequenceChannel::D)),
+            Screen::ChannelC => Some(chan(SequenceChannel::C)),
+            Screen::Lfos => Some(lfo_screen(state)),
+            Screen::Scope => Some(scope_screen(&self.backend.scope())),
+            Screen::Settings => Some(settings(&self.config, &self.cards, &self.backend)),
+        } {
+            dis = dis.push(screen);
+        } else {
+            dis = dis.push(Text::new("not implemented").center());
+        }
 
-            if registered_ports.contains_key(&port_name) {
-                continue;
-            }
+        dis
+    }
 
-            info!("port {port_name}");
-            let mut midi_in = MidiInput::new("midir reading input")?;
-            midi_in.ignore(Ignore::None);
-
-            registered_ports.insert(
-                port_name,
-                midi_in.connect(
-                    in_port,
-                    "midir-read-input",
-                    {
-                        let synth = synth.clone();
-                        move |_stamp, message, _| {
-                            let message = MidiMessage::from(message);
-
-                            // do midi stuff
-                            synth.write().unwrap().midi_input(&message);
-                        }
-                    },
-                    (),
-                ),
+    fn subscription(&self) -> Subscription<Message> {
+        // MIDI input and the remote backend change the state without the UI sending a message,
+        // and the synth moves the LFOs, the song's playhead and the meters on its own.
+        let changes =
+            Subscription::run_with_id("synth-changes", self.backend.changes().refreshes());
+
+        match self.screen {
+            // the audio stats are not part of the synth's state.
+            Screen::Settings => Subscription::batch([
+                changes,
+                iced::time::every(Duration::from_secs(1)).map(|_| Message::Refresh),
+            ]),
+            _ => changes,
+        }
+    }
+}
+
+/// `--remote [ADDR]` controls a running synth backend instead of starting a synth in this
+/// process. `ADDR` is a unix socket path or a `host:port`, it defaults to the backend's socket.
+/// a backend that wants auth over the network takes the token in `$SYNTH_TOKEN`.
+fn remote_arg() -> Option<RemoteAddr> {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--remote" {
+            return Some(
+                args.next()
+                    .and_then(|addr| addr.parse().ok())
+                    .unwrap_or_default(),
             );
         }
     }
+
+    None
 }
 
 fn main() -> iced::Result {
     tracing_subscriber::fmt::init();
+    let remote = remote_arg();
 
     iced::application("Synth OS", App::update, App::view)
-        .theme(|_| Theme::CatppuccinMocha)
-        // .subscription(f)
-        .run()
+        .theme(App::theme)
+        .subscription(App::subscription)
+        .run_with(move || (App::new(Screen::default(), remote), Task::none()))
 }
@@ -0,0 +1,177 @@
+use crate::backend::Changes;
+use midir::{ConnectError, Ignore, MidiInput, MidiInputConnection, PortInfoError};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+use stepper_synth::synth_engines::Synth;
+use synth_engine::{play, release_notes};
+use synth_lib::{notes::HeldNotes, voices::Voices};
+use tracing::*;
+
+/// how the messages from one MIDI input are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortSettings {
+    pub enabled: bool,
+    /// when set, every message from the port is moved onto this channel (0 => A, 1 => B, ...).
+    pub route: Option<u8>,
+}
+
+impl Default for PortSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            route: None,
+        }
+    }
+}
+
+/// the connected MIDI inputs and their settings, shared by the MIDI thread and the UI.
+#[derive(Debug, Clone, Default)]
+pub struct MidiRouting {
+    /// names of the inputs that are currently connected.
+    pub connected: Vec<String>,
+    /// settings per input name, kept for inputs that are unplugged so they survive a replug.
+    pub ports: HashMap<String, PortSettings>,
+}
+
+impl MidiRouting {
+    pub fn settings(&self, port: &str) -> PortSettings {
+        self.ports.get(port).copied().unwrap_or_default()
+    }
+}
+
+/// a connected MIDI input and the notes it is holding down.
+struct Port {
+    _connection: Result<MidiInputConnection<()>, ConnectError<MidiInput>>,
+    held: Arc<RwLock<HeldNotes>>,
+}
+
+/// rewrites the channel of a channel voice message.
+fn route(message: &mut [u8], channel: u8) {
+    if let Some(status) = message.first_mut()
+        && (0x80..0xF0).contains(status)
+    {
+        *status = (*status & 0xF0) | (channel & 0x0F);
+    }
+}
+
+pub fn run_midi(
+    synth: Arc<RwLock<Synth>>,
+    routing: Arc<RwLock<MidiRouting>>,
+    held: Arc<RwLock<HeldNotes>>,
+    voices: Arc<RwLock<Voices>>,
+    changes: Changes,
+) -> anyhow::Result<()> {
+    let mut registered_ports = HashMap::new();
+
+    loop {
+        let mut midi_in = MidiInput::new("midir reading input")?;
+        midi_in.ignore(Ignore::None);
+
+        // Get an input port (read from console if multiple are available)
+        let in_ports = midi_in.ports();
+        let port_names: Vec<std::result::Result<String, PortInfoError>> = in_ports
+            .iter()
+            .map(|port| midi_in.port_name(port))
+            .collect();
+        registered_ports.retain(|k: &String, port: &mut Port| {
+            let connected = port_names.contains(&Ok(k.clone()));
+
+            // a controller unplugged mid-note never sends the note offs.
+            if !connected
+                && let Ok(notes) = port.held.read()
+                && let (Ok(mut synth), Ok(mut held), Ok(mut voices)) =
+                    (synth.write(), held.write(), voices.write())
+            {
+                info!("port {k} disconnected");
+                release_notes(&mut synth, &mut held, &mut voices, &notes);
+                changes.notify();
+            }
+
+            connected
+        });
+
+        for in_port in in_ports.iter() {
+            let Ok(port_name) = midi_in.port_name(in_port) else {
+                continue;
+            };
+
+            if registered_ports.contains_key(&port_name) {
+                continue;
+            }
+
+            info!("port {port_name}");
+            let mut midi_in = MidiInput::new("midir reading input")?;
+            midi_in.ignore(Ignore::None);
+            let port_held = Arc::new(RwLock::new(HeldNotes::default()));
+
+            let connection = midi_in.connect(
+                in_port,
+                "midir-read-input",
+                {
+                    let synth = synth.clone();
+                    let routing = routing.clone();
+                    let held = held.clone();
+                    let voices = voices.clone();
+                    let port_held = port_held.clone();
+                    let changes = changes.clone();
+                    let port_name = port_name.clone();
+
+                    move |_stamp, message, _| {
+                        let settings = routing
+                            .read()
+                            .map(|routing| routing.settings(&port_name))
+                            .unwrap_or_default();
+
+                        if !settings.enabled {
+                            return;
+                        }
+
+                        let mut message = message.to_vec();
+
+                        if let Some(channel) = settings.route {
+                            route(&mut message, channel);
+                        }
+
+                        if let Ok(mut port_held) = port_held.write() {
+                            port_held.midi_input(&message);
+                        }
+
+                        if let Ok(mut held) = held.write() {
+                            held.midi_input(&message);
+                        }
+
+                        // do midi stuff
+                        if let (Ok(mut synth), Ok(mut voices)) = (synth.write(), voices.write()) {
+                            play(&mut synth, &mut voices, &message);
+                        }
+
+                        changes.notify();
+                    }
+                },
+                (),
+            );
+            registered_ports.insert(
+                port_name,
+                Port {
+                    _connection: connection,
+                    held: port_held,
+                },
+            );
+        }
+
+        let mut connected: Vec<String> = registered_ports.keys().cloned().collect();
+        connected.sort();
+
+        if routing
+            .read()
+            .is_ok_and(|routing| routing.connected != connected)
+            && let Ok(mut routing) = routing.write()
+        {
+            routing.connected = connected;
+            changes.notify();
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::Message;
+use iced::{
+    Length::{Fill, FillPortion},
+    widget::{Column, Row, button, column, pick_list, row, slider, text},
+};
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::{CHANNEL_NAMES, PATTERN_LENS, SONG_LEN, SongState, TEMPOS};
+
+pub use song::*;
+
+pub mod song;
+
+/// the song's tempo in bpm and a slider that sets it.
+pub fn tempo_slider<'a>(tempo: f32) -> Row<'a, Message> {
+    let set_tempo = |tempo| Message::Song(SongMessage::SetTempo(tempo));
+
+    row![
+        text(format!("{tempo:.0} bpm")).center(),
+        slider(TEMPOS, tempo, set_tempo).step(1.0).width(150),
+    ]
+    .spacing(10)
+}
+
+/// the song arrangement the synth plays.
+pub fn midi_sequencer<'a>(song: &SongState, editor: &SongEditor) -> Column<'a, Message> {
+    let msg = |song_msg: SongMessage| Message::Song(song_msg);
+
+    let mk_track = |channel: SequenceChannel| {
+        let track = &song.tracks[channel as usize];
+        let lable = text(CHANNEL_NAMES[channel as usize])
+            .center()
+            .width(FillPortion(2));
+        let mute = button(text("M").center())
+            .style(if track.mute {
+                button::danger
+            } else {
+                button::secondary
+            })
+            .width(FillPortion(2))
+            .on_press(msg(SongMessage::ToggleMute(channel)));
+        let solo = button(text("S").center())
+            .style(if track.solo {
+                button::success
+            } else {
+                button::secondary
+            })
+            .width(FillPortion(2))
+            .on_press(msg(SongMessage::ToggleSolo(channel)));
+
+        let slots = track.slots.iter().enumerate().map(|(bar, seq)| {
+            let selected = editor.cursor.0 as usize == channel as usize && editor.cursor.1 == bar;
+            let playing = song.playing && song.position == bar;
+            let in_loop = (song.loop_start..=song.loop_end).contains(&bar);
+
+            button(text(SeqChoice(*seq).to_string()).center())
+                .style(if selected {
+                    button::primary
+                } else if playing {
+                    button::success
+                } else if in_loop {
+                    button::secondary
+                } else {
+                    button::text
+                })
+                .padding(2)
+                .width(FillPortion(1))
+                .height(Fill)
+                .on_press(msg(SongMessage::Select { channel, bar }))
+                .into()
+        });
+
+        row![
+            lable,
+            mute,
+            solo,
+            Row::with_children(slots)
+                .width(FillPortion(SONG_LEN as u16))
+                .spacing(2)
+        ]
+        .spacing(5)
+        .height(Fill)
+    };
+
+    let (cursor_chan, cursor_bar) = editor.cursor;
+
+    let transport = row![
+        if song.playing {
+            button(text("Stop").center()).on_press(msg(SongMessage::Stop))
+        } else {
+            button(text("Play").center()).on_press(msg(SongMessage::Play))
+        },
+        button(text("Rec").center())
+            .style(if song.recording {
+                button::danger
+            } else {
+                button::secondary
+            })
+            .on_press(msg(SongMessage::Record(!song.recording))),
+        text(format!("bar {} step {}", song.position + 1, song.step + 1)).center(),
+        tempo_slider(song.tempo),
+        text("loop").center(),
+        pick_list(Bar::all(), Some(Bar(song.loop_start)), move |bar: Bar| {
+            msg(SongMessage::SetLoopStart(bar.0))
+        }),
+        text("to").center(),
+        pick_list(Bar::all(), Some(Bar(song.loop_end)), move |bar: Bar| {
+            msg(SongMessage::SetLoopEnd(bar.0))
+        }),
+        text("steps").center(),
+        pick_list(PATTERN_LENS, Some(song.pattern_len), move |len| {
+            msg(SongMessage::SetPatternLen(len))
+        }),
+    ]
+    .spacing(10);
+
+    let editor = row![
+        text(format!("{:?} bar {} sequence", cursor_chan, cursor_bar + 1)).center(),
+        pick_list(
+            SeqChoice::all(),
+            Some(SeqChoice(editor.selected(song))),
+            move |seq| msg(SongMessage::SetSlot(seq)),
+        ),
+        button(text("Clear").center())
+            .style(button::danger)
+            .on_press_maybe(
+                editor
+                    .selected(song)
+                    .map(|seq| msg(SongMessage::ClearSequence(seq))),
+            ),
+    ]
+    .spacing(10);
+
+    column![
+        transport,
+        Column::with_children(CHANNELS.map(|channel| mk_track(channel).into()))
+            .height(Fill)
+            .spacing(5),
+        editor,
+    ]
+    .width(Fill)
+    .height(Fill)
+    .spacing(10)
+    .padding(5)
+}
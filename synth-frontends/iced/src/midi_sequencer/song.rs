@@ -0,0 +1,180 @@
+use std::fmt::Display;
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::{N_SEQUENCES, SONG_LEN, SongCmd, SongState, UiToBackend, note_name};
+
+/// the sequence channels in the order they are drawn.
+pub const CHANNELS: [SequenceChannel; 4] = [
+    SequenceChannel::A,
+    SequenceChannel::B,
+    SequenceChannel::C,
+    SequenceChannel::D,
+];
+
+/// which numbered sequence (if any) is placed in a slot of the arrangement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeqChoice(pub Option<u8>);
+
+impl SeqChoice {
+    pub fn all() -> Vec<Self> {
+        let mut choices = vec![Self(None)];
+        choices.extend((0..N_SEQUENCES as u8).map(|seq| Self(Some(seq))));
+
+        choices
+    }
+}
+
+impl Display for SeqChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(seq) => write!(f, "{}", seq + 1),
+            None => write!(f, "--"),
+        }
+    }
+}
+
+/// the pitch the stepper adds, shown by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteChoice(pub u8);
+
+impl NoteChoice {
+    pub fn all() -> Vec<Self> {
+        (0..128).map(Self).collect()
+    }
+}
+
+impl Display for NoteChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", note_name(self.0))
+    }
+}
+
+/// a bar of the song, displayed counting from one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bar(pub usize);
+
+impl Bar {
+    pub fn all() -> Vec<Self> {
+        (0..SONG_LEN).map(Self).collect()
+    }
+}
+
+impl Display for Bar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SongMessage {
+    /// moves the edit cursor to a slot in the arrangement.
+    Select {
+        channel: SequenceChannel,
+        bar: usize,
+    },
+    /// places a sequence in the slot under the edit cursor.
+    SetSlot(SeqChoice),
+    SetLoopStart(usize),
+    SetLoopEnd(usize),
+    SetPatternLen(usize),
+    /// in bpm.
+    SetTempo(f32),
+    ToggleMute(SequenceChannel),
+    ToggleSolo(SequenceChannel),
+    Play,
+    Stop,
+    Record(bool),
+    /// wipes the notes of a sequence.
+    ClearSequence(u8),
+    /// picks the sequence the stepper edits.
+    StepSequence(u8),
+    /// picks the pitch the stepper adds.
+    StepNote(u8),
+    /// adds or takes out the stepper's pitch on a step of its sequence.
+    ToggleStep(usize),
+}
+
+/// what the song and stepper screens edit. the song itself is played and kept by the synth.
+#[derive(Debug, Clone, Copy)]
+pub struct SongEditor {
+    /// the slot being edited, as (channel, bar).
+    pub cursor: (SequenceChannel, usize),
+    /// the sequence the stepper edits.
+    pub step_seq: u8,
+    /// the pitch the stepper adds.
+    pub step_note: u8,
+}
+
+impl Default for SongEditor {
+    fn default() -> Self {
+        Self {
+            cursor: (SequenceChannel::A, 0),
+            step_seq: 0,
+            step_note: 60,
+        }
+    }
+}
+
+impl SongEditor {
+    /// the sequence in the slot under the edit cursor.
+    pub fn selected(&self, song: &SongState) -> Option<u8> {
+        let (channel, bar) = self.cursor;
+
+        song.tracks[channel as usize].slots[bar]
+    }
+
+    /// the command to send the synth for a message, `song` is the song as the synth has it.
+    pub fn update(&mut self, msg: SongMessage, song: &SongState) -> Option<UiToBackend> {
+        let cmd = match msg {
+            SongMessage::Select { channel, bar } => {
+                self.cursor = (channel, bar.min(SONG_LEN - 1));
+                return None;
+            }
+            SongMessage::SetSlot(seq) => {
+                let (channel, bar) = self.cursor;
+
+                SongCmd::SetSlot {
+                    channel: channel as u8,
+                    bar: bar as u8,
+                    seq: seq.0,
+                }
+            }
+            SongMessage::SetLoopStart(bar) => SongCmd::SetLoop {
+                start: bar as u8,
+                end: song.loop_end.max(bar) as u8,
+            },
+            SongMessage::SetLoopEnd(bar) => SongCmd::SetLoop {
+                start: song.loop_start.min(bar) as u8,
+                end: bar as u8,
+            },
+            SongMessage::SetPatternLen(len) => SongCmd::SetPatternLen(len as u8),
+            SongMessage::SetTempo(tempo) => SongCmd::SetTempo(tempo.round()),
+            SongMessage::ToggleMute(channel) => SongCmd::SetMute {
+                channel: channel as u8,
+                mute: !song.tracks[channel as usize].mute,
+            },
+            SongMessage::ToggleSolo(channel) => SongCmd::SetSolo {
+                channel: channel as u8,
+                solo: !song.tracks[channel as usize].solo,
+            },
+            SongMessage::Play => SongCmd::Play,
+            SongMessage::Stop => SongCmd::Stop,
+            SongMessage::Record(on) => SongCmd::Record(on),
+            SongMessage::ClearSequence(seq) => SongCmd::ClearSequence(seq),
+            SongMessage::StepSequence(seq) => {
+                self.step_seq = seq.min(N_SEQUENCES as u8 - 1);
+                return None;
+            }
+            SongMessage::StepNote(note) => {
+                self.step_note = note.min(127);
+                return None;
+            }
+            SongMessage::ToggleStep(step) => SongCmd::ToggleStep {
+                seq: self.step_seq,
+                step: step as u8,
+                note: self.step_note,
+            },
+        };
+
+        Some(UiToBackend::Song(cmd))
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    Message,
+    midi_sequencer::{NoteChoice, SeqChoice, SongEditor, SongMessage, tempo_slider},
+};
+use iced::{
+    Length::{Fill, FillPortion},
+    widget::{Column, Row, button, column, pick_list, row, text},
+};
+use synth_common::{CHANNEL_NAMES, N_CHANNELS, N_SEQUENCES, SynthState};
+
+/// the song's transport, the steps each channel plays in the bar under the playhead, and the
+/// numbered sequence being stepped in, the ones the song screen chains together.
+pub fn midi_stepper<'a>(state: &SynthState, editor: &SongEditor) -> Column<'a, Message> {
+    let song = &state.song;
+    let msg = |song_msg: SongMessage| Message::Song(song_msg);
+
+    let mk_channel = |channel: usize| {
+        let seq = match song.current(channel) {
+            Some(seq) => format!("seq {}", seq + 1),
+            None => "--".to_string(),
+        };
+        let steps = (0..song.pattern_len).map(|step| {
+            text("■")
+                .center()
+                .width(FillPortion(1))
+                .style(if song.playing && song.step == step {
+                    text::success
+                } else if song.has_note(channel, step) {
+                    text::primary
+                } else {
+                    text::secondary
+                })
+                .into()
+        });
+
+        row![
+            text(CHANNEL_NAMES[channel]).center().width(FillPortion(2)),
+            text(seq)
+                .center()
+                .width(FillPortion(4))
+                .style(if song.audible(channel) {
+                    text::primary
+                } else {
+                    text::danger
+                }),
+            text("●").center().width(FillPortion(2)).style(
+                if state.channels[channel].held_notes > 0 {
+                    text::success
+                } else {
+                    text::secondary
+                }
+            ),
+            Row::with_children(steps)
+                .width(FillPortion(song.pattern_len as u16))
+                .spacing(2),
+        ]
+        .spacing(5)
+        .height(Fill)
+    };
+
+    let transport = row![
+        if song.playing {
+            button(text("Stop").center()).on_press(msg(SongMessage::Stop))
+        } else {
+            button(text("Play").center()).on_press(msg(SongMessage::Play))
+        },
+        button(text("Rec").center())
+            .style(if song.recording {
+                button::danger
+            } else {
+                button::secondary
+            })
+            .on_press(msg(SongMessage::Record(!song.recording))),
+        text(format!("bar {} step {}", song.position + 1, song.step + 1)).center(),
+        tempo_slider(song.tempo),
+    ]
+    .spacing(10);
+
+    let seq = editor.step_seq;
+    let note = editor.step_note;
+    let steps = (0..song.pattern_len).map(|step| {
+        let notes = song
+            .step_notes(seq)
+            .filter(|seq_note| seq_note.step as usize == step);
+        let (has_note, has_other) = notes.fold((false, false), |(has, other), seq_note| {
+            (has || seq_note.note == note, other || seq_note.note != note)
+        });
+
+        button(text((step + 1).to_string()).size(10).center())
+            .style(if has_note {
+                button::primary
+            } else if song.playing && song.step == step {
+                button::success
+            } else if has_other {
+                button::secondary
+            } else {
+                button::text
+            })
+            .padding(2)
+            .width(FillPortion(1))
+            .height(Fill)
+            .on_press(msg(SongMessage::ToggleStep(step)))
+            .into()
+    });
+    let seqs: Vec<SeqChoice> = (0..N_SEQUENCES as u8)
+        .map(|seq| SeqChoice(Some(seq)))
+        .collect();
+
+    let stepper = row![
+        text("sequence").center(),
+        pick_list(seqs, Some(SeqChoice(Some(seq))), move |seq: SeqChoice| {
+            msg(SongMessage::StepSequence(seq.0.unwrap_or_default()))
+        }),
+        text("note").center(),
+        pick_list(NoteChoice::all(), Some(NoteChoice(note)), move |note| {
+            msg(SongMessage::StepNote(note.0))
+        }),
+        button(text("Clear").center())
+            .style(button::danger)
+            .on_press(msg(SongMessage::ClearSequence(seq))),
+    ]
+    .spacing(10);
+
+    column![
+        transport,
+        Column::with_children((0..N_CHANNELS).map(|channel| mk_channel(channel).into()))
+            .height(Fill)
+            .spacing(5),
+        stepper,
+        Row::with_children(steps).height(Fill).spacing(2),
+    ]
+    .width(Fill)
+    .height(Fill)
+    .spacing(10)
+    .padding(5)
+}
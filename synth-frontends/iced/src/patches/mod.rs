@@ -0,0 +1,41 @@
+use crate::Message;
+use iced::{
+    Length::Fill,
+    widget::{Row, button, pick_list, row, text, text_input},
+};
+use stepper_synth::sequencer::SequenceChannel;
+use synth_common::list_patches;
+
+/// the patch name being typed and the patches on disk.
+#[derive(Debug, Clone, Default)]
+pub struct PatchFiles {
+    pub name: String,
+    pub saved: Vec<String>,
+}
+
+impl PatchFiles {
+    /// no name yet, and the patches that are on disk.
+    pub fn load() -> Self {
+        Self {
+            name: String::new(),
+            saved: list_patches(),
+        }
+    }
+}
+
+/// saves the channel's sound under a name, or loads a saved one onto it.
+pub fn patch_panel<'a>(channel: SequenceChannel, files: &PatchFiles) -> Row<'a, Message> {
+    row![
+        text("Patch"),
+        text_input("name", &files.name)
+            .on_input(Message::PatchName)
+            .width(Fill),
+        button(text("Save").center())
+            .on_press_maybe((!files.name.trim().is_empty()).then_some(Message::SavePatch(channel))),
+        pick_list(files.saved.clone(), None::<String>, move |name| {
+            Message::LoadPatch { channel, name }
+        })
+        .placeholder("Load"),
+    ]
+    .spacing(10)
+}
@@ -0,0 +1,175 @@
+use crate::Message;
+use iced::{
+    Length::Fill,
+    Point, Rectangle, Renderer, Theme, mouse,
+    widget::{
+        Column, canvas,
+        canvas::{Frame, Geometry, Path, Stroke},
+        column, text,
+    },
+};
+use synth_common::ScopeFrame;
+use synth_lib::spectrum::{FLOOR_DB, spectrum};
+
+/// how many samples the scope draws.
+const TRACE_LEN: usize = 512;
+/// how many samples the spectrum is taken over.
+const FFT_LEN: usize = 1024;
+
+/// the synth's output over time.
+struct Trace {
+    samples: Vec<f32>,
+}
+
+impl Trace {
+    /// starts the trace on a rising zero crossing so a steady tone stands still.
+    fn triggered(samples: &[f32]) -> Self {
+        let search = samples.len().saturating_sub(TRACE_LEN);
+        let start = samples
+            .windows(2)
+            .take(search)
+            .position(|pair| pair[0] <= 0.0 && pair[1] > 0.0)
+            .unwrap_or(search);
+
+        Self {
+            samples: samples
+                .iter()
+                .skip(start)
+                .take(TRACE_LEN)
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+impl canvas::Program<Message> for Trace {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let (width, height) = (bounds.width, bounds.height);
+        let palette = theme.extended_palette();
+
+        let center = Path::line(
+            Point::new(0.0, height / 2.0),
+            Point::new(width, height / 2.0),
+        );
+        frame.stroke(
+            &center,
+            Stroke::default().with_color(palette.background.strong.color),
+        );
+
+        let step = width / TRACE_LEN.max(1) as f32;
+        let trace = Path::new(|path| {
+            for (i, sample) in self.samples.iter().enumerate() {
+                let point = Point::new(
+                    i as f32 * step,
+                    height / 2.0 - sample.clamp(-1.0, 1.0) * height / 2.0,
+                );
+
+                if i == 0 {
+                    path.move_to(point);
+                } else {
+                    path.line_to(point);
+                }
+            }
+        });
+        frame.stroke(
+            &trace,
+            Stroke::default()
+                .with_color(palette.primary.base.color)
+                .with_width(2.0),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// the synth's output by frequency, on a log frequency axis.
+struct Spectrum {
+    /// dB per bin.
+    bins: Vec<f32>,
+    /// the width of a bin in Hz.
+    bin_hz: f32,
+}
+
+impl canvas::Program<Message> for Spectrum {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let (width, height) = (bounds.width, bounds.height);
+        let palette = theme.extended_palette();
+        let (low, high) = (
+            20.0_f32.log10(),
+            (self.bins.len() as f32 * self.bin_hz).log10(),
+        );
+
+        let curve = Path::new(|path| {
+            // the DC bin has no place on a log axis.
+            for (i, db) in self.bins.iter().enumerate().skip(1) {
+                let hz = i as f32 * self.bin_hz;
+                let x = (hz.max(20.0).log10() - low) / (high - low) * width;
+                let y = db / FLOOR_DB * height;
+                let point = Point::new(x, y);
+
+                if i == 1 {
+                    path.move_to(point);
+                } else {
+                    path.line_to(point);
+                }
+            }
+        });
+        frame.stroke(
+            &curve,
+            Stroke::default()
+                .with_color(palette.success.base.color)
+                .with_width(2.0),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
+
+pub fn scope_screen<'a>(scope: &ScopeFrame) -> Column<'a, Message> {
+    let fft_start = scope.samples.len().saturating_sub(FFT_LEN);
+    let fft_samples = &scope.samples[fft_start..];
+    // the spectrum is taken over the largest power of two that fits.
+    let bin_hz = scope.sample_rate / (1_usize << fft_samples.len().max(1).ilog2()) as f32;
+
+    column![
+        text("Scope").size(24),
+        canvas(Trace::triggered(&scope.samples))
+            .width(Fill)
+            .height(Fill),
+        text(format!(
+            "Spectrum (up to {:.0} Hz)",
+            scope.sample_rate / 2.0
+        ))
+        .size(24),
+        canvas(Spectrum {
+            bins: spectrum(fft_samples),
+            bin_hz,
+        })
+        .width(Fill)
+        .height(Fill),
+    ]
+    .width(Fill)
+    .height(Fill)
+    .spacing(10)
+    .padding(10)
+}
@@ -0,0 +1,104 @@
+use crate::midi::PortSettings;
+use iced::Theme;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt::Display, fs, path::PathBuf};
+use stepper_synth::CHANNEL_SIZE;
+use tracing::*;
+
+/// the buffer sizes (in samples) the audio device can be started with.
+pub const BUFFER_SIZES: [usize; 4] = [256, 512, 1024, 2048];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    Latte,
+    Frappe,
+    Macchiato,
+    #[default]
+    Mocha,
+}
+
+impl ThemeChoice {
+    pub const ALL: [Self; 4] = [Self::Latte, Self::Frappe, Self::Macchiato, Self::Mocha];
+
+    pub fn theme(self) -> Theme {
+        match self {
+            Self::Latte => Theme::CatppuccinLatte,
+            Self::Frappe => Theme::CatppuccinFrappe,
+            Self::Macchiato => Theme::CatppuccinMacchiato,
+            Self::Mocha => Theme::CatppuccinMocha,
+        }
+    }
+}
+
+impl Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Latte => write!(f, "Latte"),
+            Self::Frappe => write!(f, "Frappé"),
+            Self::Macchiato => write!(f, "Macchiato"),
+            Self::Mocha => write!(f, "Mocha"),
+        }
+    }
+}
+
+/// the settings that are kept between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// the name of the output device to play through, `None` uses the host's default.
+    pub audio_card: Option<String>,
+    pub buffer_size: usize,
+    pub theme: ThemeChoice,
+    pub midi_ports: HashMap<String, PortSettings>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            audio_card: None,
+            buffer_size: CHANNEL_SIZE,
+            theme: ThemeChoice::default(),
+            midi_ports: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/synth-os/settings.toml`, falling back to `~/.config`.
+    pub fn path() -> PathBuf {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .unwrap_or_default();
+
+        config_dir.join("synth-os").join("settings.toml")
+    }
+
+    /// reads the config from disk, any problem reading it results in the default config.
+    pub fn load() -> Self {
+        let path = Self::path();
+
+        match fs::read_to_string(&path) {
+            Ok(config) => toml::from_str(&config).unwrap_or_else(|e| {
+                warn!(
+                    "{} is not a valid config, using the default. {e}",
+                    path.display()
+                );
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    Message,
+    backend::{Backend, LocalSynth, RemoteSynth},
+};
+use iced::{
+    Length::Fill,
+    widget::{Column, button, column, pick_list, row, scrollable, text, toggler},
+};
+use std::fmt::Display;
+
+pub use config::*;
+
+pub mod config;
+
+/// the output device to play through, `None` being the host's default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardChoice(pub Option<String>);
+
+impl Display for CardChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(ref card) => write!(f, "{card}"),
+            None => write!(f, "default"),
+        }
+    }
+}
+
+/// which channel a MIDI input is routed to, `None` leaves messages on the channel they were sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route(pub Option<u8>);
+
+impl Route {
+    pub const ALL: [Self; 5] = [
+        Self(None),
+        Self(Some(0)),
+        Self(Some(1)),
+        Self(Some(2)),
+        Self(Some(3)),
+    ];
+}
+
+impl Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(0) => write!(f, "A"),
+            Some(1) => write!(f, "B"),
+            Some(2) => write!(f, "C"),
+            Some(3) => write!(f, "D"),
+            Some(n) => write!(f, "{}", n + 1),
+            None => write!(f, "as sent"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SettingsMessage {
+    /// restarts the audio device on a new card.
+    SetCard(CardChoice),
+    /// restarts the audio device with a new buffer size.
+    SetBufferSize(usize),
+    /// looks for audio cards again.
+    RescanCards,
+    ResetStats,
+    SetTheme(ThemeChoice),
+    SetPortEnabled {
+        port: String,
+        enabled: bool,
+    },
+    SetPortRoute {
+        port: String,
+        route: Route,
+    },
+}
+
+fn msg(settings_msg: SettingsMessage) -> Message {
+    Message::Settings(settings_msg)
+}
+
+pub fn settings<'a>(config: &Config, cards: &[String], backend: &Backend) -> Column<'a, Message> {
+    let theme = column![
+        text("Theme").size(24),
+        pick_list(ThemeChoice::ALL, Some(config.theme), move |theme| {
+            msg(SettingsMessage::SetTheme(theme))
+        }),
+    ]
+    .spacing(10);
+
+    let sections = match backend {
+        Backend::Local(local) => column![
+            audio_settings(config, cards, local),
+            midi_settings(local),
+            theme
+        ],
+        Backend::Remote(remote) => column![connection_settings(remote), theme],
+    };
+
+    column![scrollable(sections.spacing(20).padding(10)).height(Fill)]
+        .width(Fill)
+        .height(Fill)
+}
+
+/// audio and MIDI are handled by the backend, so a remote UI only shows the connection.
+fn connection_settings<'a>(remote: &RemoteSynth) -> Column<'a, Message> {
+    column![
+        text("Backend").size(24),
+        row![text("Address").width(120), text(remote.addr.to_string())].spacing(10),
+        row![
+            text("Status").width(120),
+            text(if remote.connected() {
+                "connected"
+            } else {
+                "connecting..."
+            })
+        ]
+        .spacing(10),
+    ]
+    .spacing(10)
+}
+
+fn audio_settings<'a>(
+    config: &Config,
+    cards: &[String],
+    local: &LocalSynth,
+) -> Column<'a, Message> {
+    let stats = &local.stats;
+    let mut card_choices = vec![CardChoice(None)];
+    card_choices.extend(cards.iter().cloned().map(|card| CardChoice(Some(card))));
+
+    column![
+        text("Audio").size(24),
+        row![
+            text("Output").width(120),
+            pick_list(
+                card_choices,
+                Some(CardChoice(config.audio_card.clone())),
+                move |card| msg(SettingsMessage::SetCard(card)),
+            ),
+            button(text("Rescan")).on_press(msg(SettingsMessage::RescanCards)),
+        ]
+        .spacing(10),
+        row![
+            text("Buffer size").width(120),
+            pick_list(BUFFER_SIZES, Some(config.buffer_size), move |size| {
+                msg(SettingsMessage::SetBufferSize(size))
+            }),
+        ]
+        .spacing(10),
+        row![
+            text(format!("CPU load {:.0}%", stats.load() * 100.0)).width(120),
+            text(format!("xruns {}", stats.xruns())).width(120),
+            button(text("Reset")).on_press(msg(SettingsMessage::ResetStats)),
+        ]
+        .spacing(10),
+    ]
+    .spacing(10)
+}
+
+fn midi_settings<'a>(local: &LocalSynth) -> Column<'a, Message> {
+    let routing = local.routing.read().unwrap_or_else(|e| e.into_inner());
+
+    let ports = routing.connected.iter().map(|port| {
+        let port_settings = routing.settings(port);
+        let (toggle_port, route_port) = (port.clone(), port.clone());
+
+        row![
+            text(port.clone()).width(Fill),
+            toggler(port_settings.enabled).on_toggle(move |enabled| {
+                msg(SettingsMessage::SetPortEnabled {
+                    port: toggle_port.clone(),
+                    enabled,
+                })
+            }),
+            pick_list(Route::ALL, Some(Route(port_settings.route)), move |route| {
+                msg(SettingsMessage::SetPortRoute {
+                    port: route_port.clone(),
+                    route,
+                })
+            }),
+        ]
+        .spacing(10)
+        .into()
+    });
+    column![
+        text("MIDI Inputs").size(24),
+        if routing.connected.is_empty() {
+            column![text("no MIDI inputs connected")]
+        } else {
+            Column::with_children(ports).spacing(5)
+        },
+    ]
+    .spacing(10)
+}
@@ -1,17 +1,87 @@
 use crate::{Message, Screen};
 use iced::{
     Alignment::Center,
-    widget::{Column, button, column, text},
+    widget::{Column, button, column, row, text},
 };
+use synth_common::{ChannelState, EngineType, SynthState};
 
-pub fn side_bar<'a>(_focused_screen: Screen) -> Column<'a, Message> {
+/// a short name for an engine that fits on a sidebar button.
+fn engine_abbr(engine: EngineType) -> &'static str {
+    match engine {
+        EngineType::B3Organ => "Organ",
+        EngineType::SubSynth => "Sub",
+        EngineType::Wurlitzer => "Wurli",
+        EngineType::WaveTable => "WT",
+        EngineType::MidiOut => "MIDI",
+    }
+}
+
+/// the engine, note activity, sounding voices and mute of a channel.
+fn channel_status<'a>(chan: &ChannelState) -> Column<'a, Message> {
+    column![
+        text(engine_abbr(chan.engine)).size(12),
+        row![
+            text("●").size(12).style(if chan.held_notes > 0 {
+                text::success
+            } else {
+                text::secondary
+            }),
+            text(chan.active_voices).size(12).style(text::secondary),
+            text("M").size(12).style(if chan.mute {
+                text::danger
+            } else {
+                text::secondary
+            }),
+        ]
+        .spacing(5),
+    ]
+    .align_x(Center)
+}
+
+/// shows play (▶) and record (●) states.
+fn transport_status<'a>(playing: bool, recording: bool) -> Column<'a, Message> {
+    let mut status = row![].spacing(5);
+
+    if playing {
+        status = status.push(text("▶").size(12).style(text::success));
+    }
+
+    if recording {
+        status = status.push(text("●").size(12).style(text::danger));
+    }
+
+    column![status].align_x(Center)
+}
+
+pub fn side_bar<'a>(focused_screen: Screen, state: &SynthState) -> Column<'a, Message> {
     let b_size = 75;
 
     let button = |screen: Screen| {
-        button(text(screen.to_string()).align_x(Center).align_y(Center))
+        let lable = text(screen.to_string()).align_x(Center).align_y(Center);
+        let status = match screen {
+            Screen::MidiStepper | Screen::MidiSequenser => {
+                Some(transport_status(state.song.playing, state.song.recording))
+            }
+            Screen::ChannelA => Some(channel_status(&state.channels[0])),
+            Screen::ChannelB => Some(channel_status(&state.channels[1])),
+            Screen::ChannelC => Some(channel_status(&state.channels[2])),
+            Screen::ChannelD => Some(channel_status(&state.channels[3])),
+            Screen::ChannelEditor | Screen::Lfos | Screen::Scope | Screen::Settings => None,
+        };
+        let content = match status {
+            Some(status) => column![lable, status],
+            None => column![lable],
+        };
+
+        button(content.align_x(Center).spacing(2))
             .padding(5)
             .width(b_size)
             .height(b_size)
+            .style(if screen == focused_screen {
+                button::primary
+            } else {
+                button::secondary
+            })
             .on_press(Message::ScreenChange(screen))
     };
 
@@ -23,6 +93,15 @@ pub fn side_bar<'a>(_focused_screen: Screen) -> Column<'a, Message> {
         button(Screen::ChannelB),
         button(Screen::ChannelC),
         button(Screen::ChannelD),
+        button(Screen::Lfos),
+        button(Screen::Scope),
         button(Screen::Settings),
+        // always in reach, for stuck notes.
+        iced::widget::button(text("Panic").align_x(Center))
+            .padding(5)
+            .width(b_size)
+            .style(button::danger)
+            .on_press(Message::Panic),
     ]
+    .spacing(2)
 }
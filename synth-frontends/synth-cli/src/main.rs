@@ -1,5 +1,6 @@
 #![feature(let_chains)]
 
+use clap::Parser;
 use color_eyre::Result;
 use ratatui::{
     crossterm::{
@@ -10,14 +11,27 @@ use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
-use std::{error::Error, fmt::Display, io};
+use std::{error::Error, fmt::Display, io, path::PathBuf};
 use strum::IntoEnumIterator;
+use synth_common::{N_CHANNELS, socket_path};
 use tokens::Nodes;
 use tui_input::Input;
 use tui_input::backend::crossterm::EventHandler;
 
+pub mod send;
 pub mod tokens;
 
+/// types a command for the synth backend and sends it.
+#[derive(Debug, Parser)]
+struct Args {
+    /// the channel the command edits, from 1.
+    #[arg(short, long, default_value_t = 1)]
+    channel: u8,
+    /// the backend's unix socket, found the same way the backend picks it by default.
+    #[arg(short, long)]
+    socket: Option<PathBuf>,
+}
+
 // pub type Cmd = Vec<Box<dyn CmdToken>>;
 
 // pub trait CanEnumIter: Clone {
@@ -120,6 +134,13 @@ impl App {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    let channel = match args.channel.checked_sub(1) {
+        Some(channel) if (channel as usize) < N_CHANNELS => channel,
+        _ => return Err(format!("the channel has to be from 1 to {N_CHANNELS}").into()),
+    };
+    let socket = args.socket.unwrap_or_else(socket_path);
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -141,7 +162,11 @@ fn main() -> Result<(), Box<dyn Error>> {
     terminal.show_cursor()?;
 
     match res {
-        Ok(Some(path)) => println!("{}", path.replace(" ", "/")),
+        Ok(Some(line)) => {
+            let cmd = send::parse(channel, &line)?;
+            send::send(&socket, &cmd)?;
+            println!("sent {cmd:?}");
+        }
         Ok(None) => {
             println!("QUIT")
         }
@@ -182,8 +207,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<O
                     if cmd.to_lowercase() == "quit" {
                         break Ok(None);
                     } else {
-                        // Send Command to backend over *unix-domain-socket*
-
+                        // sent to the backend once the terminal is restored.
                         break Ok(Some(app.input.to_string()));
                     }
 
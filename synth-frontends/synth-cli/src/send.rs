@@ -0,0 +1,174 @@
+use std::{error::Error, fmt::Display, os::unix::net::UnixStream, path::Path};
+use synth_common::{CommandError, ModDest, ModSlot, ModSrc, SongCmd, UiToBackend};
+use tungstenite::{Message, client, error::Error as WsError};
+
+/// why a command line couldn't be sent.
+#[derive(Debug)]
+pub enum SendError {
+    /// the command isn't one the CLI can send yet.
+    Unsupported(String),
+    /// a word that isn't what the command takes there.
+    Unknown {
+        expected: &'static str,
+        got: String,
+    },
+    Missing(&'static str),
+    Invalid(CommandError),
+    Socket(std::io::Error),
+    WebSocket(WsError),
+}
+
+impl Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(cmd) => write!(f, "\"{cmd}\" can't be sent to the backend yet"),
+            Self::Unknown { expected, got } => write!(f, "expected {expected}, got \"{got}\""),
+            Self::Missing(what) => write!(f, "the command is missing {what}"),
+            Self::Invalid(e) => write!(f, "{e}"),
+            Self::Socket(e) => write!(f, "can't reach the backend. {e}"),
+            Self::WebSocket(e) => write!(f, "the backend connection failed. {e}"),
+        }
+    }
+}
+
+impl Error for SendError {}
+
+/// a 1 based number in a token, like the 2 in `lfo2`, as an index.
+fn index(number: &str) -> Option<u8> {
+    number.parse::<u8>().ok()?.checked_sub(1)
+}
+
+/// the mod source a `ModSrc` token names.
+fn mod_src(word: &str) -> Option<ModSrc> {
+    match word {
+        "velocity" | "vel" => Some(ModSrc::Velocity),
+        "mod-wheel" | "wheel" => Some(ModSrc::ModWheel),
+        "aftertouch" | "at" => Some(ModSrc::Aftertouch),
+        "key-track" | "key" => Some(ModSrc::KeyTrack),
+        _ => match word.strip_prefix("lfo") {
+            Some(lfo) => index(lfo).map(ModSrc::Lfo),
+            None => index(word.strip_prefix("env")?).map(ModSrc::Env),
+        },
+    }
+}
+
+/// the mod destination a `ModDest` token names, like `osc1-level` or `effect2-param3`.
+fn mod_dest(word: &str) -> Option<ModDest> {
+    let (part, param) = word.split_once('-')?;
+
+    if let Some(osc) = part.strip_prefix("osc") {
+        let osc = index(osc)?;
+
+        return match param {
+            "level" => Some(ModDest::OscLevel(osc)),
+            "detune" => Some(ModDest::OscDetune(osc)),
+            "position" => Some(ModDest::OscPosition(osc)),
+            _ => None,
+        };
+    }
+
+    if let Some(filter) = part.strip_prefix("filter") {
+        let filter = index(filter)?;
+
+        return match param {
+            "cutoff" => Some(ModDest::FilterCutoff(filter)),
+            "resonance" => Some(ModDest::FilterResonance(filter)),
+            "mix" => Some(ModDest::FilterMix(filter)),
+            _ => None,
+        };
+    }
+
+    Some(ModDest::EffectParam {
+        slot: index(part.strip_prefix("effect")?)?,
+        param: index(param.strip_prefix("param")?)?,
+    })
+}
+
+/// the next word, read with `read`.
+fn next<T>(
+    words: &mut std::slice::Iter<&str>,
+    expected: &'static str,
+    read: impl FnOnce(&str) -> Option<T>,
+) -> Result<T, SendError> {
+    let word = words.next().ok_or(SendError::Missing(expected))?;
+
+    read(word).ok_or_else(|| SendError::Unknown {
+        expected,
+        got: word.to_string(),
+    })
+}
+
+/// the command a finished command line stands for, `channel` is the channel it edits.
+pub fn parse(channel: u8, line: &str) -> Result<UiToBackend, SendError> {
+    let line = line.to_lowercase();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let mut words = words.iter();
+
+    let cmd = match words.next().copied() {
+        Some("panic" | "all-notes-off") => UiToBackend::Panic,
+        Some("tempo" | "bpm") => {
+            let tempo = next(&mut words, "a tempo in bpm", |word| {
+                word.parse::<f32>().ok().filter(|tempo| tempo.is_finite())
+            })?;
+
+            UiToBackend::Song(SongCmd::SetTempo(tempo))
+        }
+        Some("mod-matrix" | "patch" | "mod-m") => {
+            let connect = next(&mut words, "connect or disconnect", |word| match word {
+                "con" | "connect" => Some(true),
+                "dcon" | "disconnect" | "discon" => Some(false),
+                _ => None,
+            })?;
+            let src = next(&mut words, "a mod source", mod_src)?;
+            let dest = next(&mut words, "a mod destination", mod_dest)?;
+
+            if connect {
+                let amount = next(&mut words, "an amount from -1.0 to 1.0", |word| {
+                    word.parse::<f32>().ok().filter(|amount| amount.is_finite())
+                })?;
+
+                UiToBackend::ModConnect {
+                    channel,
+                    slot: ModSlot {
+                        src,
+                        dest,
+                        amount: amount.clamp(-1.0, 1.0),
+                    },
+                }
+            } else {
+                UiToBackend::ModDisconnect { channel, src, dest }
+            }
+        }
+        _ => return Err(SendError::Unsupported(line.trim().to_string())),
+    };
+
+    cmd.validate().map_err(SendError::Invalid)?;
+
+    Ok(cmd)
+}
+
+/// sends `cmd` to the backend listening on `socket`, over the same `/ws` endpoint the UIs use.
+/// the unix socket is trusted, so no token is needed.
+pub fn send(socket: &Path, cmd: &UiToBackend) -> Result<(), SendError> {
+    let stream = UnixStream::connect(socket).map_err(SendError::Socket)?;
+    let (mut ws, _) = client("ws://localhost/ws", stream).map_err(|e| match e {
+        tungstenite::HandshakeError::Failure(e) => SendError::WebSocket(e),
+        tungstenite::HandshakeError::Interrupted(_) => {
+            SendError::WebSocket(WsError::ConnectionClosed)
+        }
+    })?;
+    let json = serde_json::to_string(cmd).expect("commands always serialize");
+
+    ws.send(Message::text(json)).map_err(SendError::WebSocket)?;
+    ws.close(None).map_err(SendError::WebSocket)?;
+
+    // the backend has read the command once it answers the close, the states it sends before
+    // that are dropped.
+    loop {
+        match ws.read() {
+            Ok(_) => {}
+            Err(WsError::ConnectionClosed | WsError::AlreadyClosed) => return Ok(()),
+            Err(e) => return Err(SendError::WebSocket(e)),
+        }
+    }
+}
@@ -279,18 +279,47 @@ impl CmdToken for PowerState {
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
-pub enum ModSrc {}
+pub enum ModSrc {
+    Lfo1,
+    Lfo2,
+    Lfo3,
+    Lfo4,
+    Env1,
+    Env2,
+    Velocity,
+    ModWheel,
+    Aftertouch,
+    KeyTrack,
+}
 
 impl CmdToken for ModSrc {
     fn get_one_desc(&self) -> String {
         match *self {
-            _ => String::new()
+            Self::Lfo1 => "LFO 1".into(),
+            Self::Lfo2 => "LFO 2".into(),
+            Self::Lfo3 => "LFO 3".into(),
+            Self::Lfo4 => "LFO 4".into(),
+            Self::Env1 => "Envelope 1, retriggered by each note".into(),
+            Self::Env2 => "Envelope 2, retriggered by each note".into(),
+            Self::Velocity => "How hard the last note was played".into(),
+            Self::ModWheel => "The mod wheel (CC 1)".into(),
+            Self::Aftertouch => "Channel or poly aftertouch, whichever is pressed harder".into(),
+            Self::KeyTrack => "How far the last note is from middle C".into(),
         }
     }
 
     fn get_desc_name(&self) -> Arc<[&str]> {
         match *self {
-            _ => []
+            Self::Lfo1 => ["lfo1"].into(),
+            Self::Lfo2 => ["lfo2"].into(),
+            Self::Lfo3 => ["lfo3"].into(),
+            Self::Lfo4 => ["lfo4"].into(),
+            Self::Env1 => ["env1"].into(),
+            Self::Env2 => ["env2"].into(),
+            Self::Velocity => ["velocity", "vel"].into(),
+            Self::ModWheel => ["mod-wheel", "wheel"].into(),
+            Self::Aftertouch => ["aftertouch", "at"].into(),
+            Self::KeyTrack => ["key-track", "key"].into(),
         }
     }
 
@@ -301,23 +330,115 @@ impl CmdToken for ModSrc {
     }
 
     fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
-        Vec::new()
+        ModDest::into_vec().into_iter().map(|dest| Box::new(dest).into()).collect()
     }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
-pub enum ModDest {}
+pub enum ModDest {
+    Osc1Level,
+    Osc1Detune,
+    Osc1Position,
+    Osc2Level,
+    Osc2Detune,
+    Osc2Position,
+    Osc3Level,
+    Osc3Detune,
+    Osc3Position,
+    Filter1Cutoff,
+    Filter1Resonance,
+    Filter1Mix,
+    Filter2Cutoff,
+    Filter2Resonance,
+    Filter2Mix,
+    Effect1Param1,
+    Effect1Param2,
+    Effect1Param3,
+    Effect1Param4,
+    Effect1Param5,
+    Effect1Param6,
+    Effect1Param7,
+    Effect1Param8,
+    Effect2Param1,
+    Effect2Param2,
+    Effect2Param3,
+    Effect2Param4,
+    Effect2Param5,
+    Effect2Param6,
+    Effect2Param7,
+    Effect2Param8,
+}
 
 impl CmdToken for ModDest {
     fn get_one_desc(&self) -> String {
         match *self {
-            _ => String::new()
+            Self::Osc1Level => "The level of oscillator 1".into(),
+            Self::Osc1Detune => "The detune of oscillator 1".into(),
+            Self::Osc1Position => "Where oscillator 1 plays in its wavetable".into(),
+            Self::Osc2Level => "The level of oscillator 2".into(),
+            Self::Osc2Detune => "The detune of oscillator 2".into(),
+            Self::Osc2Position => "Where oscillator 2 plays in its wavetable".into(),
+            Self::Osc3Level => "The level of oscillator 3".into(),
+            Self::Osc3Detune => "The detune of oscillator 3".into(),
+            Self::Osc3Position => "Where oscillator 3 plays in its wavetable".into(),
+            Self::Filter1Cutoff => "The cutoff of filter 1".into(),
+            Self::Filter1Resonance => "The resonance of filter 1".into(),
+            Self::Filter1Mix => "The mix of filter 1".into(),
+            Self::Filter2Cutoff => "The cutoff of filter 2".into(),
+            Self::Filter2Resonance => "The resonance of filter 2".into(),
+            Self::Filter2Mix => "The mix of filter 2".into(),
+            Self::Effect1Param1 => "Param 1 of the effect in slot 1".into(),
+            Self::Effect1Param2 => "Param 2 of the effect in slot 1".into(),
+            Self::Effect1Param3 => "Param 3 of the effect in slot 1".into(),
+            Self::Effect1Param4 => "Param 4 of the effect in slot 1".into(),
+            Self::Effect1Param5 => "Param 5 of the effect in slot 1".into(),
+            Self::Effect1Param6 => "Param 6 of the effect in slot 1".into(),
+            Self::Effect1Param7 => "Param 7 of the effect in slot 1".into(),
+            Self::Effect1Param8 => "Param 8 of the effect in slot 1".into(),
+            Self::Effect2Param1 => "Param 1 of the effect in slot 2".into(),
+            Self::Effect2Param2 => "Param 2 of the effect in slot 2".into(),
+            Self::Effect2Param3 => "Param 3 of the effect in slot 2".into(),
+            Self::Effect2Param4 => "Param 4 of the effect in slot 2".into(),
+            Self::Effect2Param5 => "Param 5 of the effect in slot 2".into(),
+            Self::Effect2Param6 => "Param 6 of the effect in slot 2".into(),
+            Self::Effect2Param7 => "Param 7 of the effect in slot 2".into(),
+            Self::Effect2Param8 => "Param 8 of the effect in slot 2".into(),
         }
     }
 
     fn get_desc_name(&self) -> Arc<[&str]> {
         match *self {
-            _ => []
+            Self::Osc1Level => ["osc1-level"].into(),
+            Self::Osc1Detune => ["osc1-detune"].into(),
+            Self::Osc1Position => ["osc1-position"].into(),
+            Self::Osc2Level => ["osc2-level"].into(),
+            Self::Osc2Detune => ["osc2-detune"].into(),
+            Self::Osc2Position => ["osc2-position"].into(),
+            Self::Osc3Level => ["osc3-level"].into(),
+            Self::Osc3Detune => ["osc3-detune"].into(),
+            Self::Osc3Position => ["osc3-position"].into(),
+            Self::Filter1Cutoff => ["filter1-cutoff"].into(),
+            Self::Filter1Resonance => ["filter1-resonance"].into(),
+            Self::Filter1Mix => ["filter1-mix"].into(),
+            Self::Filter2Cutoff => ["filter2-cutoff"].into(),
+            Self::Filter2Resonance => ["filter2-resonance"].into(),
+            Self::Filter2Mix => ["filter2-mix"].into(),
+            Self::Effect1Param1 => ["effect1-param1"].into(),
+            Self::Effect1Param2 => ["effect1-param2"].into(),
+            Self::Effect1Param3 => ["effect1-param3"].into(),
+            Self::Effect1Param4 => ["effect1-param4"].into(),
+            Self::Effect1Param5 => ["effect1-param5"].into(),
+            Self::Effect1Param6 => ["effect1-param6"].into(),
+            Self::Effect1Param7 => ["effect1-param7"].into(),
+            Self::Effect1Param8 => ["effect1-param8"].into(),
+            Self::Effect2Param1 => ["effect2-param1"].into(),
+            Self::Effect2Param2 => ["effect2-param2"].into(),
+            Self::Effect2Param3 => ["effect2-param3"].into(),
+            Self::Effect2Param4 => ["effect2-param4"].into(),
+            Self::Effect2Param5 => ["effect2-param5"].into(),
+            Self::Effect2Param6 => ["effect2-param6"].into(),
+            Self::Effect2Param7 => ["effect2-param7"].into(),
+            Self::Effect2Param8 => ["effect2-param8"].into(),
         }
     }
 
@@ -328,14 +449,15 @@ impl CmdToken for ModDest {
     }
 
     fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
-        Vec::new()
+        // the amount, from -1.0 to 1.0, when connecting.
+        Float::into_vec().into_iter().map(|f| Box::new(f).into()).collect()
     }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
 pub enum MatrixCmdArgs {
     Connect,    // (ModSrc, ModDest, f32),
-    Disconnect, // (),
+    Disconnect, // (ModSrc, ModDest),
 }
 
 impl CmdToken for MatrixCmdArgs {
@@ -360,7 +482,8 @@ impl CmdToken for MatrixCmdArgs {
     }
 
     fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
-        ModSrc::into_vec()
+        // both take the source then the destination, connect also takes an amount.
+        ModSrc::into_vec().into_iter().map(|src| Box::new(src).into()).collect()
     }
 }
 
@@ -413,15 +536,177 @@ pub struct LfoNum(usize);
 
 impl Display for LfoNum {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "")
+        write!(f, "LfoNum")
     }
 }
 
-// impl CmdToken for LfoNum {
-//     fn get_one_desc(&self) -> String {
-//         "Which LFO?".into()
-//     }
-// }
+impl CmdToken for LfoNum {
+    fn get_one_desc(&self) -> String {
+        format!("LFO {}", self.0 + 1)
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match self.0 {
+            0 => ["1", "lfo1"].into(),
+            1 => ["2", "lfo2"].into(),
+            2 => ["3", "lfo3"].into(),
+            _ => ["4", "lfo4"].into(),
+        }
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        // the global LFO bank has four.
+        (0..4).map(Self).collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        LfoParam::into_vec().into_iter().map(|param| Box::new(param).into()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum LfoParam {
+    Shape,
+    Rate,
+    Sync,
+    Retrigger,
+    Phase,
+    FadeIn,
+}
+
+impl CmdToken for LfoParam {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Shape => "The LFO's wave shape",
+            Self::Rate => "Cycles a second, when not synced",
+            Self::Sync => "Follow the song's tempo, cycling once every so many bars or beats",
+            Self::Retrigger => "Restart the LFO on each new note instead of running freely",
+            Self::Phase => "Where in the cycle the LFO starts, 0.0 to 1.0",
+            Self::FadeIn => "Seconds the LFO takes to reach full depth after a new note",
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Shape => ["shape", "wave"].into(),
+            Self::Rate => ["rate", "speed"].into(),
+            Self::Sync => ["sync"].into(),
+            Self::Retrigger => ["retrigger", "retrig"].into(),
+            Self::Phase => ["phase"].into(),
+            Self::FadeIn => ["fade-in", "fade"].into(),
+        }
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        match *self {
+            Self::Shape => LfoShape::into_vec().into_iter().map(|shape| Box::new(shape).into()).collect(),
+            Self::Sync => LfoSync::into_vec().into_iter().map(|sync| Box::new(sync).into()).collect(),
+            Self::Retrigger => PowerState::into_vec().into_iter().map(|on| Box::new(on).into()).collect(),
+            Self::Rate | Self::Phase | Self::FadeIn => {
+                Float::into_vec().into_iter().map(|f| Box::new(f).into()).collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+    SampleHold,
+}
+
+impl CmdToken for LfoShape {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Sine => "Sine wave",
+            Self::Triangle => "Triangle wave",
+            Self::Saw => "Rising saw wave",
+            Self::Square => "Square wave",
+            Self::SampleHold => "A new random value each cycle",
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Sine => ["sine", "sin"].into(),
+            Self::Triangle => ["triangle", "tri"].into(),
+            Self::Saw => ["saw"].into(),
+            Self::Square => ["square", "sqr"].into(),
+            Self::SampleHold => ["sample-hold", "s&h", "random"].into(),
+        }
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum LfoSync {
+    Off,
+    FourBars,
+    TwoBars,
+    Bar,
+    Half,
+    Quarter,
+    Eighth,
+    Sixteenth,
+}
+
+impl CmdToken for LfoSync {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Off => "Run at the LFO's own rate",
+            Self::FourBars => "One cycle every 4 bars",
+            Self::TwoBars => "One cycle every 2 bars",
+            Self::Bar => "One cycle a bar",
+            Self::Half => "One cycle every half note",
+            Self::Quarter => "One cycle a beat",
+            Self::Eighth => "One cycle every eighth note",
+            Self::Sixteenth => "One cycle every sixteenth note",
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Off => ["off", "free"].into(),
+            Self::FourBars => ["4-bars", "4/1"].into(),
+            Self::TwoBars => ["2-bars", "2/1"].into(),
+            Self::Bar => ["bar", "1/1"].into(),
+            Self::Half => ["half", "1/2"].into(),
+            Self::Quarter => ["quarter", "1/4"].into(),
+            Self::Eighth => ["eighth", "1/8"].into(),
+            Self::Sixteenth => ["sixteenth", "1/16"].into(),
+        }
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        Vec::new()
+    }
+}
 
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub struct SeqNum(usize);
@@ -788,6 +1073,300 @@ impl CmdToken for ReverbParams {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum ChorusParams {
+    Rate,
+    Depth,
+    Mix,
+    Voices,
+    Width,
+    PowerState,
+}
+
+impl CmdToken for ChorusParams {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Rate => "How fast the chorus sweeps",
+            Self::Depth => "How far the chorus sweeps",
+            Self::Mix => "How much of the chorus is heard over the dry signal",
+            Self::Voices => "How many delayed copies the chorus adds",
+            Self::Width => "How far apart the chorus voices are spread",
+            Self::PowerState => "Is the chorus on or off"
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Rate => ["rate", "speed"],
+            Self::Depth => ["depth"],
+            Self::Mix => ["mix"],
+            Self::Voices => ["voices"],
+            Self::Width => ["width", "stereo-width"],
+            Self::PowerState => ["power"],
+        }.into()
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        match *self {
+            Self::Rate => Float::into_vec(),
+            Self::Depth => Float::into_vec(),
+            Self::Mix => Float::into_vec(),
+            Self::Voices => Float::into_vec(),
+            Self::Width => Float::into_vec(),
+            Self::PowerState => PowerState::iter(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum DelayParams {
+    Time,
+    Sync,
+    Feedback,
+    Mix,
+    PingPong,
+    PowerState,
+}
+
+impl CmdToken for DelayParams {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Time => "How long until the repeats, when not synced",
+            Self::Sync => "The note length the repeats follow the tempo at, or off",
+            Self::Feedback => "How many times the delay repeats",
+            Self::Mix => "How much of the delay is heard over the dry signal",
+            Self::PingPong => "How far the repeats bounce between left and right",
+            Self::PowerState => "Is the delay on or off"
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Time => ["time"],
+            Self::Sync => ["sync", "tempo-sync"],
+            Self::Feedback => ["feedback", "repeats"],
+            Self::Mix => ["mix"],
+            Self::PingPong => ["ping-pong", "stereo"],
+            Self::PowerState => ["power"],
+        }.into()
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        match *self {
+            Self::Time => Float::into_vec(),
+            Self::Sync => Float::into_vec(),
+            Self::Feedback => Float::into_vec(),
+            Self::Mix => Float::into_vec(),
+            Self::PingPong => Float::into_vec(),
+            Self::PowerState => PowerState::iter(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum DriveParams {
+    Drive,
+    Tone,
+    Mix,
+    Level,
+    PowerState,
+}
+
+impl CmdToken for DriveParams {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Drive => "How hard the signal is clipped",
+            Self::Tone => "How bright the driven signal is",
+            Self::Mix => "How much of the drive is heard over the dry signal",
+            Self::Level => "How loud the driven signal is",
+            Self::PowerState => "Is the drive on or off"
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Drive => ["drive", "gain"],
+            Self::Tone => ["tone"],
+            Self::Mix => ["mix"],
+            Self::Level => ["level", "volume"],
+            Self::PowerState => ["power"],
+        }.into()
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        match *self {
+            Self::Drive => Float::into_vec(),
+            Self::Tone => Float::into_vec(),
+            Self::Mix => Float::into_vec(),
+            Self::Level => Float::into_vec(),
+            Self::PowerState => PowerState::iter(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum EqParams {
+    Low,
+    Mid,
+    High,
+    Frequency,
+    PowerState,
+}
+
+impl CmdToken for EqParams {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Low => "Boost or cut the lows",
+            Self::Mid => "Boost or cut the mids",
+            Self::High => "Boost or cut the highs",
+            Self::Frequency => "Where the mid band is centered",
+            Self::PowerState => "Is the EQ on or off"
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Low => ["low", "bass"],
+            Self::Mid => ["mid"],
+            Self::High => ["high", "treble"],
+            Self::Frequency => ["frequency", "mid-freq"],
+            Self::PowerState => ["power"],
+        }.into()
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        match *self {
+            Self::Low => Float::into_vec(),
+            Self::Mid => Float::into_vec(),
+            Self::High => Float::into_vec(),
+            Self::Frequency => Float::into_vec(),
+            Self::PowerState => PowerState::iter(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum CompressorParams {
+    Threshold,
+    Ratio,
+    Attack,
+    Release,
+    Makeup,
+    PowerState,
+}
+
+impl CmdToken for CompressorParams {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Threshold => "The level the compressor starts working at",
+            Self::Ratio => "How hard loud signals are turned down",
+            Self::Attack => "How fast the compressor clamps down",
+            Self::Release => "How fast the compressor lets go",
+            Self::Makeup => "How much the compressed signal is turned back up",
+            Self::PowerState => "Is the compressor on or off"
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Threshold => ["threshold"],
+            Self::Ratio => ["ratio"],
+            Self::Attack => ["attack"],
+            Self::Release => ["release"],
+            Self::Makeup => ["makeup", "makeup-gain"],
+            Self::PowerState => ["power"],
+        }.into()
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        match *self {
+            Self::Threshold => Float::into_vec(),
+            Self::Ratio => Float::into_vec(),
+            Self::Attack => Float::into_vec(),
+            Self::Release => Float::into_vec(),
+            Self::Makeup => Float::into_vec(),
+            Self::PowerState => PowerState::iter(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, EnumIter, Display)]
+pub enum PhaserParams {
+    Rate,
+    Depth,
+    Feedback,
+    Mix,
+    PowerState,
+}
+
+impl CmdToken for PhaserParams {
+    fn get_one_desc(&self) -> String {
+        match *self {
+            Self::Rate => "How fast the phaser sweeps",
+            Self::Depth => "How wide the phaser sweeps",
+            Self::Feedback => "How sharp the phaser's notches are",
+            Self::Mix => "How much of the phaser is heard over the dry signal",
+            Self::PowerState => "Is the phaser on or off"
+        }.into()
+    }
+
+    fn get_desc_name(&self) -> Arc<[&str]> {
+        match *self {
+            Self::Rate => ["rate", "speed"],
+            Self::Depth => ["depth"],
+            Self::Feedback => ["feedback", "resonance"],
+            Self::Mix => ["mix"],
+            Self::PowerState => ["power"],
+        }.into()
+    }
+
+    fn into_vec() -> Vec<Self>
+        where
+            Self: Sized {
+        Self::iter().collect()
+    }
+
+    fn get_sugestions(&self, tokens: &[NodeType]) -> Vec<Node> {
+        match *self {
+            Self::Rate => Float::into_vec(),
+            Self::Depth => Float::into_vec(),
+            Self::Feedback => Float::into_vec(),
+            Self::Mix => Float::into_vec(),
+            Self::PowerState => PowerState::iter(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, EnumIter, Display)]
 pub enum CmdContext {
     /// set organ params
@@ -798,12 +1377,26 @@ pub enum CmdContext {
     Reverb,
     /// set chourus effect params
     Chorus,
+    /// set delay effect params
+    Delay,
+    /// set drive effect params
+    Drive,
+    /// set EQ effect params
+    Eq,
+    /// set compressor effect params
+    Compressor,
+    /// set phaser effect params
+    Phaser,
     /// chagne LFO parameters
     Lfo,
     /// make-new/break-old connections in the mod matrix
     Matrix, // (MatrixCmdArgs),
     /// Goto display
     GoTo, // (Screen),
+    /// stop every voice on every channel
+    Panic,
+    /// set the song's tempo
+    Tempo,
 }
 
 impl CmdToken for CmdContext {
@@ -815,9 +1408,16 @@ impl CmdToken for CmdContext {
             Self::SubSynth => "Subtractive synth engine params".into(),
             Self::Reverb => "Reverb effect params".into(),
             Self::Chorus => "Chorus effect params".into(),
+            Self::Delay => "Delay effect params".into(),
+            Self::Drive => "Drive effect params".into(),
+            Self::Eq => "EQ effect params".into(),
+            Self::Compressor => "Compressor effect params".into(),
+            Self::Phaser => "Phaser effect params".into(),
             Self::Lfo => "Controls over the LFOs".into(),
             Self::Matrix => "Edit the mod-matrix".into(),
             Self::GoTo => "Change the active screen".into(),
+            Self::Panic => "Stop all notes and reset the controllers on every channel".into(),
+            Self::Tempo => "Set the tempo the song, synced LFOs and effects follow, in bpm".into(),
         }
     }
 
@@ -827,9 +1427,16 @@ impl CmdToken for CmdContext {
             Self::SubSynth => ["synth"].into(),
             Self::Reverb => ["reverb", "verb"].into(),
             Self::Chorus => ["chorus"].into(),
+            Self::Delay => ["delay", "echo"].into(),
+            Self::Drive => ["drive", "distortion", "dist"].into(),
+            Self::Eq => ["eq"].into(),
+            Self::Compressor => ["compressor", "comp"].into(),
+            Self::Phaser => ["phaser"].into(),
             Self::Lfo => ["lfo"].into(),
             Self::Matrix => ["mod-matrix", "patch", "mod-m"].into(),
             Self::GoTo => ["goto", "screen", "view"].into(),
+            Self::Panic => ["panic", "all-notes-off"].into(),
+            Self::Tempo => ["tempo", "bpm"].into(),
         }
     }
 
@@ -845,11 +1452,18 @@ impl CmdToken for CmdContext {
             [] => Self::into_vec(),
             [NodeType::Known(Self::Organ)] => OrganParam::into_vec()
             [NodeType::Known(Self::SubSynth)] => SubSynthParam::into_vec(),
-            [NodeType::Known(Self::Lfo)] => [].to_vec(),
+            [NodeType::Known(Self::Lfo)] => LfoNum::into_vec(),
             [NodeType::Known(Self::Reverb)] => ReverbParams::into_vec(),
-            [NodeType::Known(Self::Chorus)] => [].to_vec(),
+            [NodeType::Known(Self::Chorus)] => ChorusParams::into_vec(),
+            [NodeType::Known(Self::Delay)] => DelayParams::into_vec(),
+            [NodeType::Known(Self::Drive)] => DriveParams::into_vec(),
+            [NodeType::Known(Self::Eq)] => EqParams::into_vec(),
+            [NodeType::Known(Self::Compressor)] => CompressorParams::into_vec(),
+            [NodeType::Known(Self::Phaser)] => PhaserParams::into_vec(),
             [NodeType::Known(Self::Matrix)] => MatrixCmdArgs::into_vec(),  // [].to_vec(),
             [NodeType::Known(Self::GoTo)] => GoToParams::into_vec(),  // [].to_vec(),
+            [NodeType::Known(Self::Panic)] => [].to_vec(),
+            [NodeType::Known(Self::Tempo)] => [].to_vec(),
         }
     }
 }
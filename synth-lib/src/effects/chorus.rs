@@ -0,0 +1,144 @@
+use super::{delay::DelayLine, exp_range};
+use crate::mixer::Frame;
+use std::f32::consts::TAU;
+
+/// the most delayed copies the chorus mixes in.
+pub const MAX_CHORUS_VOICES: usize = 4;
+/// the delay the copies sweep around, and how far a full depth sweeps them, in seconds.
+const BASE_DELAY: f32 = 0.007;
+const MAX_DEPTH: f32 = 0.005;
+/// how far ahead of the left side's sweep the right side's runs at full width, in turns.
+const MAX_WIDTH: f32 = 0.25;
+
+/// a chorus, copies of each side on sine swept delays spaced evenly around the sweep. the width
+/// runs the right side's sweep ahead of the left's, so the copies move apart across the sides.
+#[derive(Clone, Debug)]
+pub struct Chorus {
+    /// rate, depth, mix, voices, width.
+    pub(super) params: [f32; 5],
+    sample_rate: f32,
+    phase: f32,
+    lines: [DelayLine; 2],
+}
+
+impl Chorus {
+    pub fn new(sample_rate: f32) -> Self {
+        let len = ((BASE_DELAY + MAX_DEPTH) * sample_rate) as usize + 2;
+
+        Self {
+            params: [0.3, 0.5, 0.5, 0.3, 0.5],
+            sample_rate,
+            phase: 0.0,
+            lines: [DelayLine::new(len), DelayLine::new(len)],
+        }
+    }
+
+    pub(super) fn set_param(&mut self, param: usize, value: f32) {
+        if let Some(p) = self.params.get_mut(param) {
+            *p = value;
+        }
+    }
+
+    /// the sweep's rate in Hz.
+    pub fn rate(&self) -> f32 {
+        exp_range(self.params[0], 0.05, 5.0)
+    }
+
+    /// how many delayed copies are mixed in, from 1 to `MAX_CHORUS_VOICES`.
+    pub fn voices(&self) -> usize {
+        1 + (self.params[3] * (MAX_CHORUS_VOICES - 1) as f32).round() as usize
+    }
+
+    pub(super) fn process(&mut self, frame: Frame) -> Frame {
+        let [_, depth, mix, _, width] = self.params;
+        let voices = self.voices();
+
+        self.phase = (self.phase + self.rate() / self.sample_rate).fract();
+
+        std::array::from_fn(|side| {
+            let line = &mut self.lines[side];
+            let ahead = side as f32 * width * MAX_WIDTH;
+            line.write(frame[side]);
+
+            let wet = (0..voices)
+                .map(|voice| {
+                    let phase = self.phase + ahead + voice as f32 / voices as f32;
+                    let sweep = 0.5 + 0.5 * (phase * TAU).sin();
+
+                    line.read((BASE_DELAY + MAX_DEPTH * depth * sweep) * self.sample_rate)
+                })
+                .sum::<f32>()
+                / voices as f32;
+
+            frame[side] * (1.0 - mix) + wet * mix
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::sine;
+
+    const SAMPLE_RATE: f32 = 48_000.0;
+
+    fn chorus(width: f32) -> Chorus {
+        let mut chorus = Chorus::new(SAMPLE_RATE);
+        chorus.set_param(4, width);
+
+        chorus
+    }
+
+    #[test]
+    fn no_width_keeps_the_sides_together() {
+        let mut chorus = chorus(0.0);
+
+        for frame in sine(440.0, SAMPLE_RATE, 0.5) {
+            let [left, right] = chorus.process(frame);
+
+            assert_eq!(left, right);
+        }
+    }
+
+    #[test]
+    fn width_spreads_the_sides_apart() {
+        let mut chorus = chorus(1.0);
+        let spread = sine(440.0, SAMPLE_RATE, 0.5)
+            .map(|frame| chorus.process(frame))
+            .fold(0.0_f32, |spread, [left, right]| {
+                spread.max((left - right).abs())
+            });
+
+        assert!(spread > 0.1, "{spread}");
+    }
+
+    #[test]
+    fn still_copies_are_delayed_by_the_base_delay() {
+        let mut chorus = chorus(1.0);
+        chorus.set_param(1, 0.0);
+        chorus.set_param(2, 1.0);
+        let out: Vec<_> = (0..SAMPLE_RATE as usize / 50)
+            .map(|i| chorus.process(if i == 0 { [1.0; 2] } else { [0.0; 2] }))
+            .collect();
+        let loudest = (0..out.len())
+            .max_by(|&a, &b| out[a][0].abs().total_cmp(&out[b][0].abs()))
+            .unwrap();
+
+        assert!((loudest as f32 - BASE_DELAY * SAMPLE_RATE).abs() <= 1.0);
+        assert!(out.iter().all(|[left, right]| left == right));
+    }
+
+    #[test]
+    fn full_settings_stay_bounded() {
+        let mut chorus = chorus(1.0);
+        for param in 0..5 {
+            chorus.set_param(param, 1.0);
+        }
+
+        for frame in sine(440.0, SAMPLE_RATE, 1.0) {
+            let out = chorus.process(frame);
+
+            assert!(out.iter().all(|side| side.abs() <= 1.0 + 1e-6));
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use super::{db_to_gain, exp_range};
+use crate::mixer::Frame;
+
+/// a feed forward peak compressor. both sides follow the louder one so the image holds still.
+#[derive(Clone, Debug)]
+pub struct Compressor {
+    /// threshold, ratio, attack, release, makeup.
+    pub(super) params: [f32; 5],
+    sample_rate: f32,
+    /// how much of the level the envelope keeps each sample when rising and falling.
+    attack: f32,
+    release: f32,
+    env: f32,
+}
+
+impl Compressor {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut comp = Self {
+            params: [0.7, 0.2, 0.3, 0.3, 0.0],
+            sample_rate,
+            attack: 0.0,
+            release: 0.0,
+            env: 0.0,
+        };
+        comp.update();
+
+        comp
+    }
+
+    pub(super) fn set_param(&mut self, param: usize, value: f32) {
+        if let Some(p) = self.params.get_mut(param) {
+            *p = value;
+            self.update();
+        }
+    }
+
+    fn update(&mut self) {
+        let keep = |secs: f32| (-1.0 / (secs * self.sample_rate).max(1.0)).exp();
+
+        self.attack = keep(exp_range(self.params[2], 0.0001, 0.1));
+        self.release = keep(exp_range(self.params[3], 0.01, 1.0));
+    }
+
+    /// the level where compression starts, in dB.
+    pub fn threshold_db(&self) -> f32 {
+        -60.0 + self.params[0] * 60.0
+    }
+
+    pub fn ratio(&self) -> f32 {
+        1.0 + self.params[1] * 19.0
+    }
+
+    pub fn makeup_db(&self) -> f32 {
+        self.params[4] * 24.0
+    }
+
+    pub(super) fn process(&mut self, frame: Frame) -> Frame {
+        let level = frame[0].abs().max(frame[1].abs());
+        let keep = if level > self.env {
+            self.attack
+        } else {
+            self.release
+        };
+        self.env = level + (self.env - level) * keep;
+
+        let over = 20.0 * self.env.max(1e-6).log10() - self.threshold_db();
+        let reduction = if over > 0.0 {
+            over * (1.0 - 1.0 / self.ratio())
+        } else {
+            0.0
+        };
+
+        let gain = db_to_gain(self.makeup_db() - reduction);
+
+        frame.map(|side| side * gain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::sine;
+
+    #[test]
+    fn turns_loud_signals_down() {
+        let mut comp = Compressor::new(48_000.0);
+        comp.set_param(0, 0.5);
+        comp.set_param(1, 1.0);
+        let out: Vec<_> = sine(220.0, 48_000.0, 1.0)
+            .map(|frame| comp.process(frame))
+            .collect();
+        let peak = out[out.len() / 2..]
+            .iter()
+            .flatten()
+            .fold(0.0_f32, |peak, side| side.abs().max(peak));
+
+        // 30 dB over a -30 dB threshold at 20:1 comes out about 1.5 dB over it.
+        assert!(peak < db_to_gain(-27.0), "{peak}");
+        assert!(out.iter().flatten().all(|side| side.abs() <= 1.0));
+    }
+
+    #[test]
+    fn leaves_signals_under_the_threshold() {
+        let mut comp = Compressor::new(48_000.0);
+        comp.set_param(0, 1.0);
+
+        for frame in sine(220.0, 48_000.0, 0.2) {
+            let frame = frame.map(|side| side * 0.5);
+
+            assert_eq!(comp.process(frame), frame);
+        }
+    }
+
+    #[test]
+    fn both_sides_follow_the_louder() {
+        let mut comp = Compressor::new(48_000.0);
+        comp.set_param(0, 0.5);
+        comp.set_param(1, 1.0);
+
+        for frame in sine(220.0, 48_000.0, 0.5) {
+            let [left, right] = comp.process([frame[0], frame[0] * 0.1]);
+
+            assert!((left * 0.1 - right).abs() < 1e-6);
+        }
+    }
+}
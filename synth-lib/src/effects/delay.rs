@@ -0,0 +1,215 @@
+use super::{exp_range, smoothing};
+use crate::{
+    lfo::LfoSync,
+    mixer::{Frame, mono},
+};
+
+/// the longest a delay can be set to by hand, in seconds.
+pub const MAX_DELAY_TIME: f32 = 2.0;
+/// the longest a delay line holds, synced delays at slow tempos are cut to this.
+const MAX_DELAY_LINE: f32 = 4.0;
+/// the loudest the repeats feed back, so they always die away.
+const MAX_FEEDBACK: f32 = 0.95;
+
+/// a ring buffer read at a fractional delay.
+#[derive(Clone, Debug)]
+pub(super) struct DelayLine {
+    buf: Vec<f32>,
+    pos: usize,
+}
+
+impl DelayLine {
+    pub(super) fn new(len: usize) -> Self {
+        Self {
+            buf: vec![0.0; len.max(2)],
+            pos: 0,
+        }
+    }
+
+    /// the sample written `delay` samples ago, between samples it is interpolated.
+    pub(super) fn read(&self, delay: f32) -> f32 {
+        let len = self.buf.len();
+        let delay = delay.clamp(1.0, (len - 1) as f32);
+        let whole = delay as usize;
+        let frac = delay - whole as f32;
+        let a = self.buf[(self.pos + len - whole) % len];
+        let b = self.buf[(self.pos + len - whole - 1) % len];
+
+        a + (b - a) * frac
+    }
+
+    pub(super) fn write(&mut self, sample: f32) {
+        self.pos = (self.pos + 1) % self.buf.len();
+        self.buf[self.pos] = sample;
+    }
+}
+
+/// a stereo delay. at no ping pong each side repeats itself, at full ping pong the input goes
+/// into the left line and the repeats bounce from side to side.
+#[derive(Clone, Debug)]
+pub struct Delay {
+    /// time, sync, feedback, mix, ping pong.
+    pub(super) params: [f32; 5],
+    sample_rate: f32,
+    lines: [DelayLine; 2],
+    /// the delay time in samples, smoothed so moving it doesn't click.
+    time: f32,
+}
+
+impl Delay {
+    pub fn new(sample_rate: f32) -> Self {
+        let len = (MAX_DELAY_LINE * sample_rate) as usize;
+        let mut delay = Self {
+            params: [0.35, 0.0, 0.4, 0.3, 1.0],
+            sample_rate,
+            lines: [DelayLine::new(len), DelayLine::new(len)],
+            time: 0.0,
+        };
+        delay.time = delay.target(120.0);
+
+        delay
+    }
+
+    pub(super) fn set_param(&mut self, param: usize, value: f32) {
+        if let Some(p) = self.params.get_mut(param) {
+            *p = value;
+        }
+    }
+
+    /// the division the sync param picks, `LfoSync::Off` leaves the time param in charge.
+    pub fn sync(&self) -> LfoSync {
+        let last = LfoSync::ALL.len() - 1;
+
+        LfoSync::ALL[(self.params[1] * last as f32).round() as usize]
+    }
+
+    /// the delay time in seconds at `tempo` bpm.
+    pub fn seconds(&self, tempo: f32) -> f32 {
+        let secs = match self.sync().beats() {
+            Some(beats) if tempo > 0.0 => beats * 60.0 / tempo,
+            _ => exp_range(self.params[0], 0.01, MAX_DELAY_TIME),
+        };
+
+        secs.min(MAX_DELAY_LINE)
+    }
+
+    fn target(&self, tempo: f32) -> f32 {
+        self.seconds(tempo) * self.sample_rate
+    }
+
+    pub(super) fn process(&mut self, frame: Frame, tempo: f32) -> Frame {
+        let [_, _, feedback, mix, ping_pong] = self.params;
+        let feedback = feedback * MAX_FEEDBACK;
+
+        self.time += (self.target(tempo) - self.time) * smoothing(0.05, self.sample_rate);
+
+        let [left, right] = self.lines.each_ref().map(|line| line.read(self.time));
+        // each line takes its own side's input and repeats, ping pong crosses them over.
+        let cross = |own: f32, other: f32| own * (1.0 - ping_pong) + other * ping_pong;
+        let input = [cross(frame[0], mono(frame)), cross(frame[1], 0.0)];
+        let repeats = [cross(left, right), cross(right, left)];
+
+        for ((line, input), repeat) in self.lines.iter_mut().zip(input).zip(repeats) {
+            line.write(input + feedback * repeat);
+        }
+
+        [
+            frame[0] * (1.0 - mix) + left * mix,
+            frame[1] * (1.0 - mix) + right * mix,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::sine;
+
+    const SAMPLE_RATE: f32 = 48_000.0;
+
+    /// a delay with only the repeats heard and one repeat each.
+    fn delay(ping_pong: f32) -> Delay {
+        let mut delay = Delay::new(SAMPLE_RATE);
+        delay.set_param(2, 0.0);
+        delay.set_param(3, 1.0);
+        delay.set_param(4, ping_pong);
+
+        delay
+    }
+
+    /// where a click on both sides is heard back on each side, in samples.
+    fn repeats(delay: &mut Delay, tempo: f32, len: usize) -> Vec<Frame> {
+        (0..len)
+            .map(|i| delay.process(if i == 0 { [1.0; 2] } else { [0.0; 2] }, tempo))
+            .collect()
+    }
+
+    fn loudest(frames: &[Frame], side: usize) -> usize {
+        (0..frames.len())
+            .max_by(|&a, &b| frames[a][side].abs().total_cmp(&frames[b][side].abs()))
+            .unwrap()
+    }
+
+    #[test]
+    fn repeats_after_the_delay_time() {
+        let mut delay = delay(0.0);
+        let time = delay.seconds(120.0) * SAMPLE_RATE;
+        let out = repeats(&mut delay, 120.0, (time * 1.5) as usize);
+
+        for side in 0..2 {
+            assert!((loudest(&out, side) as f32 - time).abs() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn ping_pong_bounces_the_repeats_between_the_sides() {
+        let mut delay = delay(1.0);
+        delay.set_param(2, 1.0);
+        let time = delay.seconds(120.0) * SAMPLE_RATE;
+        let out = repeats(&mut delay, 120.0, (time * 2.5) as usize);
+        let (first, second) = out.split_at((time * 1.5) as usize);
+        let quiet = |frames: &[Frame], side: usize| frames.iter().all(|f| f[side].abs() < 1e-6);
+
+        assert!((loudest(first, 0) as f32 - time).abs() <= 1.0);
+        assert!(quiet(first, 1), "the first repeat is only on the left");
+        // the second repeat is read between samples twice, so it is smeared a little wider.
+        assert!((loudest(second, 1) as f32 + first.len() as f32 - time * 2.0).abs() <= 2.0);
+        assert!(quiet(second, 0), "the second is only on the right");
+    }
+
+    #[test]
+    fn synced_delays_follow_a_tempo_change() {
+        let mut delay = delay(0.0);
+        let quarter = LfoSync::ALL
+            .iter()
+            .position(|sync| *sync == LfoSync::Quarter);
+        delay.set_param(1, quarter.unwrap() as f32 / (LfoSync::ALL.len() - 1) as f32);
+
+        assert_eq!(delay.seconds(120.0), 0.5);
+        assert_eq!(delay.seconds(240.0), 0.25);
+
+        // give the smoothed time a second to settle on the new tempo.
+        for _ in 0..SAMPLE_RATE as usize {
+            delay.process([0.0; 2], 240.0);
+        }
+        let out = repeats(&mut delay, 240.0, SAMPLE_RATE as usize / 2);
+
+        assert!((loudest(&out, 0) as f32 - SAMPLE_RATE / 4.0).abs() <= 1.0);
+    }
+
+    #[test]
+    fn full_feedback_stays_bounded_and_dies_away() {
+        for ping_pong in [0.0, 1.0] {
+            let mut delay = delay(ping_pong);
+            delay.set_param(2, 1.0);
+            let out: Vec<_> = sine(220.0, SAMPLE_RATE, 1.0)
+                .chain(std::iter::repeat_n([0.0; 2], 10 * SAMPLE_RATE as usize))
+                .map(|frame| delay.process(frame, 120.0))
+                .collect();
+            let peak = |frames: &[Frame]| frames.iter().flatten().fold(0.0, |p, s| s.abs().max(p));
+
+            assert!(peak(&out) <= 1.0 / (1.0 - MAX_FEEDBACK));
+            assert!(peak(&out[out.len() - SAMPLE_RATE as usize..]) < 0.01);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use super::{exp_range, smoothing};
+use crate::mixer::Frame;
+
+/// a soft clipping overdrive with a low pass tone control.
+#[derive(Clone, Debug)]
+pub struct Drive {
+    /// drive, tone, mix, level.
+    pub(super) params: [f32; 4],
+    sample_rate: f32,
+    /// the tone filter's coefficient, from the tone param.
+    tone: f32,
+    last: Frame,
+}
+
+impl Drive {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut drive = Self {
+            params: [0.3, 0.7, 1.0, 0.5],
+            sample_rate,
+            tone: 0.0,
+            last: [0.0; 2],
+        };
+        drive.update();
+
+        drive
+    }
+
+    pub(super) fn set_param(&mut self, param: usize, value: f32) {
+        if let Some(p) = self.params.get_mut(param) {
+            *p = value;
+            self.update();
+        }
+    }
+
+    fn update(&mut self) {
+        let cutoff = exp_range(self.params[1], 500.0, 16_000.0);
+
+        self.tone = smoothing(1.0 / (std::f32::consts::TAU * cutoff), self.sample_rate);
+    }
+
+    pub(super) fn process(&mut self, frame: Frame) -> Frame {
+        let [drive, _, mix, level] = self.params;
+        let gain = exp_range(drive, 1.0, 50.0);
+
+        std::array::from_fn(|side| {
+            let driven = (frame[side] * gain).tanh();
+            let last = &mut self.last[side];
+            *last += (driven - *last) * self.tone;
+
+            // a level of 0.5 leaves the clipped signal at full scale.
+            frame[side] * (1.0 - mix) + *last * level * 2.0 * mix
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::sine;
+
+    #[test]
+    fn full_drive_stays_bounded() {
+        let mut drive = Drive::new(48_000.0);
+        for param in 0..4 {
+            drive.set_param(param, 1.0);
+        }
+
+        for frame in sine(110.0, 48_000.0, 0.5) {
+            let out = drive.process(frame.map(|side| side * 10.0));
+
+            assert!(out.iter().all(|side| side.abs() <= 2.0));
+        }
+    }
+
+    #[test]
+    fn no_mix_passes_the_dry_signal() {
+        let mut drive = Drive::new(48_000.0);
+        drive.set_param(2, 0.0);
+
+        for frame in sine(110.0, 48_000.0, 0.1) {
+            assert_eq!(drive.process(frame), frame);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use super::exp_range;
+use crate::mixer::Frame;
+use std::f32::consts::{SQRT_2, TAU};
+
+/// how far each band boosts or cuts, in dB.
+pub const EQ_RANGE_DB: f32 = 12.0;
+const LOW_SHELF: f32 = 200.0;
+const HIGH_SHELF: f32 = 4000.0;
+const MID_Q: f32 = 1.0;
+
+/// a biquad filter, with the coefficients from the RBJ audio EQ cookbook.
+#[derive(Clone, Copy, Debug, Default)]
+struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+    z: [f32; 2],
+}
+
+impl Biquad {
+    fn set(&mut self, b: [f32; 3], a: [f32; 3]) {
+        self.b = b.map(|b| b / a[0]);
+        self.a = [a[1] / a[0], a[2] / a[0]];
+    }
+
+    fn low_shelf(&mut self, freq: f32, db: f32, sample_rate: f32) {
+        let a = 10.0_f32.powf(db / 40.0);
+        let (sin, cos) = (TAU * freq / sample_rate).sin_cos();
+        let alpha = sin / 2.0 * SQRT_2;
+        let sq = 2.0 * a.sqrt() * alpha;
+
+        self.set(
+            [
+                a * ((a + 1.0) - (a - 1.0) * cos + sq),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - sq),
+            ],
+            [
+                (a + 1.0) + (a - 1.0) * cos + sq,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - sq,
+            ],
+        );
+    }
+
+    fn high_shelf(&mut self, freq: f32, db: f32, sample_rate: f32) {
+        let a = 10.0_f32.powf(db / 40.0);
+        let (sin, cos) = (TAU * freq / sample_rate).sin_cos();
+        let alpha = sin / 2.0 * SQRT_2;
+        let sq = 2.0 * a.sqrt() * alpha;
+
+        self.set(
+            [
+                a * ((a + 1.0) + (a - 1.0) * cos + sq),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - sq),
+            ],
+            [
+                (a + 1.0) - (a - 1.0) * cos + sq,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - sq,
+            ],
+        );
+    }
+
+    fn peak(&mut self, freq: f32, db: f32, sample_rate: f32) {
+        let a = 10.0_f32.powf(db / 40.0);
+        let (sin, cos) = (TAU * freq / sample_rate).sin_cos();
+        let alpha = sin / (2.0 * MID_Q);
+
+        self.set(
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        );
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+
+        y
+    }
+}
+
+/// a 3 band EQ, shelves for the lows and highs and a bell for the mids.
+#[derive(Clone, Debug)]
+pub struct Equalizer {
+    /// low, mid, high, mid frequency. the gains are flat at 0.5.
+    pub(super) params: [f32; 4],
+    sample_rate: f32,
+    /// the bands of each side, they share their settings.
+    bands: [[Biquad; 3]; 2],
+}
+
+impl Equalizer {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut eq = Self {
+            params: [0.5; 4],
+            sample_rate,
+            bands: Default::default(),
+        };
+        eq.update();
+
+        eq
+    }
+
+    pub(super) fn set_param(&mut self, param: usize, value: f32) {
+        if let Some(p) = self.params.get_mut(param) {
+            *p = value;
+            self.update();
+        }
+    }
+
+    fn update(&mut self) {
+        let [low, mid, high, freq] = self.params;
+        let db = |gain: f32| (gain - 0.5) * 2.0 * EQ_RANGE_DB;
+        let sr = self.sample_rate;
+
+        for bands in self.bands.iter_mut() {
+            bands[0].low_shelf(LOW_SHELF, db(low), sr);
+            bands[1].peak(exp_range(freq, 200.0, 5000.0), db(mid), sr);
+            bands[2].high_shelf(HIGH_SHELF, db(high), sr);
+        }
+    }
+
+    pub(super) fn process(&mut self, frame: Frame) -> Frame {
+        std::array::from_fn(|side| {
+            self.bands[side]
+                .iter_mut()
+                .fold(frame[side], |sample, band| band.process(sample))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::{db_to_gain, sine};
+
+    #[test]
+    fn flat_bands_pass_the_signal() {
+        let mut eq = Equalizer::new(48_000.0);
+
+        for frame in sine(1000.0, 48_000.0, 0.1) {
+            let out = eq.process(frame);
+
+            assert!((out[0] - frame[0]).abs() < 1e-4);
+            assert!((out[1] - frame[1]).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn full_boost_stays_bounded() {
+        let mut eq = Equalizer::new(48_000.0);
+        for param in 0..4 {
+            eq.set_param(param, 1.0);
+        }
+        let bound = db_to_gain(3.0 * EQ_RANGE_DB);
+
+        for freq in [50.0, 1000.0, 5000.0, 15_000.0] {
+            for frame in sine(freq, 48_000.0, 0.2) {
+                let out = eq.process(frame);
+
+                assert!(out.iter().all(|side| side.abs() <= bound));
+            }
+        }
+    }
+}
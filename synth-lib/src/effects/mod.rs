@@ -0,0 +1,161 @@
+use crate::mixer::Frame;
+use chorus::Chorus;
+use compressor::Compressor;
+use delay::Delay;
+use drive::Drive;
+use eq::Equalizer;
+use phaser::Phaser;
+use reverb::Reverb;
+
+pub mod chorus;
+pub mod compressor;
+pub mod delay;
+pub mod drive;
+pub mod eq;
+pub mod phaser;
+pub mod reverb;
+
+/// the effects the synth-lib mixer runs in a channel's effect slots. they run in slot order on
+/// the channel's stereo frame, which stays centered until the delay or chorus spreads it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EffectKind {
+    Delay,
+    Drive,
+    Eq,
+    Compressor,
+    Phaser,
+    Chorus,
+    Reverb,
+}
+
+impl EffectKind {
+    pub const ALL: [Self; 7] = [
+        Self::Delay,
+        Self::Drive,
+        Self::Eq,
+        Self::Compressor,
+        Self::Phaser,
+        Self::Chorus,
+        Self::Reverb,
+    ];
+
+    /// the names of the effect's params, in the order `Effect::params` returns them.
+    pub fn param_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Delay => &["time", "sync", "feedback", "mix", "ping pong"],
+            Self::Drive => &["drive", "tone", "mix", "level"],
+            Self::Eq => &["low", "mid", "high", "frequency"],
+            Self::Compressor => &["threshold", "ratio", "attack", "release", "makeup"],
+            Self::Phaser => &["rate", "depth", "feedback", "mix"],
+            Self::Chorus => &["rate", "depth", "mix", "voices", "width"],
+            Self::Reverb => &["gain", "decay", "cutoff", "damping"],
+        }
+    }
+}
+
+/// one of the synth-lib effects with its running state. every param goes from `0.0` to `1.0`,
+/// each effect maps them onto its own ranges.
+#[derive(Clone, Debug)]
+pub enum Effect {
+    Delay(Delay),
+    Drive(Drive),
+    Eq(Equalizer),
+    Compressor(Compressor),
+    Phaser(Phaser),
+    Chorus(Chorus),
+    Reverb(Reverb),
+}
+
+impl Effect {
+    /// the effect with its default settings.
+    pub fn new(kind: EffectKind, sample_rate: f32) -> Self {
+        match kind {
+            EffectKind::Delay => Self::Delay(Delay::new(sample_rate)),
+            EffectKind::Drive => Self::Drive(Drive::new(sample_rate)),
+            EffectKind::Eq => Self::Eq(Equalizer::new(sample_rate)),
+            EffectKind::Compressor => Self::Compressor(Compressor::new(sample_rate)),
+            EffectKind::Phaser => Self::Phaser(Phaser::new(sample_rate)),
+            EffectKind::Chorus => Self::Chorus(Chorus::new(sample_rate)),
+            EffectKind::Reverb => Self::Reverb(Reverb::new(sample_rate)),
+        }
+    }
+
+    pub fn kind(&self) -> EffectKind {
+        match self {
+            Self::Delay(_) => EffectKind::Delay,
+            Self::Drive(_) => EffectKind::Drive,
+            Self::Eq(_) => EffectKind::Eq,
+            Self::Compressor(_) => EffectKind::Compressor,
+            Self::Phaser(_) => EffectKind::Phaser,
+            Self::Chorus(_) => EffectKind::Chorus,
+            Self::Reverb(_) => EffectKind::Reverb,
+        }
+    }
+
+    /// the values of the params, named by `EffectKind::param_names`.
+    pub fn params(&self) -> &[f32] {
+        match self {
+            Self::Delay(fx) => &fx.params,
+            Self::Drive(fx) => &fx.params,
+            Self::Eq(fx) => &fx.params,
+            Self::Compressor(fx) => &fx.params,
+            Self::Phaser(fx) => &fx.params,
+            Self::Chorus(fx) => &fx.params,
+            Self::Reverb(fx) => &fx.params,
+        }
+    }
+
+    pub fn param(&self, param: usize) -> Option<f32> {
+        self.params().get(param).copied()
+    }
+
+    /// sets a param, clamped to `0.0..=1.0`. params the effect doesn't have are ignored.
+    pub fn set_param(&mut self, param: usize, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+
+        match self {
+            Self::Delay(fx) => fx.set_param(param, value),
+            Self::Drive(fx) => fx.set_param(param, value),
+            Self::Eq(fx) => fx.set_param(param, value),
+            Self::Compressor(fx) => fx.set_param(param, value),
+            Self::Phaser(fx) => fx.set_param(param, value),
+            Self::Chorus(fx) => fx.set_param(param, value),
+            Self::Reverb(fx) => fx.set_param(param, value),
+        }
+    }
+
+    /// runs one frame through the effect, synced effects follow `tempo` bpm.
+    pub fn process(&mut self, frame: Frame, tempo: f32) -> Frame {
+        match self {
+            Self::Delay(fx) => fx.process(frame, tempo),
+            Self::Drive(fx) => fx.process(frame),
+            Self::Eq(fx) => fx.process(frame),
+            Self::Compressor(fx) => fx.process(frame),
+            Self::Phaser(fx) => fx.process(frame),
+            Self::Chorus(fx) => fx.process(frame),
+            Self::Reverb(fx) => fx.process(frame),
+        }
+    }
+}
+
+/// maps `0.0..=1.0` onto `low..=high` exponentially, for times and frequencies.
+fn exp_range(value: f32, low: f32, high: f32) -> f32 {
+    low * (high / low).powf(value)
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10.0_f32.powf(db / 20.0)
+}
+
+/// how much of the distance to a target a one pole smoother covers each sample, for a time
+/// constant of `secs` seconds.
+fn smoothing(secs: f32, sample_rate: f32) -> f32 {
+    1.0 - (-1.0 / (secs * sample_rate).max(1.0)).exp()
+}
+
+/// a full scale sine with the same sample on both sides, to run through the effects in tests.
+#[cfg(test)]
+fn sine(freq: f32, sample_rate: f32, secs: f32) -> impl Iterator<Item = Frame> {
+    (0..(secs * sample_rate) as usize)
+        .map(move |i| [(std::f32::consts::TAU * freq * i as f32 / sample_rate).sin(); 2])
+}
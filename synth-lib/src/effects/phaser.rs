@@ -0,0 +1,101 @@
+use super::exp_range;
+use crate::mixer::Frame;
+use std::f32::consts::{PI, TAU};
+
+/// how many all pass stages the phaser sweeps, each pair makes a notch.
+const STAGES: usize = 4;
+/// the bottom of the sweep, in Hz.
+const MIN_FREQ: f32 = 200.0;
+const MAX_FEEDBACK: f32 = 0.9;
+
+/// a phaser, a chain of all pass filters swept by a sine LFO and mixed with the dry signal.
+#[derive(Clone, Debug)]
+pub struct Phaser {
+    /// rate, depth, feedback, mix.
+    pub(super) params: [f32; 4],
+    sample_rate: f32,
+    phase: f32,
+    /// each side's all passes and last output, they share the sweep.
+    stages: [[f32; STAGES]; 2],
+    last: Frame,
+}
+
+impl Phaser {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            params: [0.3, 0.6, 0.4, 0.5],
+            sample_rate,
+            phase: 0.0,
+            stages: [[0.0; STAGES]; 2],
+            last: [0.0; 2],
+        }
+    }
+
+    pub(super) fn set_param(&mut self, param: usize, value: f32) {
+        if let Some(p) = self.params.get_mut(param) {
+            *p = value;
+        }
+    }
+
+    /// the sweep's rate in Hz.
+    pub fn rate(&self) -> f32 {
+        exp_range(self.params[0], 0.05, 5.0)
+    }
+
+    pub(super) fn process(&mut self, frame: Frame) -> Frame {
+        let [_, depth, feedback, mix] = self.params;
+
+        self.phase = (self.phase + self.rate() / self.sample_rate).fract();
+        let sweep = 0.5 + 0.5 * (self.phase * TAU).sin();
+        let freq = MIN_FREQ * 20.0_f32.powf(depth * sweep);
+        let t = (PI * freq / self.sample_rate).tan();
+        let a = (t - 1.0) / (t + 1.0);
+
+        std::array::from_fn(|side| {
+            let mut wet = frame[side] + self.last[side] * feedback * MAX_FEEDBACK;
+            for state in self.stages[side].iter_mut() {
+                let y = a * wet + *state;
+                *state = wet - a * y;
+                wet = y;
+            }
+            self.last[side] = wet;
+
+            frame[side] * (1.0 - mix) + wet * mix
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::sine;
+
+    #[test]
+    fn full_feedback_stays_bounded() {
+        let mut phaser = Phaser::new(48_000.0);
+        for param in 0..4 {
+            phaser.set_param(param, 1.0);
+        }
+        // the feedback loop peaks at 1 / (1 - feedback), the moving sweep can overshoot that a
+        // little.
+        let bound = 1.1 / (1.0 - MAX_FEEDBACK);
+
+        for freq in [20.0, 100.0, 1000.0, 8000.0, 15_000.0] {
+            for frame in sine(freq, 48_000.0, 0.5) {
+                let out = phaser.process(frame);
+
+                assert!(out.iter().all(|side| side.abs() <= bound));
+            }
+        }
+    }
+
+    #[test]
+    fn no_mix_passes_the_dry_signal() {
+        let mut phaser = Phaser::new(48_000.0);
+        phaser.set_param(3, 0.0);
+
+        for frame in sine(440.0, 48_000.0, 0.1) {
+            assert_eq!(phaser.process(frame), frame);
+        }
+    }
+}
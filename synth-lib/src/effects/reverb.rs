@@ -0,0 +1,141 @@
+use super::{exp_range, smoothing};
+use crate::mixer::{Frame, mono};
+use std::f32::consts::TAU;
+
+/// freeverb's comb and all pass lengths, in samples at 44.1 kHz.
+const COMBS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALL_PASSES: [usize; 4] = [556, 441, 341, 225];
+/// how much longer the right side's combs and all passes are, so the two tails differ.
+const STEREO_SPREAD: usize = 23;
+/// the combs add up to a lot, so their input is turned down and the tail back up.
+const INPUT_GAIN: f32 = 0.015;
+const WET_GAIN: f32 = 3.0;
+/// the comb feedback at no and full decay.
+const MIN_DECAY: f32 = 0.7;
+const MAX_DECAY: f32 = 0.98;
+const MAX_DAMPING: f32 = 0.4;
+
+/// a feedback comb filter with a low pass in the loop.
+#[derive(Clone, Debug)]
+struct Comb {
+    buf: Vec<f32>,
+    pos: usize,
+    /// the loop's low pass.
+    last: f32,
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Self {
+            buf: vec![0.0; len.max(1)],
+            pos: 0,
+            last: 0.0,
+        }
+    }
+
+    fn process(&mut self, sample: f32, feedback: f32, damping: f32) -> f32 {
+        let out = self.buf[self.pos];
+
+        self.last = out * (1.0 - damping) + self.last * damping;
+        self.buf[self.pos] = sample + self.last * feedback;
+        self.pos = (self.pos + 1) % self.buf.len();
+
+        out
+    }
+}
+
+/// a schroeder all pass, it smears the combs' echoes without colouring them.
+#[derive(Clone, Debug)]
+struct AllPass {
+    buf: Vec<f32>,
+    pos: usize,
+}
+
+impl AllPass {
+    fn new(len: usize) -> Self {
+        Self {
+            buf: vec![0.0; len.max(1)],
+            pos: 0,
+        }
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        let delayed = self.buf[self.pos];
+
+        self.buf[self.pos] = sample + delayed * 0.5;
+        self.pos = (self.pos + 1) % self.buf.len();
+
+        delayed - sample
+    }
+}
+
+/// a stereo freeverb, parallel combs into a chain of all passes for each side, both fed the
+/// frame mixed down to mono. the gain is how loud the tail is on top of the dry signal, the
+/// cutoff low passes what goes into it.
+#[derive(Clone, Debug)]
+pub struct Reverb {
+    /// gain, decay, cutoff, damping.
+    pub(super) params: [f32; 4],
+    sample_rate: f32,
+    /// the input filter's coefficient, from the cutoff param.
+    cutoff: f32,
+    last: f32,
+    combs: [Vec<Comb>; 2],
+    all_passes: [Vec<AllPass>; 2],
+}
+
+impl Reverb {
+    pub fn new(sample_rate: f32) -> Self {
+        let scale = |len: usize| (len as f32 * sample_rate / 44_100.0) as usize;
+        let spread = [0, STEREO_SPREAD];
+        let mut reverb = Self {
+            params: [0.3, 0.5, 0.7, 0.5],
+            sample_rate,
+            cutoff: 0.0,
+            last: 0.0,
+            combs: spread.map(|extra| COMBS.map(|len| Comb::new(scale(len + extra))).to_vec()),
+            all_passes: spread.map(|extra| {
+                ALL_PASSES
+                    .map(|len| AllPass::new(scale(len + extra)))
+                    .to_vec()
+            }),
+        };
+        reverb.update();
+
+        reverb
+    }
+
+    pub(super) fn set_param(&mut self, param: usize, value: f32) {
+        if let Some(p) = self.params.get_mut(param) {
+            *p = value;
+            self.update();
+        }
+    }
+
+    fn update(&mut self) {
+        let cutoff = exp_range(self.params[2], 500.0, 16_000.0);
+
+        self.cutoff = smoothing(1.0 / (TAU * cutoff), self.sample_rate);
+    }
+
+    pub(super) fn process(&mut self, frame: Frame) -> Frame {
+        let [gain, decay, _, damping] = self.params;
+        let feedback = MIN_DECAY + (MAX_DECAY - MIN_DECAY) * decay;
+        let damping = damping * MAX_DAMPING;
+
+        self.last += (mono(frame) - self.last) * self.cutoff;
+        let input = self.last * INPUT_GAIN;
+
+        std::array::from_fn(|side| {
+            let wet = self.combs[side]
+                .iter_mut()
+                .map(|comb| comb.process(input, feedback, damping))
+                .sum();
+            let wet = self.all_passes[side]
+                .iter_mut()
+                .fold(wet, |wet, all_pass| all_pass.process(wet));
+
+            frame[side] + wet * WET_GAIN * gain
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+/// the widest bend range a channel can be set to, in semitones.
+pub const MAX_BEND_RANGE: u8 = 48;
+
+/// how far a full pitch bend moves the pitch, in semitones each way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BendRange {
+    pub up: u8,
+    pub down: u8,
+}
+
+impl Default for BendRange {
+    fn default() -> Self {
+        Self { up: 2, down: 2 }
+    }
+}
+
+impl BendRange {
+    /// the range an engine bent by MIDI is told about, the wider of the two directions. the
+    /// narrower one is reached by scaling the bends down.
+    pub fn engine_range(&self) -> u8 {
+        self.up.max(self.down).min(MAX_BEND_RANGE)
+    }
+
+    /// rescales a pitch bend, centred on 0, from the engine's range to this one.
+    pub fn scale(&self, amt: i16) -> i16 {
+        let range = self.engine_range();
+
+        if range == 0 {
+            return 0;
+        }
+
+        let semitones = if amt > 0 { self.up } else { self.down }.min(range);
+
+        (amt as i32 * semitones as i32 / range as i32) as i16
+    }
+
+    /// sets the engine's pitch bend sensitivity (RPN 0), then deselects the RPN so stray data
+    /// entry doesn't change it.
+    pub fn controllers(&self, channel: u8) -> [[u8; 3]; 6] {
+        let cc = |code, data| [0xB0 | (channel & 0x0F), code, data];
+
+        [
+            cc(101, 0),
+            cc(100, 0),
+            cc(6, self.engine_range()),
+            cc(38, 0),
+            cc(101, 127),
+            cc(100, 127),
+        ]
+    }
+}
+
+/// where a channel's expressive controllers are, read from the raw MIDI messages it is sent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Expression {
+    pub bend_range: BendRange,
+    /// the last pitch bend as it came in, centred on 0.
+    bend: i16,
+    mod_wheel: u8,
+    pressure: u8,
+    /// the poly aftertouch of each note.
+    poly_pressure: [u8; 128],
+}
+
+impl Default for Expression {
+    fn default() -> Self {
+        Self {
+            bend_range: BendRange::default(),
+            bend: 0,
+            mod_wheel: 0,
+            pressure: 0,
+            poly_pressure: [0; 128],
+        }
+    }
+}
+
+impl Expression {
+    /// keeps track of `message` and returns what to send the engine in its place. pitch bends
+    /// are rescaled to the bend range, everything else is passed on as is.
+    pub fn midi_input(&mut self, message: &[u8]) -> Vec<u8> {
+        let Some(status) = message.first() else {
+            return message.to_vec();
+        };
+        let channel = status & 0x0F;
+
+        match (status & 0xF0, &message[1..]) {
+            (0xE0, [lsb, msb, ..]) => {
+                self.bend = ((*msb as i16) << 7 | *lsb as i16) - 0x2000;
+
+                return self.bend_message(channel).to_vec();
+            }
+            (0xD0, [pressure, ..]) => self.pressure = *pressure,
+            (0xA0, [note, pressure, ..]) => self.poly_pressure[(note & 0x7F) as usize] = *pressure,
+            // a key let go takes its aftertouch with it.
+            (0x80 | 0x90, [note, ..]) => self.poly_pressure[(note & 0x7F) as usize] = 0,
+            (0xB0, [1, value, ..]) => self.mod_wheel = *value,
+            // reset all controllers.
+            (0xB0, [121, ..]) => self.reset(),
+            _ => {}
+        }
+
+        message.to_vec()
+    }
+
+    /// the current bend, rescaled to the bend range.
+    pub fn bend_message(&self, channel: u8) -> [u8; 3] {
+        let value = (self.bend_range.scale(self.bend) as i32 + 0x2000).clamp(0, 0x3FFF) as u16;
+
+        [0xE0 | channel, (value & 0x7F) as u8, (value >> 7) as u8]
+    }
+
+    /// how far the pitch is bent, from -1.0 (all the way down) to 1.0 (all the way up).
+    pub fn bend(&self) -> f32 {
+        self.bend as f32 / if self.bend > 0 { 8191.0 } else { 8192.0 }
+    }
+
+    /// how far the pitch is bent in semitones, within the bend range.
+    pub fn bend_semitones(&self) -> f32 {
+        let range = if self.bend > 0 {
+            self.bend_range.up
+        } else {
+            self.bend_range.down
+        };
+
+        self.bend() * range.min(MAX_BEND_RANGE) as f32
+    }
+
+    /// the mod wheel, from 0.0 to 1.0.
+    pub fn mod_wheel(&self) -> f32 {
+        self.mod_wheel as f32 / 127.0
+    }
+
+    /// the channel aftertouch, from 0.0 to 1.0.
+    pub fn pressure(&self) -> f32 {
+        self.pressure as f32 / 127.0
+    }
+
+    /// the hardest pressed key's poly aftertouch, from 0.0 to 1.0.
+    pub fn poly_pressure(&self) -> f32 {
+        self.poly_pressure.iter().copied().max().unwrap_or_default() as f32 / 127.0
+    }
+
+    /// the channel or poly aftertouch, whichever is pressed harder. controllers only send one
+    /// of the two, so this is what aftertouch modulates.
+    pub fn aftertouch(&self) -> f32 {
+        self.pressure().max(self.poly_pressure())
+    }
+
+    /// centres the controllers, keeping the bend range.
+    pub fn reset(&mut self) {
+        *self = Self {
+            bend_range: self.bend_range,
+            ..Self::default()
+        };
+    }
+}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::{f32::consts::TAU, fmt::Display, ops::RangeInclusive};
+
+/// how many LFOs the global bank has.
+pub const N_LFOS: usize = 4;
+/// the rates a free running LFO can be set to, in Hz.
+pub const LFO_RATES: RangeInclusive<f32> = 0.01..=20.0;
+/// the longest an LFO can take to fade in, in seconds.
+pub const MAX_FADE_IN: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LfoShape {
+    #[default]
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+    /// a new random value each cycle.
+    SampleHold,
+}
+
+impl LfoShape {
+    pub const ALL: [Self; 5] = [
+        Self::Sine,
+        Self::Triangle,
+        Self::Saw,
+        Self::Square,
+        Self::SampleHold,
+    ];
+}
+
+impl Display for LfoShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sine => write!(f, "Sine"),
+            Self::Triangle => write!(f, "Triangle"),
+            Self::Saw => write!(f, "Saw"),
+            Self::Square => write!(f, "Square"),
+            Self::SampleHold => write!(f, "Sample & Hold"),
+        }
+    }
+}
+
+/// how long one cycle takes when an LFO follows the song's tempo, in 4/4.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LfoSync {
+    /// runs at its own rate.
+    #[default]
+    Off,
+    FourBars,
+    TwoBars,
+    Bar,
+    Half,
+    Quarter,
+    Eighth,
+    Sixteenth,
+}
+
+impl LfoSync {
+    pub const ALL: [Self; 8] = [
+        Self::Off,
+        Self::FourBars,
+        Self::TwoBars,
+        Self::Bar,
+        Self::Half,
+        Self::Quarter,
+        Self::Eighth,
+        Self::Sixteenth,
+    ];
+
+    /// how many beats one cycle lasts, `None` when not synced.
+    pub fn beats(self) -> Option<f32> {
+        match self {
+            Self::Off => None,
+            Self::FourBars => Some(16.0),
+            Self::TwoBars => Some(8.0),
+            Self::Bar => Some(4.0),
+            Self::Half => Some(2.0),
+            Self::Quarter => Some(1.0),
+            Self::Eighth => Some(0.5),
+            Self::Sixteenth => Some(0.25),
+        }
+    }
+}
+
+impl Display for LfoSync {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::FourBars => write!(f, "4 Bars"),
+            Self::TwoBars => write!(f, "2 Bars"),
+            Self::Bar => write!(f, "1 Bar"),
+            Self::Half => write!(f, "1/2"),
+            Self::Quarter => write!(f, "1/4"),
+            Self::Eighth => write!(f, "1/8"),
+            Self::Sixteenth => write!(f, "1/16"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LfoSettings {
+    pub shape: LfoShape,
+    /// in Hz, used when not synced to the tempo.
+    pub rate: f32,
+    pub sync: LfoSync,
+    /// restarts the LFO on each new note instead of running freely.
+    pub retrigger: bool,
+    /// where in the cycle the LFO starts, from 0.0 to 1.0.
+    pub phase: f32,
+    /// how long the LFO takes to reach its full depth after a new note, in seconds.
+    pub fade_in: f32,
+}
+
+impl Default for LfoSettings {
+    fn default() -> Self {
+        Self {
+            shape: LfoShape::Sine,
+            rate: 1.0,
+            sync: LfoSync::Off,
+            retrigger: false,
+            phase: 0.0,
+            fade_in: 0.0,
+        }
+    }
+}
+
+impl LfoSettings {
+    /// the settings with every number brought into its range, a client can send anything.
+    pub fn clamped(self) -> Self {
+        let finite = |value: f32, default: f32| if value.is_finite() { value } else { default };
+
+        Self {
+            rate: finite(self.rate, 1.0).clamp(*LFO_RATES.start(), *LFO_RATES.end()),
+            phase: finite(self.phase, 0.0).rem_euclid(1.0),
+            fade_in: finite(self.fade_in, 0.0).clamp(0.0, MAX_FADE_IN),
+            ..self
+        }
+    }
+
+    /// how many cycles the LFO runs a second at `tempo` bpm.
+    pub fn frequency(&self, tempo: f32) -> f32 {
+        match self.sync.beats() {
+            Some(beats) => tempo / 60.0 / beats,
+            None => self.rate.clamp(*LFO_RATES.start(), *LFO_RATES.end()),
+        }
+    }
+}
+
+/// a running LFO, where it is in its cycle and how long since it was restarted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lfo {
+    phase: f32,
+    /// the value the sample and hold shape is holding.
+    held: f32,
+    seed: u32,
+    /// seconds since the last restart, for the fade in.
+    age: f32,
+}
+
+impl Default for Lfo {
+    fn default() -> Self {
+        Self {
+            phase: 0.0,
+            held: 0.0,
+            seed: 0x9E37_79B9,
+            age: 0.0,
+        }
+    }
+}
+
+impl Lfo {
+    /// a new random value from -1.0 to 1.0 (xorshift).
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+
+        self.seed as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+
+    /// moves the LFO on by `dt` seconds at `tempo` bpm.
+    pub fn advance(&mut self, settings: &LfoSettings, dt: f32, tempo: f32) {
+        let before = (self.phase + settings.phase).rem_euclid(1.0);
+        self.phase = (self.phase + settings.frequency(tempo) * dt).rem_euclid(1.0);
+        self.age += dt;
+
+        // a new cycle started.
+        if (self.phase + settings.phase).rem_euclid(1.0) < before {
+            self.held = self.random();
+        }
+    }
+
+    /// starts the fade in again, and the cycle too with `retrigger`.
+    pub fn restart(&mut self, retrigger: bool) {
+        self.age = 0.0;
+
+        if retrigger {
+            self.phase = 0.0;
+            self.held = self.random();
+        }
+    }
+
+    /// the LFO's output, from -1.0 to 1.0, without the fade in.
+    pub fn value(&self, settings: &LfoSettings) -> f32 {
+        let phase = (self.phase + settings.phase).rem_euclid(1.0);
+
+        match settings.shape {
+            LfoShape::Sine => (phase * TAU).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            LfoShape::Saw => phase * 2.0 - 1.0,
+            LfoShape::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::SampleHold => self.held,
+        }
+    }
+
+    /// how far faded in the LFO is, from 0.0 to 1.0.
+    pub fn fade(&self, settings: &LfoSettings) -> f32 {
+        if settings.fade_in > 0.0 {
+            (self.age / settings.fade_in).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// the global LFO bank every channel's mod matrix reads. the free running LFOs run here, the
+/// retriggered ones run per channel, restarted by its notes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lfos {
+    pub settings: [LfoSettings; N_LFOS],
+    free: [Lfo; N_LFOS],
+    /// the song's tempo, in bpm, at the last tick.
+    tempo: f32,
+}
+
+impl Lfos {
+    /// moves the free running LFOs on by `dt` seconds at `tempo` bpm.
+    pub fn tick(&mut self, dt: f32, tempo: f32) {
+        self.tempo = tempo;
+
+        for (lfo, settings) in self.free.iter_mut().zip(self.settings.iter()) {
+            lfo.advance(settings, dt, tempo);
+        }
+    }
+
+    pub fn tempo(&self) -> f32 {
+        self.tempo
+    }
+
+    /// the output of a free running LFO, from -1.0 to 1.0.
+    pub fn free(&self, lfo: usize) -> f32 {
+        match (self.free.get(lfo), self.settings.get(lfo)) {
+            (Some(free), Some(settings)) => free.value(settings),
+            _ => 0.0,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+pub mod effects;
+pub mod expression;
+pub mod lfo;
+pub mod meter;
+pub mod mixer;
+pub mod modulation;
+pub mod notes;
+pub mod scope;
+pub mod song;
+pub mod spectrum;
+pub mod voices;
+pub mod wavetable;
+
+/// how many sequence channels the synth has.
+pub const N_CHANNELS: usize = 4;
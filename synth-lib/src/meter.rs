@@ -0,0 +1,60 @@
+use crate::mixer::Frame;
+
+/// how much the held peak falls each sample, about 20 dB a second at 48 kHz.
+const PEAK_DECAY: f32 = 0.999_95;
+/// the smoothing of the mean square, about a 100 ms window at 48 kHz.
+const RMS_COEF: f32 = 1.0 / 4800.0;
+
+/// the peak and RMS level of a signal.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Meter {
+    peak: f32,
+    /// smoothed mean square.
+    ms: f32,
+}
+
+impl Meter {
+    pub fn push(&mut self, sample: f32) {
+        let level = sample.abs();
+
+        self.peak = if level > self.peak {
+            level
+        } else {
+            self.peak * PEAK_DECAY
+        };
+        self.ms += (sample * sample - self.ms) * RMS_COEF;
+    }
+
+    /// meters the louder side of a stereo frame.
+    pub fn push_frame(&mut self, [left, right]: Frame) {
+        self.push(left.abs().max(right.abs()));
+    }
+
+    /// linear, 1.0 is full scale.
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+
+    /// linear, 1.0 is full scale.
+    pub fn rms(&self) -> f32 {
+        self.ms.sqrt()
+    }
+}
+
+/// where the limiter starts bending the signal down.
+pub const LIMIT_THRESHOLD: f32 = 0.8;
+
+/// a soft limiter, the output follows the input below `LIMIT_THRESHOLD` and above it bends
+/// smoothly towards full scale without ever reaching it.
+pub fn soft_limit(sample: f32) -> f32 {
+    let level = sample.abs();
+
+    if level <= LIMIT_THRESHOLD {
+        return sample;
+    }
+
+    let knee = 1.0 - LIMIT_THRESHOLD;
+    let limited = LIMIT_THRESHOLD + knee * ((level - LIMIT_THRESHOLD) / knee).tanh();
+
+    limited.copysign(sample)
+}
@@ -0,0 +1,123 @@
+use crate::{
+    N_CHANNELS,
+    effects::Effect,
+    meter::{Meter, soft_limit},
+};
+
+/// one stereo sample, left then right.
+pub type Frame = [f32; 2];
+
+/// the two sides of a frame mixed down to one, for mono outputs and the scope.
+pub fn mono([left, right]: Frame) -> f32 {
+    (left + right) * 0.5
+}
+
+/// writes a frame into one frame of an interleaved output buffer. a mono output gets both sides
+/// mixed down, wider ones get left and right in turn.
+pub fn write_frame(frame: Frame, out: &mut [f32]) {
+    if let [sample] = out {
+        *sample = mono(frame);
+    } else {
+        for (sample, side) in out.iter_mut().zip(frame.iter().cycle()) {
+            *sample = *side;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChannelMix {
+    /// linear gain applied to the channel, 0.0 is silent and 1.0 is unity.
+    pub volume: f32,
+    pub mute: bool,
+    /// the channel's level after the volume and mute, the louder side of the two.
+    pub meter: Meter,
+    /// the effects in the channel's effect slots and whether they are on, they run in slot order.
+    pub effects: [Option<(Effect, bool)>; 2],
+}
+
+impl Default for ChannelMix {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            mute: false,
+            meter: Meter::default(),
+            effects: Default::default(),
+        }
+    }
+}
+
+/// sums the mono sequence channels into the stereo output. a channel stays centered unless its
+/// effects spread it.
+#[derive(Debug, Clone)]
+pub struct Mixer {
+    pub channels: [ChannelMix; N_CHANNELS],
+    /// softly limits the master bus so stacked channels don't clip the output.
+    pub limiter: bool,
+    /// the level of the output, after the limiter.
+    pub master: Meter,
+    /// the master gain, only lowered by `fade_out`.
+    gain: f32,
+    /// how much the master gain drops per sample while fading out.
+    fade_step: f32,
+    /// the song's tempo in bpm, for the synced effects.
+    pub tempo: f32,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            channels: Default::default(),
+            limiter: false,
+            master: Meter::default(),
+            gain: 1.0,
+            fade_step: 0.0,
+            tempo: 120.0,
+        }
+    }
+}
+
+impl Mixer {
+    /// ramps the output down to silence over the next `samples` samples, so stopping doesn't
+    /// click.
+    pub fn fade_out(&mut self, samples: usize) {
+        self.fade_step = self.gain / samples.max(1) as f32;
+    }
+
+    /// whether a fade out has finished.
+    pub fn silent(&self) -> bool {
+        self.gain <= 0.0
+    }
+
+    /// mixes one sample from each channel, in channel order, into a stereo frame.
+    ///
+    /// muted channels should still be rendered by the caller so their envelopes and effect
+    /// tails keep time. the channels' effects run in slot order before the volume.
+    pub fn mix(&mut self, samples: impl IntoIterator<Item = f32>) -> Frame {
+        let tempo = self.tempo;
+        let sum = samples
+            .into_iter()
+            .zip(self.channels.iter_mut())
+            .map(|(sample, mix)| {
+                let frame = mix
+                    .effects
+                    .iter_mut()
+                    .flatten()
+                    .filter(|(_, on)| *on)
+                    .fold([sample; 2], |frame, (effect, _)| {
+                        effect.process(frame, tempo)
+                    });
+                let gain = if mix.mute { 0.0 } else { mix.volume };
+                let frame = frame.map(|side| side * gain);
+                mix.meter.push_frame(frame);
+
+                frame
+            })
+            .fold([0.0; 2], |[left, right], [l, r]| [left + l, right + r]);
+        let limit = |side| if self.limiter { soft_limit(side) } else { side };
+        let out = sum.map(|side| limit(side) * self.gain);
+        self.gain = (self.gain - self.fade_step).max(0.0);
+        self.master.push_frame(out);
+
+        out
+    }
+}
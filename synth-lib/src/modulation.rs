@@ -0,0 +1,430 @@
+use crate::{
+    expression::Expression,
+    lfo::{Lfo, Lfos, N_LFOS},
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, ops::RangeInclusive};
+
+/// the most connections a channel's matrix holds.
+pub const MAX_MOD_SLOTS: usize = 16;
+/// the envelopes, oscillators and filters the matrix can address. engines with fewer leave the
+/// connections to the missing ones doing nothing.
+pub const N_MOD_ENVS: u8 = 2;
+pub const N_MOD_OSCS: u8 = 3;
+pub const N_MOD_FILTERS: u8 = 2;
+/// how many octaves a full amount moves a filter's cutoff.
+const CUTOFF_OCTAVES: f32 = 10.0;
+
+/// what drives a connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModSrc {
+    Lfo(u8),
+    Env(u8),
+    /// the velocity of the last note played.
+    Velocity,
+    ModWheel,
+    /// channel or poly aftertouch, whichever is pressed harder.
+    Aftertouch,
+    /// the last note played, 0.0 at middle C and 1.0 five octaves above it.
+    KeyTrack,
+}
+
+impl ModSrc {
+    pub fn all() -> Vec<Self> {
+        (0..N_LFOS as u8)
+            .map(Self::Lfo)
+            .chain((0..N_MOD_ENVS).map(Self::Env))
+            .chain([
+                Self::Velocity,
+                Self::ModWheel,
+                Self::Aftertouch,
+                Self::KeyTrack,
+            ])
+            .collect()
+    }
+}
+
+impl Display for ModSrc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lfo(lfo) => write!(f, "LFO {}", lfo + 1),
+            Self::Env(env) => write!(f, "Env {}", env + 1),
+            Self::Velocity => write!(f, "Velocity"),
+            Self::ModWheel => write!(f, "Mod Wheel"),
+            Self::Aftertouch => write!(f, "Aftertouch"),
+            Self::KeyTrack => write!(f, "Key Track"),
+        }
+    }
+}
+
+/// what a connection moves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModDest {
+    OscLevel(u8),
+    OscDetune(u8),
+    /// where in its wavetable the oscillator plays.
+    OscPosition(u8),
+    FilterCutoff(u8),
+    FilterResonance(u8),
+    FilterMix(u8),
+    /// one of the params of the effect in a slot, `param` is its place in the effect's param list.
+    EffectParam {
+        slot: u8,
+        param: u8,
+    },
+}
+
+impl ModDest {
+    /// every destination, the effect params are listed for `effect_params` params per slot.
+    pub fn all(effect_params: [usize; 2]) -> Vec<Self> {
+        (0..N_MOD_OSCS)
+            .flat_map(|osc| {
+                [
+                    Self::OscLevel(osc),
+                    Self::OscDetune(osc),
+                    Self::OscPosition(osc),
+                ]
+            })
+            .chain((0..N_MOD_FILTERS).flat_map(|filter| {
+                [
+                    Self::FilterCutoff(filter),
+                    Self::FilterResonance(filter),
+                    Self::FilterMix(filter),
+                ]
+            }))
+            .chain(effect_params.into_iter().enumerate().flat_map(|(slot, n)| {
+                (0..n as u8).map(move |param| Self::EffectParam {
+                    slot: slot as u8,
+                    param,
+                })
+            }))
+            .collect()
+    }
+
+    /// the values the destination is kept within.
+    pub fn range(self) -> RangeInclusive<f32> {
+        match self {
+            Self::OscDetune(_) => -1.0..=1.0,
+            Self::FilterCutoff(_) => 20.0..=20_000.0,
+            Self::OscLevel(_)
+            | Self::OscPosition(_)
+            | Self::FilterResonance(_)
+            | Self::FilterMix(_)
+            | Self::EffectParam { .. } => 0.0..=1.0,
+        }
+    }
+
+    /// `base` moved by `offset`, from -1.0 to 1.0 of the destination's range. the cutoff moves
+    /// in octaves so the sweep sounds even.
+    pub fn modulate(self, base: f32, offset: f32) -> f32 {
+        let range = self.range();
+        let value = match self {
+            Self::FilterCutoff(_) => base * (offset * CUTOFF_OCTAVES).exp2(),
+            _ => base + offset * (range.end() - range.start()),
+        };
+
+        value.clamp(*range.start(), *range.end())
+    }
+}
+
+impl Display for ModDest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OscLevel(osc) => write!(f, "Osc {} Level", osc + 1),
+            Self::OscDetune(osc) => write!(f, "Osc {} Detune", osc + 1),
+            Self::OscPosition(osc) => write!(f, "Osc {} Position", osc + 1),
+            Self::FilterCutoff(filter) => write!(f, "Filter {} Cutoff", filter + 1),
+            Self::FilterResonance(filter) => write!(f, "Filter {} Res", filter + 1),
+            Self::FilterMix(filter) => write!(f, "Filter {} Mix", filter + 1),
+            Self::EffectParam { slot, param } => {
+                write!(f, "Effect {} Param {}", slot + 1, param + 1)
+            }
+        }
+    }
+}
+
+/// one connection in the matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModSlot {
+    pub src: ModSrc,
+    pub dest: ModDest,
+    /// from -1.0 to 1.0, negative amounts move the destination the other way.
+    pub amount: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModMatrix {
+    pub slots: Vec<ModSlot>,
+}
+
+impl ModMatrix {
+    /// adds a connection, or changes the amount of the one between the same source and
+    /// destination. false when the matrix is full.
+    pub fn connect(&mut self, slot: ModSlot) -> bool {
+        let slot = ModSlot {
+            amount: slot.amount.clamp(-1.0, 1.0),
+            ..slot
+        };
+
+        if let Some(existing) = self
+            .slots
+            .iter_mut()
+            .find(|s| s.src == slot.src && s.dest == slot.dest)
+        {
+            *existing = slot;
+        } else if self.slots.len() < MAX_MOD_SLOTS {
+            self.slots.push(slot);
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    /// replaces the connection in slot `index`, or adds one when `index` is past the last. a
+    /// connection between the same source and destination in another slot is taken out.
+    pub fn set(&mut self, index: usize, slot: ModSlot) {
+        let slot = ModSlot {
+            amount: slot.amount.clamp(-1.0, 1.0),
+            ..slot
+        };
+
+        if let Some(existing) = self.slots.get_mut(index) {
+            *existing = slot;
+        } else if self.slots.len() < MAX_MOD_SLOTS {
+            self.slots.push(slot);
+        } else {
+            return;
+        }
+
+        let index = index.min(self.slots.len() - 1);
+        let mut i = 0;
+
+        self.slots.retain(|s| {
+            let keep = i == index || s.src != slot.src || s.dest != slot.dest;
+            i += 1;
+            keep
+        });
+    }
+
+    /// a connection with no amount between the first source and destination that aren't
+    /// connected yet, for UIs to add and then edit.
+    pub fn unused(&self, dests: &[ModDest]) -> Option<ModSlot> {
+        ModSrc::all().into_iter().find_map(|src| {
+            dests
+                .iter()
+                .find(|dest| !self.slots.iter().any(|s| s.src == src && s.dest == **dest))
+                .map(|dest| ModSlot {
+                    src,
+                    dest: *dest,
+                    amount: 0.0,
+                })
+        })
+    }
+
+    pub fn disconnect(&mut self, src: ModSrc, dest: ModDest) {
+        self.slots.retain(|s| s.src != src || s.dest != dest);
+    }
+
+    /// true when any connection moves `dest`.
+    pub fn modulates(&self, dest: ModDest) -> bool {
+        self.slots.iter().any(|s| s.dest == dest)
+    }
+}
+
+/// the settings of an envelope, times in seconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Adsr {
+    pub atk: f32,
+    pub dcy: f32,
+    pub sus: f32,
+    pub rel: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Stage {
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+    #[default]
+    Idle,
+}
+
+/// an envelope run at control rate, following the notes of the whole channel.
+#[derive(Clone, Copy, Debug, Default)]
+struct ModEnv {
+    level: f32,
+    stage: Stage,
+}
+
+impl ModEnv {
+    fn tick(&mut self, adsr: Adsr, dt: f32) -> f32 {
+        // a zero length stage is over straight away.
+        let step = |time: f32| if time > 0.0 { dt / time } else { 1.0 };
+
+        match self.stage {
+            Stage::Attack => {
+                self.level += step(adsr.atk);
+
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                self.level -= step(adsr.dcy) * (1.0 - adsr.sus);
+
+                if self.level <= adsr.sus {
+                    self.level = adsr.sus;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => self.level = adsr.sus,
+            Stage::Release => {
+                self.level -= step(adsr.rel);
+
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+            Stage::Idle => self.level = 0.0,
+        }
+
+        self.level
+    }
+}
+
+/// a channel's matrix, the LFOs and envelopes that drive it and the unmodulated values of the
+/// params it moves.
+#[derive(Clone, Debug, Default)]
+pub struct Modulation {
+    pub matrix: ModMatrix,
+    /// the channel's own run of each LFO, restarted by its notes. the retriggered LFOs are read
+    /// from here, the free running ones only take their fade in.
+    lfos: [Lfo; N_LFOS],
+    envs: [ModEnv; N_MOD_ENVS as usize],
+    velocity: u8,
+    note: u8,
+    /// what each modulated param was set to before the matrix moved it.
+    bases: Vec<(ModDest, f32)>,
+}
+
+impl Modulation {
+    /// a note started, `retrigger` restarts the envelopes and the LFOs.
+    pub fn note_on(&mut self, note: u8, vel: u8, retrigger: bool) {
+        self.note = note;
+        self.velocity = vel;
+
+        if retrigger {
+            self.envs
+                .iter_mut()
+                .for_each(|env| env.stage = Stage::Attack);
+            self.lfos.iter_mut().for_each(|lfo| lfo.restart(true));
+        }
+    }
+
+    /// the last key was let go.
+    pub fn release(&mut self) {
+        for env in self.envs.iter_mut() {
+            if env.stage != Stage::Idle {
+                env.stage = Stage::Release;
+            }
+        }
+    }
+
+    /// silences the envelopes, for when every note has been stopped.
+    pub fn reset(&mut self) {
+        self.envs = Default::default();
+    }
+
+    /// moves the LFOs and envelopes on by `dt` seconds and returns how far each modulated
+    /// destination is moved, from -1.0 to 1.0 of its range. `lfos` should already have been
+    /// ticked.
+    pub fn tick(
+        &mut self,
+        dt: f32,
+        lfos: &Lfos,
+        envs: &[Adsr],
+        expression: &Expression,
+    ) -> Vec<(ModDest, f32)> {
+        let lfos: Vec<f32> = self
+            .lfos
+            .iter_mut()
+            .zip(lfos.settings.iter())
+            .enumerate()
+            .map(|(i, (lfo, settings))| {
+                lfo.advance(settings, dt, lfos.tempo());
+                let value = if settings.retrigger {
+                    lfo.value(settings)
+                } else {
+                    lfos.free(i)
+                };
+
+                value * lfo.fade(settings)
+            })
+            .collect();
+        let envs: Vec<f32> = self
+            .envs
+            .iter_mut()
+            .zip(envs)
+            .map(|(env, adsr)| env.tick(*adsr, dt))
+            .collect();
+
+        let value = |src: ModSrc| match src {
+            ModSrc::Lfo(lfo) => lfos.get(lfo as usize).copied().unwrap_or_default(),
+            ModSrc::Env(env) => envs.get(env as usize).copied().unwrap_or_default(),
+            ModSrc::Velocity => self.velocity as f32 / 127.0,
+            ModSrc::ModWheel => expression.mod_wheel(),
+            ModSrc::Aftertouch => expression.aftertouch(),
+            ModSrc::KeyTrack => ((self.note as f32 - 60.0) / 60.0).clamp(-1.0, 1.0),
+        };
+
+        let mut offsets: Vec<(ModDest, f32)> = Vec::new();
+
+        for slot in self.matrix.slots.iter() {
+            let offset = value(slot.src) * slot.amount;
+
+            match offsets.iter_mut().find(|(dest, _)| *dest == slot.dest) {
+                Some((_, total)) => *total += offset,
+                None => offsets.push((slot.dest, offset)),
+            }
+        }
+
+        offsets
+    }
+
+    /// the unmodulated value of `dest`, if the matrix is moving it.
+    pub fn base(&self, dest: ModDest) -> Option<f32> {
+        self.bases
+            .iter()
+            .find(|(d, _)| *d == dest)
+            .map(|(_, base)| *base)
+    }
+
+    /// the unmodulated values of the params the matrix is moving.
+    pub fn bases(&self) -> &[(ModDest, f32)] {
+        &self.bases
+    }
+
+    /// records what `dest` is set to, before the matrix moves it.
+    pub fn set_base(&mut self, dest: ModDest, value: f32) {
+        match self.bases.iter_mut().find(|(d, _)| *d == dest) {
+            Some((_, base)) => *base = value,
+            None => self.bases.push((dest, value)),
+        }
+    }
+
+    /// takes out the bases of the params the matrix no longer moves, or every one with `all`,
+    /// so they can be set back.
+    pub fn take_bases(&mut self, all: bool) -> Vec<(ModDest, f32)> {
+        let (taken, kept) = self
+            .bases
+            .drain(..)
+            .partition(|(dest, _)| all || !self.matrix.modulates(*dest));
+        self.bases = kept;
+
+        taken
+    }
+}
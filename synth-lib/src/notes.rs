@@ -0,0 +1,52 @@
+use crate::N_CHANNELS;
+
+/// the notes held down on each channel, kept up to date from the raw MIDI messages sent to
+/// the synth.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeldNotes {
+    /// one bit per MIDI note.
+    notes: [u128; N_CHANNELS],
+}
+
+impl HeldNotes {
+    pub fn midi_input(&mut self, message: &[u8]) {
+        let [status, data_1, data_2, ..] = *message else {
+            return;
+        };
+        let Some(notes) = self.notes.get_mut((status & 0x0F) as usize) else {
+            return;
+        };
+        let note = 1 << (data_1 & 0x7F);
+
+        match status & 0xF0 {
+            0x90 if data_2 > 0 => *notes |= note,
+            // a note on with zero velocity is a note off.
+            0x80 | 0x90 => *notes &= !note,
+            // all sound off and all notes off.
+            0xB0 if data_1 == 120 || data_1 == 123 => *notes = 0,
+            _ => {}
+        }
+    }
+
+    /// how many notes are held on a channel.
+    pub fn held(&self, channel: usize) -> u32 {
+        self.notes
+            .get(channel)
+            .map_or(0, |notes| notes.count_ones())
+    }
+
+    /// a raw note off for every held note, to release them.
+    pub fn note_offs(&self) -> impl Iterator<Item = [u8; 3]> + '_ {
+        (0..N_CHANNELS).flat_map(move |channel| {
+            self.notes(channel)
+                .map(move |note| [0x80 | channel as u8, note, 0])
+        })
+    }
+
+    /// the held notes of a channel, lowest first.
+    pub fn notes(&self, channel: usize) -> impl Iterator<Item = u8> + '_ {
+        let notes = self.notes.get(channel).copied().unwrap_or_default();
+
+        (0..128).filter(move |note| notes & (1 << note) != 0)
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU32, AtomicUsize, Ordering},
+};
+
+/// how many output samples are averaged into one scope sample.
+pub const DECIMATION: usize = 4;
+/// how many decimated samples the ring holds.
+pub const SCOPE_LEN: usize = 2048;
+
+/// a lock-free ring of the synth's decimated output. the audio callback writes it through a
+/// `ScopeWriter` and the UIs read the latest samples without ever blocking the audio thread.
+#[derive(Debug)]
+pub struct ScopeRing {
+    /// `f32` bits.
+    samples: Box<[AtomicU32]>,
+    /// how many samples have been written in total.
+    written: AtomicUsize,
+}
+
+impl Default for ScopeRing {
+    fn default() -> Self {
+        Self {
+            samples: (0..SCOPE_LEN).map(|_| AtomicU32::new(0)).collect(),
+            written: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ScopeRing {
+    /// the only writer of the ring, to be moved into the audio callback.
+    pub fn writer(self: &Arc<Self>) -> ScopeWriter {
+        ScopeWriter {
+            ring: self.clone(),
+            acc: 0.0,
+            count: 0,
+        }
+    }
+
+    /// the sample rate of the ring's samples given the output's sample rate.
+    pub fn sample_rate(output_rate: f32) -> f32 {
+        output_rate / DECIMATION as f32
+    }
+
+    /// the latest `len` samples, oldest first. `len` is capped at `SCOPE_LEN`.
+    pub fn latest(&self, len: usize) -> Vec<f32> {
+        let len = len.min(SCOPE_LEN);
+        let end = self.written.load(Ordering::Acquire);
+        let start = end.saturating_sub(len);
+
+        (start..end)
+            .map(|i| f32::from_bits(self.samples[i % SCOPE_LEN].load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct ScopeWriter {
+    ring: Arc<ScopeRing>,
+    acc: f32,
+    count: usize,
+}
+
+impl ScopeWriter {
+    /// adds one output sample, every `DECIMATION` samples are averaged into the ring.
+    pub fn push(&mut self, sample: f32) {
+        self.acc += sample;
+        self.count += 1;
+
+        if self.count < DECIMATION {
+            return;
+        }
+
+        let i = self.ring.written.load(Ordering::Relaxed);
+        self.ring.samples[i % SCOPE_LEN]
+            .store((self.acc / DECIMATION as f32).to_bits(), Ordering::Relaxed);
+        self.ring
+            .written
+            .store(i.wrapping_add(1), Ordering::Release);
+        self.acc = 0.0;
+        self.count = 0;
+    }
+}
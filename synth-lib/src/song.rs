@@ -0,0 +1,721 @@
+use crate::N_CHANNELS;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// how many bars long a song arrangement can be.
+pub const SONG_LEN: usize = 16;
+/// how many numbered sequences a song can chain together.
+pub const N_SEQUENCES: usize = 16;
+/// the pattern lengths (in steps) that can be chosen for a song.
+pub const PATTERN_LENS: [usize; 4] = [8, 16, 32, 64];
+/// the longest a pattern can be, in steps.
+pub const MAX_PATTERN_LEN: usize = 64;
+/// the most notes a sequence keeps, later notes are not recorded.
+pub const MAX_SEQUENCE_NOTES: usize = 512;
+/// how hard the notes the stepper adds are played.
+pub const STEP_VELOCITY: u8 = 100;
+/// the tempos a song can be played at, in bpm.
+pub const TEMPOS: RangeInclusive<f32> = 40.0..=300.0;
+
+/// a note recorded or stepped into a sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeqNote {
+    /// the step of the bar it starts on.
+    pub step: u8,
+    pub note: u8,
+    pub vel: u8,
+    /// how many steps it is held for.
+    pub len: u8,
+}
+
+/// one of the numbered sequences, the notes played over a bar. the stepper edits them a step at
+/// a time and the song chains them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sequence {
+    notes: Vec<SeqNote>,
+}
+
+impl Sequence {
+    pub fn notes(&self) -> &[SeqNote] {
+        &self.notes
+    }
+
+    /// one bit per step that starts a note.
+    pub fn steps(&self) -> u64 {
+        self.notes
+            .iter()
+            .fold(0, |steps, note| steps | 1 << note.step)
+    }
+
+    /// whether a note of this pitch starts on the step.
+    pub fn has(&self, step: usize, note: u8) -> bool {
+        self.notes
+            .iter()
+            .any(|old| old.step as usize == step && old.note == note)
+    }
+
+    /// records a note, replacing one of the same pitch on the same step. returns where it went.
+    fn record(&mut self, note: SeqNote) -> Option<usize> {
+        match self
+            .notes
+            .iter()
+            .position(|old| old.step == note.step && old.note == note.note)
+        {
+            Some(i) => {
+                self.notes[i] = note;
+                Some(i)
+            }
+            None if self.notes.len() < MAX_SEQUENCE_NOTES => {
+                self.notes.push(note);
+                Some(self.notes.len() - 1)
+            }
+            None => None,
+        }
+    }
+}
+
+/// the arrangement of a single channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Track {
+    /// the sequence to play for each bar of the song.
+    pub slots: [Option<u8>; SONG_LEN],
+    pub mute: bool,
+    pub solo: bool,
+}
+
+/// a note the song started and will stop.
+#[derive(Clone, Copy, Debug)]
+struct Sounding {
+    channel: u8,
+    note: u8,
+    /// the step count it stops at.
+    until: u64,
+}
+
+/// a note being recorded, its length is known once it is let go.
+#[derive(Clone, Copy, Debug)]
+struct Recording {
+    channel: u8,
+    note: u8,
+    seq: usize,
+    index: usize,
+    /// the step count it started at.
+    start: u64,
+}
+
+/// a song built by chaining numbered sequences together per channel, one bar at a time. it is
+/// ticked at its own tempo, which the synced LFOs and effects follow too, and gives back the raw
+/// MIDI messages it plays, which go through the voice allocator like any other notes. only the
+/// arrangement, tempo and sequences are saved, not where it is playing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Song {
+    pub tracks: [Track; N_CHANNELS],
+    /// first bar of the loop (inclusive).
+    pub loop_start: usize,
+    /// last bar of the loop (inclusive).
+    pub loop_end: usize,
+    /// how many 16th note steps each bar lasts.
+    pub pattern_len: usize,
+    /// in beats per minute.
+    pub tempo: f32,
+    #[serde(skip)]
+    pub playing: bool,
+    /// notes played while the song plays go into the sequence each channel is playing.
+    #[serde(skip)]
+    pub recording: bool,
+    /// the bar that is playing.
+    #[serde(skip)]
+    pub position: usize,
+    /// the step of the bar that is playing.
+    #[serde(skip)]
+    pub step: usize,
+    sequences: [Sequence; N_SEQUENCES],
+    /// seconds into the current step.
+    #[serde(skip)]
+    clock: f32,
+    /// whether the current step's notes have been started.
+    #[serde(skip)]
+    started: bool,
+    /// steps played since the song started, notes stop by it.
+    #[serde(skip)]
+    count: u64,
+    #[serde(skip)]
+    sounding: Vec<Sounding>,
+    #[serde(skip)]
+    held: Vec<Recording>,
+    /// the messages to send on the next tick.
+    #[serde(skip)]
+    out: Vec<[u8; 3]>,
+}
+
+impl Default for Song {
+    fn default() -> Self {
+        Self {
+            tracks: [Track::default(); N_CHANNELS],
+            loop_start: 0,
+            loop_end: SONG_LEN - 1,
+            pattern_len: 16,
+            tempo: 120.0,
+            playing: false,
+            recording: false,
+            position: 0,
+            step: 0,
+            sequences: Default::default(),
+            clock: 0.0,
+            started: false,
+            count: 0,
+            sounding: Vec::new(),
+            held: Vec::new(),
+            out: Vec::new(),
+        }
+    }
+}
+
+impl Song {
+    pub fn sequence(&self, seq: usize) -> Option<&Sequence> {
+        self.sequences.get(seq)
+    }
+
+    /// places a sequence in (or with `None` clears) a bar of a channel's arrangement.
+    pub fn set_slot(&mut self, channel: usize, bar: usize, seq: Option<u8>) {
+        if let Some(slot) = self
+            .tracks
+            .get_mut(channel)
+            .and_then(|track| track.slots.get_mut(bar))
+        {
+            *slot = seq.filter(|seq| (*seq as usize) < N_SEQUENCES);
+        }
+    }
+
+    /// sets the bars the song loops over, both inclusive.
+    pub fn set_loop(&mut self, start: usize, end: usize) {
+        self.loop_start = start.min(SONG_LEN - 1);
+        self.loop_end = end.clamp(self.loop_start, SONG_LEN - 1);
+    }
+
+    pub fn set_pattern_len(&mut self, len: usize) {
+        if PATTERN_LENS.contains(&len) {
+            self.pattern_len = len;
+        }
+    }
+
+    /// sets the tempo in bpm, brought into `TEMPOS`.
+    pub fn set_tempo(&mut self, tempo: f32) {
+        if tempo.is_finite() {
+            self.tempo = tempo.clamp(*TEMPOS.start(), *TEMPOS.end());
+        }
+    }
+
+    pub fn set_mute(&mut self, channel: usize, mute: bool) {
+        if let Some(track) = self.tracks.get_mut(channel) {
+            track.mute = mute;
+            self.silence();
+        }
+    }
+
+    pub fn set_solo(&mut self, channel: usize, solo: bool) {
+        if let Some(track) = self.tracks.get_mut(channel) {
+            track.solo = solo;
+            self.silence();
+        }
+    }
+
+    /// starts the song from the loop start, its first step plays on the next tick.
+    pub fn play(&mut self) {
+        self.release(|_| true);
+        self.playing = true;
+        self.position = self.loop_start;
+        self.step = 0;
+        self.clock = 0.0;
+        self.started = false;
+    }
+
+    /// stops the song and the notes it is playing.
+    pub fn stop(&mut self) {
+        self.release(|_| true);
+        self.held.clear();
+        self.playing = false;
+    }
+
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+
+        if !recording {
+            self.held.clear();
+        }
+    }
+
+    /// adds a note of `note` on a step of a sequence, or takes it out when the step already has
+    /// one, as the stepper does.
+    pub fn toggle_step(&mut self, seq: usize, step: usize, note: u8) {
+        if step >= MAX_PATTERN_LEN || note > 127 {
+            return;
+        }
+
+        let Some(sequence) = self.sequences.get_mut(seq) else {
+            return;
+        };
+
+        match sequence
+            .notes
+            .iter()
+            .position(|old| old.step as usize == step && old.note == note)
+        {
+            Some(i) => {
+                sequence.notes.remove(i);
+                // the notes after it moved down one.
+                self.held
+                    .retain(|held| !(held.seq == seq && held.index == i));
+                for held in self.held.iter_mut() {
+                    if held.seq == seq && held.index > i {
+                        held.index -= 1;
+                    }
+                }
+            }
+            None => {
+                sequence.record(SeqNote {
+                    step: step as u8,
+                    note,
+                    vel: STEP_VELOCITY,
+                    len: 1,
+                });
+            }
+        }
+    }
+
+    /// wipes the notes of a sequence.
+    pub fn clear_sequence(&mut self, seq: usize) {
+        if let Some(sequence) = self.sequences.get_mut(seq) {
+            sequence.notes.clear();
+            self.held.retain(|held| held.seq != seq);
+        }
+    }
+
+    /// the song with everything brought into range, a saved one can hold anything.
+    pub fn clamped(mut self) -> Self {
+        let default = Self::default();
+
+        if !PATTERN_LENS.contains(&self.pattern_len) {
+            self.pattern_len = default.pattern_len;
+        }
+
+        if !self.tempo.is_finite() {
+            self.tempo = default.tempo;
+        }
+
+        self.set_tempo(self.tempo);
+        self.set_loop(self.loop_start, self.loop_end);
+
+        for track in self.tracks.iter_mut() {
+            for slot in track.slots.iter_mut() {
+                *slot = slot.filter(|seq| (*seq as usize) < N_SEQUENCES);
+            }
+        }
+
+        for sequence in self.sequences.iter_mut() {
+            sequence.notes.retain(|note| {
+                (note.step as usize) < MAX_PATTERN_LEN && note.note < 128 && note.vel < 128
+            });
+            sequence.notes.truncate(MAX_SEQUENCE_NOTES);
+
+            for note in sequence.notes.iter_mut() {
+                note.len = note.len.clamp(1, MAX_PATTERN_LEN as u8);
+            }
+        }
+
+        self
+    }
+
+    /// forgets the notes the song is playing without stopping them, for when something else
+    /// already has.
+    pub fn forget(&mut self) {
+        self.sounding.clear();
+        self.held.clear();
+        self.out.clear();
+    }
+
+    /// true when the channel should be heard, taking mute and solo into account.
+    pub fn audible(&self, channel: usize) -> bool {
+        let Some(track) = self.tracks.get(channel) else {
+            return false;
+        };
+
+        if self.tracks.iter().any(|track| track.solo) {
+            track.solo
+        } else {
+            !track.mute
+        }
+    }
+
+    /// the sequence a channel plays at the current position.
+    pub fn now_playing(&self, channel: usize) -> Option<usize> {
+        if !self.playing || !self.audible(channel) {
+            return None;
+        }
+
+        self.tracks[channel].slots[self.position].map(usize::from)
+    }
+
+    /// moves the song on by `dt` seconds, returning the raw MIDI messages it plays. the steps are
+    /// 16th notes.
+    pub fn tick(&mut self, dt: f32) -> Vec<[u8; 3]> {
+        if self.playing {
+            if !self.started {
+                self.started = true;
+                self.play_step();
+            }
+
+            let step_len = 15.0 / self.tempo.max(1.0);
+            self.clock += dt;
+
+            while self.clock >= step_len {
+                self.clock -= step_len;
+                self.advance();
+                self.play_step();
+            }
+        }
+
+        std::mem::take(&mut self.out)
+    }
+
+    /// records a note on from a MIDI input into the sequence the channel is playing, on the step
+    /// that is playing.
+    pub fn record_note_on(&mut self, channel: u8, note: u8, vel: u8) {
+        if !self.recording || !self.playing || !self.started {
+            return;
+        }
+
+        let Some(seq) = self
+            .tracks
+            .get(channel as usize)
+            .and_then(|track| track.slots[self.position])
+            .map(usize::from)
+        else {
+            return;
+        };
+        let recorded = SeqNote {
+            step: self.step as u8,
+            note,
+            vel,
+            len: 1,
+        };
+
+        if let Some(index) = self.sequences[seq].record(recorded) {
+            self.held
+                .retain(|held| !(held.channel == channel && held.note == note));
+            self.held.push(Recording {
+                channel,
+                note,
+                seq,
+                index,
+                start: self.count,
+            });
+        }
+    }
+
+    /// sets the length of a recorded note once it is let go.
+    pub fn record_note_off(&mut self, channel: u8, note: u8) {
+        let Some(i) = self
+            .held
+            .iter()
+            .position(|held| held.channel == channel && held.note == note)
+        else {
+            return;
+        };
+        let held = self.held.swap_remove(i);
+        let len = (self.count - held.start).clamp(1, MAX_PATTERN_LEN as u64);
+
+        if let Some(recorded) = self.sequences[held.seq].notes.get_mut(held.index) {
+            recorded.len = len as u8;
+        }
+    }
+
+    /// moves to the next step, and to the next bar after the last step of one. the bar after
+    /// the loop end is the loop start.
+    fn advance(&mut self) {
+        self.count += 1;
+        self.step += 1;
+
+        if self.step >= self.pattern_len {
+            self.step = 0;
+            self.position = if self.position >= self.loop_end || self.position < self.loop_start {
+                self.loop_start
+            } else {
+                self.position + 1
+            };
+        }
+    }
+
+    /// stops the notes that are done and starts the current step's notes on the audible
+    /// channels.
+    fn play_step(&mut self) {
+        let count = self.count;
+        self.release(|sounding| sounding.until <= count);
+
+        for channel in 0..N_CHANNELS {
+            let Some(seq) = self.now_playing(channel) else {
+                continue;
+            };
+
+            for note in self.sequences[seq].notes.iter() {
+                if note.step as usize != self.step {
+                    continue;
+                }
+
+                // the same note again restarts it.
+                if let Some(i) = self
+                    .sounding
+                    .iter()
+                    .position(|s| s.channel as usize == channel && s.note == note.note)
+                {
+                    self.sounding.swap_remove(i);
+                    self.out.push([0x80 | channel as u8, note.note, 0]);
+                }
+
+                self.out.push([0x90 | channel as u8, note.note, note.vel]);
+                self.sounding.push(Sounding {
+                    channel: channel as u8,
+                    note: note.note,
+                    until: count + note.len.max(1) as u64,
+                });
+            }
+        }
+    }
+
+    /// stops the sounding notes that `done` picks, on this tick or the next.
+    fn release(&mut self, done: impl Fn(&Sounding) -> bool) {
+        let out = &mut self.out;
+
+        self.sounding.retain(|sounding| {
+            let done = done(sounding);
+
+            if done {
+                out.push([0x80 | sounding.channel, sounding.note, 0]);
+            }
+
+            !done
+        });
+    }
+
+    /// stops the notes of the channels that can't be heard any more.
+    fn silence(&mut self) {
+        let audible: [bool; N_CHANNELS] = std::array::from_fn(|channel| self.audible(channel));
+        self.release(|sounding| !audible[sounding.channel as usize]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a song with sequence 0 on channel 0 in the first two bars, playing a note on step 0
+    /// and one on step 2.
+    fn song() -> Song {
+        let mut song = Song::default();
+        song.set_tempo(150.0);
+        song.set_pattern_len(8);
+        song.set_slot(0, 0, Some(0));
+        song.set_slot(0, 1, Some(0));
+        song.sequences[0].notes = vec![
+            SeqNote {
+                step: 0,
+                note: 60,
+                vel: 100,
+                len: 1,
+            },
+            SeqNote {
+                step: 2,
+                note: 64,
+                vel: 90,
+                len: 2,
+            },
+        ];
+
+        song
+    }
+
+    /// ticks a song at 150 bpm a step, 0.1 seconds, at a time.
+    fn steps(song: &mut Song, steps: usize) -> Vec<[u8; 3]> {
+        (0..steps).flat_map(|_| song.tick(0.1)).collect()
+    }
+
+    #[test]
+    fn plays_nothing_until_started() {
+        let mut song = song();
+
+        assert!(steps(&mut song, 4).is_empty());
+    }
+
+    #[test]
+    fn plays_the_sequence_in_the_bar() {
+        let mut song = song();
+        song.play();
+
+        assert_eq!(song.tick(0.0), vec![[0x90, 60, 100]]);
+        assert_eq!(steps(&mut song, 2), vec![[0x80, 60, 0], [0x90, 64, 90]]);
+        assert_eq!(steps(&mut song, 2), vec![[0x80, 64, 0]]);
+    }
+
+    #[test]
+    fn steps_follow_the_tempo() {
+        let mut song = song();
+        song.play();
+        song.tick(0.0);
+
+        // at 300 bpm a step lasts 0.05 seconds, so the note on step 2 starts after 0.1.
+        song.set_tempo(300.0);
+        assert_eq!(song.tick(0.05), vec![[0x80, 60, 0]]);
+        assert_eq!(song.tick(0.05), vec![[0x90, 64, 90]]);
+        assert_eq!(song.step, 2);
+    }
+
+    #[test]
+    fn tempos_are_kept_in_range() {
+        let mut song = Song::default();
+
+        song.set_tempo(1000.0);
+        assert_eq!(song.tempo, *TEMPOS.end());
+        song.set_tempo(f32::NAN);
+        assert_eq!(song.tempo, *TEMPOS.end());
+        song.set_tempo(0.0);
+        assert_eq!(song.tempo, *TEMPOS.start());
+    }
+
+    #[test]
+    fn loops_back_to_the_loop_start() {
+        let mut song = song();
+        song.set_loop(1, 2);
+        song.play();
+        song.tick(0.0);
+        assert_eq!(song.position, 1);
+
+        steps(&mut song, 8);
+        assert_eq!(song.position, 2);
+
+        // bar 3 is empty, the song goes back to bar 2 and plays it.
+        assert_eq!(steps(&mut song, 8), vec![[0x90, 60, 100]]);
+        assert_eq!(song.position, 1);
+    }
+
+    #[test]
+    fn muted_and_unsoloed_channels_are_silent() {
+        let mut song = song();
+        song.play();
+        song.tick(0.0);
+        song.set_mute(0, true);
+
+        // the playing note is stopped, and no more start.
+        assert_eq!(steps(&mut song, 8), vec![[0x80, 60, 0]]);
+
+        song.set_mute(0, false);
+        song.set_solo(1, true);
+        assert!(steps(&mut song, 8).is_empty());
+    }
+
+    #[test]
+    fn stop_releases_the_notes() {
+        let mut song = song();
+        song.play();
+        song.tick(0.0);
+        song.stop();
+
+        assert_eq!(song.tick(0.1), vec![[0x80, 60, 0]]);
+        assert!(steps(&mut song, 8).is_empty());
+    }
+
+    #[test]
+    fn steps_toggle_notes() {
+        let mut song = song();
+
+        song.toggle_step(0, 5, 67);
+        assert!(song.sequence(0).unwrap().has(5, 67));
+        assert_eq!(song.sequence(0).unwrap().steps(), 1 | 1 << 2 | 1 << 5);
+
+        // another pitch on the same step is added beside it, the same one is taken out.
+        song.toggle_step(0, 5, 72);
+        song.toggle_step(0, 5, 67);
+        assert!(!song.sequence(0).unwrap().has(5, 67));
+        assert!(song.sequence(0).unwrap().has(5, 72));
+
+        // steps past the longest pattern and sequences past the last are ignored.
+        song.toggle_step(0, MAX_PATTERN_LEN, 60);
+        song.toggle_step(N_SEQUENCES, 0, 60);
+        assert_eq!(song.sequence(0).unwrap().notes().len(), 3);
+    }
+
+    #[test]
+    fn stepped_notes_play() {
+        let mut song = Song::default();
+        song.set_tempo(150.0);
+        song.set_slot(2, 0, Some(1));
+        song.toggle_step(1, 1, 50);
+        song.play();
+        song.tick(0.0);
+
+        assert_eq!(steps(&mut song, 1), vec![[0x92, 50, STEP_VELOCITY]]);
+    }
+
+    #[test]
+    fn clamped_brings_a_saved_song_into_range() {
+        let mut song = song();
+        song.pattern_len = 12;
+        song.tempo = f32::INFINITY;
+        song.loop_start = 40;
+        song.tracks[1].slots[3] = Some(200);
+        song.sequences[0].notes.push(SeqNote {
+            step: 70,
+            note: 60,
+            vel: 100,
+            len: 1,
+        });
+        song.sequences[0].notes[0].len = 0;
+
+        let song = song.clamped();
+
+        assert_eq!(song.pattern_len, 16);
+        assert_eq!(song.tempo, 120.0);
+        assert_eq!(
+            (song.loop_start, song.loop_end),
+            (SONG_LEN - 1, SONG_LEN - 1)
+        );
+        assert_eq!(song.tracks[1].slots[3], None);
+        assert_eq!(song.sequences[0].notes.len(), 2);
+        assert_eq!(song.sequences[0].notes[0].len, 1);
+    }
+
+    #[test]
+    fn records_into_the_playing_sequence() {
+        let mut song = Song::default();
+        song.set_tempo(150.0);
+        song.set_pattern_len(8);
+        song.set_slot(1, 0, Some(3));
+        song.set_recording(true);
+        song.play();
+        song.tick(0.0);
+        steps(&mut song, 3);
+
+        song.record_note_on(1, 67, 80);
+        steps(&mut song, 2);
+        song.record_note_off(1, 67);
+        // channel 0 has no sequence in the bar, so nothing is recorded.
+        song.record_note_on(0, 48, 80);
+
+        assert_eq!(
+            song.sequence(3).unwrap().notes(),
+            &[SeqNote {
+                step: 3,
+                note: 67,
+                vel: 80,
+                len: 2
+            }]
+        );
+        assert_eq!(song.sequence(3).unwrap().steps(), 1 << 3);
+        assert!(
+            song.sequences
+                .iter()
+                .enumerate()
+                .all(|(i, seq)| i == 3 || seq.notes.is_empty())
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::f32::consts::PI;
+
+/// the quietest level the spectrum reports, in dB.
+pub const FLOOR_DB: f32 = -96.0;
+
+/// the magnitude spectrum of `samples` in dB (0 dB is a full scale sine), one bin per
+/// `sample_rate / len` Hz from DC up to Nyquist. only the largest power of two prefix of
+/// `samples` is used.
+pub fn spectrum(samples: &[f32]) -> Vec<f32> {
+    if samples.len() < 2 {
+        return Vec::new();
+    }
+
+    let n = 1 << samples.len().ilog2();
+    // a Hann window keeps the leakage of off-bin frequencies down.
+    let mut re: Vec<f32> = samples[..n]
+        .iter()
+        .enumerate()
+        .map(|(i, s)| s * (0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+
+    fft(&mut re, &mut im);
+
+    // a full scale sine lands n / 4 in its bin once windowed.
+    let scale = 4.0 / n as f32;
+
+    re.iter()
+        .zip(im.iter())
+        .take(n / 2)
+        .map(|(re, im)| {
+            let mag = (re * re + im * im).sqrt() * scale;
+
+            (20.0 * mag.log10()).max(FLOOR_DB)
+        })
+        .collect()
+}
+
+/// an in place radix-2 FFT, the length must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let bits = n.trailing_zeros();
+
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+
+        if j > i {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+
+        len *= 2;
+    }
+}
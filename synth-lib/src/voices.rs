@@ -0,0 +1,802 @@
+use crate::{
+    N_CHANNELS,
+    expression::{BendRange, Expression},
+    lfo::{LfoSettings, Lfos},
+    modulation::{Adsr, ModDest, Modulation},
+    song::Song,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// the most voices a channel can be set to.
+pub const MAX_POLYPHONY: u8 = 16;
+/// the longest glide, in seconds. the portamento time controller's full range.
+pub const MAX_GLIDE: f32 = 2.0;
+
+/// how a channel plays notes that overlap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VoiceMode {
+    #[default]
+    Poly,
+    /// one note at a time, each note restarts the envelopes.
+    Mono,
+    /// one note at a time, a note played while another is held slides from it without
+    /// restarting the envelopes.
+    Legato,
+}
+
+impl VoiceMode {
+    pub const ALL: [Self; 3] = [Self::Poly, Self::Mono, Self::Legato];
+}
+
+impl Display for VoiceMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Poly => write!(f, "Poly"),
+            Self::Mono => write!(f, "Mono"),
+            Self::Legato => write!(f, "Legato"),
+        }
+    }
+}
+
+/// which voice a new note takes when all of a channel's voices are busy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Steal {
+    #[default]
+    Oldest,
+    /// the voice started with the lowest velocity.
+    Quietest,
+    /// a note that is already sounding restarts its own voice instead of taking another one,
+    /// otherwise the oldest voice is taken.
+    SameNote,
+}
+
+impl Steal {
+    pub const ALL: [Self; 3] = [Self::Oldest, Self::Quietest, Self::SameNote];
+}
+
+impl Display for Steal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Oldest => write!(f, "Oldest"),
+            Self::Quietest => write!(f, "Quietest"),
+            Self::SameNote => write!(f, "Same Note"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VoiceSettings {
+    /// how many notes can sound at once in `Poly` mode.
+    pub polyphony: u8,
+    pub mode: VoiceMode,
+    pub steal: Steal,
+    /// seconds to slide from one note to the next in the mono modes, 0.0 turns glide off.
+    pub glide: f32,
+}
+
+impl Default for VoiceSettings {
+    fn default() -> Self {
+        Self {
+            polyphony: 8,
+            mode: VoiceMode::default(),
+            steal: Steal::default(),
+            glide: 0.0,
+        }
+    }
+}
+
+impl VoiceSettings {
+    /// how many voices can sound at once.
+    pub fn limit(&self) -> usize {
+        match self.mode {
+            VoiceMode::Poly => self.polyphony.clamp(1, MAX_POLYPHONY) as usize,
+            VoiceMode::Mono | VoiceMode::Legato => 1,
+        }
+    }
+
+    /// the controllers that tell an engine that isn't pitched by the voices about the mode and
+    /// glide. mono or poly mode, the legato footswitch, portamento on and portamento time.
+    pub fn controllers(&self, channel: u8) -> [[u8; 3]; 4] {
+        let cc = |code, data| [0xB0 | (channel & 0x0F), code, data];
+        let mono = self.mode != VoiceMode::Poly;
+        let legato = self.mode == VoiceMode::Legato;
+        let glide = (self.glide / MAX_GLIDE * 127.0).round().clamp(0.0, 127.0) as u8;
+
+        [
+            if mono { cc(126, 1) } else { cc(127, 0) },
+            cc(68, if legato { 127 } else { 0 }),
+            cc(65, if mono && glide > 0 { 127 } else { 0 }),
+            cc(5, glide),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Voice {
+    note: u8,
+    vel: u8,
+    /// when the voice was started, counted in note ons.
+    started: u64,
+    /// the note the engine was sent. a legato note keeps the first note's and is slid to its own
+    /// by the pitch.
+    engine_note: u8,
+}
+
+/// where a channel's pitch is sliding to, in semitones from the note its engine plays.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Glide {
+    now: f32,
+    target: f32,
+    /// semitones a second.
+    rate: f32,
+}
+
+impl Glide {
+    /// slides to `target` over `secs` seconds, a glide of 0.0 jumps straight there.
+    fn slide(&mut self, target: f32, secs: f32) {
+        self.target = target;
+
+        if secs > 0.0 {
+            self.rate = (target - self.now).abs() / secs;
+        } else {
+            self.now = target;
+        }
+    }
+
+    fn tick(&mut self, dt: f32) {
+        let left = self.target - self.now;
+        let step = self.rate * dt;
+
+        self.now = if left.abs() <= step {
+            self.target
+        } else {
+            self.now + step.copysign(left)
+        };
+    }
+}
+
+/// the voices of one channel.
+#[derive(Clone, Debug, Default)]
+pub struct ChannelVoices {
+    pub settings: VoiceSettings,
+    pub expression: Expression,
+    pub modulation: Modulation,
+    sounding: Vec<Voice>,
+    /// the keys held down with their velocities, oldest first. the mono modes go back to the
+    /// newest one still held when a note is let go.
+    keys: Vec<(u8, u8)>,
+    clock: u64,
+    /// whether the engine's pitch is moved by `pitch`, so glide, legato and the bend range are
+    /// played here. engines that aren't are sent `VoiceSettings::controllers` and
+    /// `BendRange::controllers` instead.
+    pitched: bool,
+    glide: Glide,
+    /// the pitch the engine was last moved to.
+    engine_pitch: f32,
+}
+
+impl ChannelVoices {
+    /// how many notes are sounding.
+    pub fn active(&self) -> u32 {
+        self.sounding.len() as u32
+    }
+
+    /// how far the engine's pitch should be moved, in semitones from the notes it was sent.
+    pub fn pitch(&self) -> f32 {
+        match self.pitched {
+            true => self.glide.now + self.expression.bend_semitones(),
+            false => 0.0,
+        }
+    }
+
+    fn start(&mut self, note: u8, vel: u8) -> Voice {
+        self.clock += 1;
+
+        Voice {
+            note,
+            vel,
+            started: self.clock,
+            engine_note: note,
+        }
+    }
+
+    /// the engine was restarted on `to` from `from`, the pitch slides on from where it was.
+    fn glide_from(&mut self, from: u8, to: u8) {
+        self.glide.now += from as f32 - to as f32;
+        self.glide.slide(0.0, self.settings.glide);
+    }
+
+    fn note_on(&mut self, channel: u8, note: u8, vel: u8, out: &mut Vec<[u8; 3]>) {
+        let on = |note, vel| [0x90 | channel, note, vel];
+        let off = |note| [0x80 | channel, note, 0];
+
+        self.keys.retain(|(key, _)| *key != note);
+        self.keys.push((note, vel));
+        let voice = self.start(note, vel);
+        // a legato note slides on from the held one without restarting the envelopes.
+        let retrigger = self.settings.mode != VoiceMode::Legato || self.keys.len() == 1;
+        self.modulation.note_on(note, vel, retrigger);
+
+        match self.settings.mode {
+            VoiceMode::Poly => {
+                if self.settings.steal == Steal::SameNote
+                    && let Some(same) = self.sounding.iter_mut().find(|v| v.note == note)
+                {
+                    out.extend([off(same.engine_note), on(note, vel)]);
+                    *same = voice;
+                    return;
+                }
+
+                if self.sounding.len() >= self.settings.limit() {
+                    let victim = match self.settings.steal {
+                        Steal::Quietest => self
+                            .sounding
+                            .iter()
+                            .enumerate()
+                            .min_by_key(|(_, v)| (v.vel, v.started)),
+                        Steal::Oldest | Steal::SameNote => self
+                            .sounding
+                            .iter()
+                            .enumerate()
+                            .min_by_key(|(_, v)| v.started),
+                    }
+                    .map(|(i, _)| i);
+
+                    if let Some(victim) = victim {
+                        out.push(off(self.sounding.remove(victim).engine_note));
+                    }
+                }
+
+                self.sounding.push(voice);
+                out.push(on(note, vel));
+            }
+            VoiceMode::Mono | VoiceMode::Legato => {
+                let current = self.sounding.first().copied();
+                self.sounding = vec![voice];
+
+                match current {
+                    // the engine carries on with the note it has, the pitch slides it to the new
+                    // one.
+                    Some(current) if self.pitched && !retrigger => {
+                        self.sounding[0].engine_note = current.engine_note;
+                        self.glide.slide(
+                            note as f32 - current.engine_note as f32,
+                            self.settings.glide,
+                        );
+                    }
+                    Some(current) if self.pitched => {
+                        self.glide_from(current.engine_note, note);
+                        out.extend([off(current.engine_note), on(note, vel)]);
+                    }
+                    // legato plays the new note before letting go of the old one, so an engine
+                    // that knows legato slides instead of restarting.
+                    Some(current)
+                        if self.settings.mode == VoiceMode::Legato && current.note != note =>
+                    {
+                        out.extend([on(note, vel), off(current.note)])
+                    }
+                    Some(current) => out.extend([off(current.engine_note), on(note, vel)]),
+                    None => {
+                        self.glide = Glide::default();
+                        out.push(on(note, vel));
+                    }
+                }
+            }
+        }
+    }
+
+    fn note_off(&mut self, channel: u8, note: u8, out: &mut Vec<[u8; 3]>) {
+        let on = |note, vel| [0x90 | channel, note, vel];
+        let off = |note| [0x80 | channel, note, 0];
+
+        self.keys.retain(|(key, _)| *key != note);
+
+        if self.keys.is_empty() {
+            self.modulation.release();
+        }
+
+        let Some(index) = self.sounding.iter().position(|v| v.note == note) else {
+            // its voice was stolen, it is already silent.
+            return;
+        };
+        let current = self.sounding[index];
+
+        match self.settings.mode {
+            VoiceMode::Poly => {
+                self.sounding.remove(index);
+                out.push(off(current.engine_note));
+            }
+            VoiceMode::Mono | VoiceMode::Legato => match self.keys.last().copied() {
+                Some((prev, vel)) => {
+                    self.sounding = vec![self.start(prev, vel)];
+
+                    if self.pitched && self.settings.mode == VoiceMode::Legato {
+                        self.sounding[0].engine_note = current.engine_note;
+                        self.glide.slide(
+                            prev as f32 - current.engine_note as f32,
+                            self.settings.glide,
+                        );
+                    } else if self.pitched {
+                        self.glide_from(current.engine_note, prev);
+                        out.extend([off(current.engine_note), on(prev, vel)]);
+                    } else if self.settings.mode == VoiceMode::Legato {
+                        out.extend([on(prev, vel), off(note)]);
+                    } else {
+                        out.extend([off(note), on(prev, vel)]);
+                    }
+                }
+                None => {
+                    self.sounding.clear();
+                    out.push(off(current.engine_note));
+                }
+            },
+        }
+    }
+
+    /// forgets every note, for when the engine has been told to stop them all.
+    pub fn clear(&mut self) {
+        self.sounding.clear();
+        self.keys.clear();
+        self.glide = Glide::default();
+        self.modulation.reset();
+    }
+}
+
+/// decides which notes reach the engines, within each channel's polyphony and mode. sits between
+/// the MIDI inputs and the synth, so everything the synth plays should go through it.
+#[derive(Clone, Debug, Default)]
+pub struct Voices {
+    channels: [ChannelVoices; N_CHANNELS],
+    lfos: Lfos,
+    /// plays its notes through the channels and records the notes played into it.
+    song: Song,
+}
+
+impl Voices {
+    /// the raw messages to send the synth for one incoming raw MIDI message.
+    pub fn midi_input(&mut self, message: &[u8]) -> Vec<Vec<u8>> {
+        if let [status, note, vel, ..] = *message {
+            match status & 0xF0 {
+                0x90 if vel > 0 => self.song.record_note_on(status & 0x0F, note, vel),
+                0x80 | 0x90 => self.song.record_note_off(status & 0x0F, note),
+                _ => {}
+            }
+        }
+
+        self.allocate(message)
+    }
+
+    /// the raw messages to send the synth for a raw MIDI message, within the channel's voices.
+    fn allocate(&mut self, message: &[u8]) -> Vec<Vec<u8>> {
+        let Some(status) = message.first() else {
+            return Vec::new();
+        };
+        let channel = status & 0x0F;
+        let Some(voices) = self.channels.get_mut(channel as usize) else {
+            return vec![message.to_vec()];
+        };
+        let message = voices.expression.midi_input(message);
+
+        // the bend is in the pitch.
+        if voices.pitched && status & 0xF0 == 0xE0 {
+            return Vec::new();
+        }
+
+        let [status, data_1, data_2, ..] = *message else {
+            return vec![message];
+        };
+        let mut out = Vec::new();
+
+        match status & 0xF0 {
+            0x90 if data_2 > 0 => voices.note_on(channel, data_1, data_2, &mut out),
+            // a note on with zero velocity is a note off.
+            0x80 | 0x90 => voices.note_off(channel, data_1, &mut out),
+            // all sound off and all notes off.
+            0xB0 if data_1 == 120 || data_1 == 123 => {
+                voices.clear();
+                return vec![message];
+            }
+            _ => return vec![message],
+        }
+
+        out.into_iter().map(Vec::from).collect()
+    }
+
+    pub fn settings(&self, channel: usize) -> VoiceSettings {
+        self.channels
+            .get(channel)
+            .map(|voices| voices.settings)
+            .unwrap_or_default()
+    }
+
+    /// changes a channel's settings, returning the messages that tell the engine and stop the
+    /// notes that no longer fit.
+    pub fn set_settings(&mut self, channel: usize, settings: VoiceSettings) -> Vec<Vec<u8>> {
+        let Some(voices) = self.channels.get_mut(channel) else {
+            return Vec::new();
+        };
+        let channel = channel as u8;
+        voices.settings = settings;
+
+        let mut out: Vec<Vec<u8>> = match voices.pitched {
+            true => Vec::new(),
+            false => settings
+                .controllers(channel)
+                .into_iter()
+                .map(Vec::from)
+                .collect(),
+        };
+
+        // the notes poly plays are all at their own pitch.
+        if settings.mode == VoiceMode::Poly {
+            voices.glide = Glide::default();
+        }
+
+        while voices.sounding.len() > settings.limit() {
+            let oldest = voices
+                .sounding
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, v)| v.started)
+                .map_or(0, |(i, _)| i);
+            let note = voices.sounding.remove(oldest).engine_note;
+
+            out.push(vec![0x80 | channel, note, 0]);
+        }
+
+        out
+    }
+
+    /// tells a channel whether its engine's pitch is moved by `Voices::pitch`, returning the
+    /// controllers and bend an engine that isn't needs for the channel's settings.
+    pub fn set_pitched(&mut self, channel: usize, pitched: bool) -> Vec<Vec<u8>> {
+        let Some(voices) = self.channels.get_mut(channel) else {
+            return Vec::new();
+        };
+        let channel = channel as u8;
+        voices.pitched = pitched;
+
+        if pitched {
+            return Vec::new();
+        }
+
+        let expression = voices.expression;
+
+        voices
+            .settings
+            .controllers(channel)
+            .into_iter()
+            .chain(expression.bend_range.controllers(channel))
+            .chain([expression.bend_message(channel)])
+            .map(Vec::from)
+            .collect()
+    }
+
+    pub fn pitched(&self, channel: usize) -> bool {
+        self.channels
+            .get(channel)
+            .is_some_and(|voices| voices.pitched)
+    }
+
+    /// how far a pitched channel's engine should be moved, in semitones from the notes it was
+    /// sent.
+    pub fn pitch(&self, channel: usize) -> f32 {
+        self.channels
+            .get(channel)
+            .map_or(0.0, |voices| voices.pitch())
+    }
+
+    /// the pitch a channel's engine was last moved to, the engine adds the difference to
+    /// `pitch` each tick.
+    pub fn engine_pitch(&self, channel: usize) -> f32 {
+        self.channels
+            .get(channel)
+            .map_or(0.0, |voices| voices.engine_pitch)
+    }
+
+    pub fn set_engine_pitch(&mut self, channel: usize, pitch: f32) {
+        if let Some(voices) = self.channels.get_mut(channel) {
+            voices.engine_pitch = pitch;
+        }
+    }
+
+    /// slides the glides on by `dt` seconds, once per control tick.
+    pub fn tick_glide(&mut self, dt: f32) {
+        for voices in self.channels.iter_mut() {
+            voices.glide.tick(dt);
+        }
+    }
+
+    /// where a channel's pitch bend, mod wheel and aftertouch are.
+    pub fn expression(&self, channel: usize) -> Expression {
+        self.channels
+            .get(channel)
+            .map(|voices| voices.expression)
+            .unwrap_or_default()
+    }
+
+    /// changes a channel's bend range, returning the messages that tell an engine bent by MIDI
+    /// and move a bend in progress to the new range. a pitched channel's `pitch` follows it.
+    pub fn set_bend_range(&mut self, channel: usize, range: BendRange) -> Vec<Vec<u8>> {
+        let Some(voices) = self.channels.get_mut(channel) else {
+            return Vec::new();
+        };
+        let channel = channel as u8;
+        voices.expression.bend_range = range;
+
+        if voices.pitched {
+            return Vec::new();
+        }
+
+        range
+            .controllers(channel)
+            .into_iter()
+            .chain([voices.expression.bend_message(channel)])
+            .map(Vec::from)
+            .collect()
+    }
+
+    pub fn modulation(&self, channel: usize) -> Option<&Modulation> {
+        self.channels.get(channel).map(|voices| &voices.modulation)
+    }
+
+    pub fn modulation_mut(&mut self, channel: usize) -> Option<&mut Modulation> {
+        self.channels
+            .get_mut(channel)
+            .map(|voices| &mut voices.modulation)
+    }
+
+    /// the global LFO bank the mod matrices read.
+    pub fn lfos(&self) -> &Lfos {
+        &self.lfos
+    }
+
+    pub fn set_lfo(&mut self, lfo: usize, settings: LfoSettings) {
+        if let Some(lfo) = self.lfos.settings.get_mut(lfo) {
+            *lfo = settings.clamped();
+        }
+    }
+
+    /// moves the free running LFOs on by `dt` seconds at the song's tempo, once per control tick
+    /// before the channels are modulated.
+    pub fn tick_lfos(&mut self, dt: f32) {
+        self.lfos.tick(dt, self.song.tempo);
+    }
+
+    /// runs a channel's mod matrix for `dt` seconds, see `Modulation::tick`.
+    pub fn modulate(&mut self, channel: usize, dt: f32, envs: &[Adsr]) -> Vec<(ModDest, f32)> {
+        let lfos = &self.lfos;
+
+        self.channels
+            .get_mut(channel)
+            .map_or_else(Vec::new, |voices| {
+                voices.modulation.tick(dt, lfos, envs, &voices.expression)
+            })
+    }
+
+    pub fn song(&self) -> &Song {
+        &self.song
+    }
+
+    pub fn song_mut(&mut self) -> &mut Song {
+        &mut self.song
+    }
+
+    /// moves the song on by `dt` seconds, returning the raw messages to send the synth for the
+    /// notes it plays.
+    pub fn tick_song(&mut self, dt: f32) -> Vec<Vec<u8>> {
+        self.song
+            .tick(dt)
+            .iter()
+            .flat_map(|message| self.allocate(message))
+            .collect()
+    }
+
+    /// how many notes are sounding on a channel.
+    pub fn active(&self, channel: usize) -> u32 {
+        self.channels
+            .get(channel)
+            .map_or(0, |voices| voices.active())
+    }
+
+    /// forgets every note and centres the controllers on every channel, keeping the settings.
+    pub fn clear(&mut self) {
+        for voices in self.channels.iter_mut() {
+            voices.clear();
+            voices.expression.reset();
+        }
+
+        self.song.forget();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(note: u8, vel: u8) -> Vec<u8> {
+        vec![0x90, note, vel]
+    }
+
+    fn off(note: u8) -> Vec<u8> {
+        vec![0x80, note, 0]
+    }
+
+    fn voices(settings: VoiceSettings, pitched: bool) -> Voices {
+        let mut voices = Voices::default();
+        voices.set_pitched(0, pitched);
+        voices.set_settings(0, settings);
+
+        voices
+    }
+
+    fn mode(mode: VoiceMode, glide: f32) -> VoiceSettings {
+        VoiceSettings {
+            mode,
+            glide,
+            ..VoiceSettings::default()
+        }
+    }
+
+    fn poly(polyphony: u8, steal: Steal) -> VoiceSettings {
+        VoiceSettings {
+            polyphony,
+            steal,
+            ..VoiceSettings::default()
+        }
+    }
+
+    #[test]
+    fn oldest_is_stolen() {
+        let mut voices = voices(poly(2, Steal::Oldest), false);
+        voices.midi_input(&on(60, 100));
+        voices.midi_input(&on(62, 100));
+
+        assert_eq!(voices.midi_input(&on(64, 100)), [off(60), on(64, 100)]);
+        assert_eq!(voices.active(0), 2);
+        // the stolen note is already silent when its key comes up.
+        assert!(voices.midi_input(&off(60)).is_empty());
+    }
+
+    #[test]
+    fn quietest_is_stolen() {
+        let mut voices = voices(poly(2, Steal::Quietest), false);
+        voices.midi_input(&on(60, 100));
+        voices.midi_input(&on(62, 20));
+
+        assert_eq!(voices.midi_input(&on(64, 100)), [off(62), on(64, 100)]);
+    }
+
+    #[test]
+    fn same_note_restarts_its_voice() {
+        let mut voices = voices(poly(2, Steal::SameNote), false);
+        voices.midi_input(&on(60, 100));
+        voices.midi_input(&on(62, 100));
+
+        assert_eq!(voices.midi_input(&on(60, 90)), [off(60), on(60, 90)]);
+        assert_eq!(voices.active(0), 2);
+    }
+
+    #[test]
+    fn fewer_voices_stop_the_oldest_notes() {
+        let mut voices = voices(poly(3, Steal::Oldest), false);
+        voices.midi_input(&on(60, 100));
+        voices.midi_input(&on(62, 100));
+        voices.midi_input(&on(64, 100));
+
+        let out = voices.set_settings(0, poly(1, Steal::Oldest));
+
+        assert!(out.ends_with(&[off(60), off(62)]));
+        assert_eq!(voices.active(0), 1);
+        assert_eq!(voices.midi_input(&off(64)), [off(64)]);
+    }
+
+    #[test]
+    fn mono_goes_back_to_the_held_key() {
+        let mut voices = voices(mode(VoiceMode::Mono, 0.0), true);
+
+        assert_eq!(voices.midi_input(&on(60, 100)), [on(60, 100)]);
+        assert_eq!(voices.midi_input(&on(64, 90)), [off(60), on(64, 90)]);
+        assert_eq!(voices.midi_input(&off(64)), [off(64), on(60, 100)]);
+        assert_eq!(voices.midi_input(&off(60)), [off(60)]);
+        assert_eq!(voices.active(0), 0);
+    }
+
+    #[test]
+    fn mono_glides_from_the_last_note() {
+        let mut voices = voices(mode(VoiceMode::Mono, 1.0), true);
+        voices.midi_input(&on(60, 100));
+        voices.midi_input(&on(72, 100));
+
+        assert_eq!(voices.pitch(0), -12.0);
+        voices.tick_glide(0.5);
+        assert_eq!(voices.pitch(0), -6.0);
+        voices.tick_glide(1.0);
+        assert_eq!(voices.pitch(0), 0.0);
+    }
+
+    #[test]
+    fn legato_slides_without_restarting_the_engine() {
+        let mut voices = voices(mode(VoiceMode::Legato, 0.0), true);
+        voices.midi_input(&on(60, 100));
+
+        assert!(voices.midi_input(&on(67, 100)).is_empty());
+        assert_eq!(voices.pitch(0), 7.0);
+        assert!(voices.midi_input(&off(67)).is_empty());
+        assert_eq!(voices.pitch(0), 0.0);
+        assert!(voices.midi_input(&on(64, 100)).is_empty());
+        // the engine is still playing the first note, the last key up stops that one.
+        assert!(voices.midi_input(&off(60)).is_empty());
+        assert_eq!(voices.midi_input(&off(64)), [off(60)]);
+    }
+
+    #[test]
+    fn legato_glides() {
+        let mut voices = voices(mode(VoiceMode::Legato, 2.0), true);
+        voices.midi_input(&on(60, 100));
+        voices.midi_input(&on(64, 100));
+
+        assert_eq!(voices.pitch(0), 0.0);
+        voices.tick_glide(1.0);
+        assert_eq!(voices.pitch(0), 2.0);
+    }
+
+    #[test]
+    fn pitched_bends_follow_the_range() {
+        let mut voices = voices(VoiceSettings::default(), true);
+        voices.set_bend_range(0, BendRange { up: 12, down: 2 });
+
+        assert!(voices.midi_input(&[0xE0, 0x7F, 0x7F]).is_empty());
+        assert_eq!(voices.pitch(0), 12.0);
+        voices.midi_input(&[0xE0, 0, 0]);
+        assert_eq!(voices.pitch(0), -2.0);
+        voices.midi_input(&[0xE0, 0, 0x40]);
+        assert_eq!(voices.pitch(0), 0.0);
+    }
+
+    #[test]
+    fn unpitched_bends_are_rescaled() {
+        let mut voices = voices(VoiceSettings::default(), false);
+        voices.set_bend_range(0, BendRange { up: 12, down: 6 });
+
+        // the engine is set to 12 semitones, so a full bend down is sent as half of one.
+        assert_eq!(voices.midi_input(&[0xE0, 0, 0]), [vec![0xE0, 0, 0x20]]);
+        assert_eq!(voices.pitch(0), 0.0);
+    }
+
+    #[test]
+    fn unpitched_legato_overlaps_the_notes() {
+        let mut voices = voices(mode(VoiceMode::Legato, 0.0), false);
+        voices.midi_input(&on(60, 100));
+
+        assert_eq!(voices.midi_input(&on(64, 90)), [on(64, 90), off(60)]);
+        assert_eq!(voices.midi_input(&off(64)), [on(60, 100), off(64)]);
+        assert_eq!(voices.pitch(0), 0.0);
+    }
+
+    #[test]
+    fn synced_lfos_follow_the_song_tempo() {
+        use crate::lfo::{LfoShape, LfoSync};
+
+        let mut voices = Voices::default();
+        voices.set_lfo(
+            0,
+            LfoSettings {
+                shape: LfoShape::Saw,
+                sync: LfoSync::Bar,
+                ..LfoSettings::default()
+            },
+        );
+
+        // a bar lasts 2 seconds at 120 bpm, so half a second is a quarter of a cycle.
+        voices.tick_lfos(0.5);
+        assert!((voices.lfos().free(0) + 0.5).abs() < 1e-4);
+
+        // at 240 bpm the same half second is half a cycle.
+        voices.song_mut().set_tempo(240.0);
+        voices.tick_lfos(0.5);
+        assert!((voices.lfos().free(0) - 0.5).abs() < 1e-4);
+        assert_eq!(voices.lfos().tempo(), 240.0);
+    }
+}
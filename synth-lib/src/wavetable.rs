@@ -0,0 +1,749 @@
+use crate::{N_CHANNELS, modulation::N_MOD_OSCS};
+use serde::{Deserialize, Serialize};
+use std::{
+    f32::consts::TAU,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// how many samples each frame of a table is resampled to.
+pub const TABLE_LEN: usize = 2048;
+/// the most frames a table keeps, the rest of a longer file is dropped.
+pub const MAX_FRAMES: usize = 256;
+/// files up to this many samples are taken as a single cycle, longer ones are split into frames
+/// of `TABLE_LEN`, unless they say otherwise.
+const MAX_SINGLE_CYCLE: usize = 4096;
+/// how many harmonics the built in band limited shapes are summed from.
+const HARMONICS: usize = 64;
+
+/// the tables that come with the synth.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuiltinTable {
+    Sine,
+    Triangle,
+    #[default]
+    Saw,
+    Square,
+    /// a square that narrows across the frames.
+    Pulse,
+    /// fades from a sine to a saw across the frames.
+    SineToSaw,
+    /// adds a harmonic each frame.
+    Harmonics,
+}
+
+impl BuiltinTable {
+    pub const ALL: [Self; 7] = [
+        Self::Sine,
+        Self::Triangle,
+        Self::Saw,
+        Self::Square,
+        Self::Pulse,
+        Self::SineToSaw,
+        Self::Harmonics,
+    ];
+
+    fn frames(self) -> Vec<Vec<f32>> {
+        let harmonic = |k: usize, x: f32| (k as f32 * x).sin();
+        let cycle = |f: &dyn Fn(f32) -> f32| -> Vec<f32> {
+            (0..TABLE_LEN)
+                .map(|i| f(i as f32 / TABLE_LEN as f32 * TAU))
+                .collect()
+        };
+        let saw = |x: f32| -> f32 {
+            (1..=HARMONICS)
+                .map(|k| harmonic(k, x) / k as f32)
+                .sum::<f32>()
+                * 2.0
+                / std::f32::consts::PI
+        };
+
+        match self {
+            Self::Sine => vec![cycle(&|x| x.sin())],
+            Self::Triangle => vec![cycle(&|x| {
+                (1..=HARMONICS)
+                    .step_by(2)
+                    .enumerate()
+                    .map(|(i, k)| {
+                        let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                        sign * harmonic(k, x) / (k * k) as f32
+                    })
+                    .sum::<f32>()
+                    * 8.0
+                    / (std::f32::consts::PI * std::f32::consts::PI)
+            })],
+            Self::Saw => vec![cycle(&saw)],
+            Self::Square => vec![cycle(&|x| {
+                (1..=HARMONICS)
+                    .step_by(2)
+                    .map(|k| harmonic(k, x) / k as f32)
+                    .sum::<f32>()
+                    * 4.0
+                    / std::f32::consts::PI
+            })],
+            Self::Pulse => (0..32)
+                .map(|frame| {
+                    let width = 0.5 - 0.45 * frame as f32 / 31.0;
+
+                    cycle(&|x| if x / TAU < width { 1.0 } else { -1.0 })
+                })
+                .collect(),
+            Self::SineToSaw => (0..32)
+                .map(|frame| {
+                    let mix = frame as f32 / 31.0;
+
+                    cycle(&|x| x.sin() * (1.0 - mix) + saw(x) * mix)
+                })
+                .collect(),
+            Self::Harmonics => (1..=16)
+                .map(|n| cycle(&|x| (1..=n).map(|k| harmonic(k, x)).sum::<f32>() / n as f32))
+                .collect(),
+        }
+    }
+}
+
+impl Display for BuiltinTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sine => write!(f, "Sine"),
+            Self::Triangle => write!(f, "Triangle"),
+            Self::Saw => write!(f, "Saw"),
+            Self::Square => write!(f, "Square"),
+            Self::Pulse => write!(f, "Pulse"),
+            Self::SineToSaw => write!(f, "Sine to Saw"),
+            Self::Harmonics => write!(f, "Harmonics"),
+        }
+    }
+}
+
+/// names a user table by a hash of its name, so patches find it again after a restart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TableId(pub u64);
+
+impl TableId {
+    /// FNV-1a of `name`.
+    pub fn of(name: &str) -> Self {
+        Self(name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableChoice {
+    Builtin(BuiltinTable),
+    /// imported from a WAV file.
+    User(TableId),
+}
+
+impl Default for TableChoice {
+    fn default() -> Self {
+        Self::Builtin(BuiltinTable::default())
+    }
+}
+
+/// a name and the choice that picks it, for the UIs to list.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub choice: TableChoice,
+    pub name: String,
+    pub frames: usize,
+}
+
+impl Display for TableInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TableError {
+    /// not a RIFF WAVE file, or cut short.
+    NotWav,
+    /// a sample format other than 8 to 32 bit PCM or 32/64 bit float.
+    Unsupported {
+        format: u16,
+        bits: u16,
+    },
+    Empty,
+    /// a float sample that is NaN or infinite.
+    NotFinite,
+    Io(String),
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotWav => write!(f, "not a WAV file"),
+            Self::Unsupported { format, bits } => {
+                write!(f, "unsupported WAV sample format {format} at {bits} bits")
+            }
+            Self::Empty => write!(f, "the WAV file has no samples"),
+            Self::NotFinite => write!(f, "the WAV file has samples that are NaN or infinite"),
+            Self::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// the parts of a WAV file a table is read from.
+struct Wav {
+    channels: usize,
+    samples: Vec<f32>,
+    /// the frame size a wavetable editor wrote into a `clm ` chunk.
+    frame_len: Option<usize>,
+}
+
+impl Wav {
+    fn parse(bytes: &[u8]) -> Result<Self, TableError> {
+        // the lengths come from the file, so every offset is checked rather than trusted.
+        let get = |at: usize, len: usize| bytes.get(at..at.checked_add(len)?);
+        let u16_at =
+            |at: usize| -> Option<u16> { Some(u16::from_le_bytes(get(at, 2)?.try_into().ok()?)) };
+        let u32_at =
+            |at: usize| -> Option<u32> { Some(u32::from_le_bytes(get(at, 4)?.try_into().ok()?)) };
+        // a chunk's body, cut short where the file ends.
+        let body_of =
+            |body: usize, len: usize| bytes.get(body..body.saturating_add(len).min(bytes.len()));
+
+        if bytes.get(0..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+            return Err(TableError::NotWav);
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut frame_len = None;
+        let mut at = 12;
+
+        while let (Some(id), Some(len), Some(body)) = (
+            get(at, 4),
+            at.checked_add(4).and_then(u32_at),
+            at.checked_add(8),
+        ) {
+            let len = len as usize;
+            let field = |offset: usize| {
+                body.checked_add(offset)
+                    .and_then(u16_at)
+                    .ok_or(TableError::NotWav)
+            };
+
+            match id {
+                b"fmt " => {
+                    let tag = field(0)?;
+                    // WAVE_FORMAT_EXTENSIBLE keeps the real format in its sub format.
+                    let tag = if tag == 0xFFFE { field(24)? } else { tag };
+                    let channels = field(2)?;
+                    let bits = field(14)?;
+
+                    format = Some((tag, channels.max(1) as usize, bits));
+                }
+                b"data" => data = body_of(body, len),
+                // Serum's "<!>2048 ..." marker.
+                b"clm " => {
+                    frame_len = body_of(body, len)
+                        .and_then(|text| text.strip_prefix(b"<!>"))
+                        .and_then(|text| {
+                            text.iter()
+                                .take_while(|c| c.is_ascii_digit())
+                                .try_fold(0_usize, |n, c| {
+                                    n.checked_mul(10)?.checked_add((c - b'0') as usize)
+                                })
+                        })
+                        .filter(|len| *len > 0)
+                }
+                _ => {}
+            }
+
+            // chunks are padded to an even length, a length past the end of memory ends the file.
+            match body
+                .checked_add(len)
+                .and_then(|end| end.checked_add(len % 2))
+            {
+                Some(next) => at = next,
+                None => break,
+            }
+        }
+
+        let (tag, channels, bits) = format.ok_or(TableError::NotWav)?;
+        let data = data.ok_or(TableError::NotWav)?;
+        let samples: Vec<f32> = match (tag, bits) {
+            (1, 8) => data.iter().map(|s| (*s as f32 - 128.0) / 128.0).collect(),
+            (1, 16) => data
+                .chunks_exact(2)
+                .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0)
+                .collect(),
+            (1, 24) => data
+                .chunks_exact(3)
+                .map(|s| i32::from_le_bytes([0, s[0], s[1], s[2]]) as f32 / 2147483648.0)
+                .collect(),
+            (1, 32) => data
+                .chunks_exact(4)
+                .map(|s| i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2147483648.0)
+                .collect(),
+            (3, 32) => data
+                .chunks_exact(4)
+                .map(|s| f32::from_le_bytes([s[0], s[1], s[2], s[3]]))
+                .collect(),
+            (3, 64) => data
+                .chunks_exact(8)
+                .map(|s| f64::from_le_bytes(s.try_into().unwrap_or_default()) as f32)
+                .collect(),
+            (format, bits) => return Err(TableError::Unsupported { format, bits }),
+        };
+
+        if samples.iter().any(|s| !s.is_finite()) {
+            return Err(TableError::NotFinite);
+        }
+
+        Ok(Self {
+            channels,
+            samples,
+            frame_len,
+        })
+    }
+
+    /// the samples mixed down to mono.
+    fn mono(&self) -> Vec<f32> {
+        self.samples
+            .chunks_exact(self.channels)
+            .map(|frame| frame.iter().sum::<f32>() / self.channels as f32)
+            .collect()
+    }
+}
+
+/// resamples one cycle to `TABLE_LEN`, wrapping around the end.
+fn resample(cycle: &[f32]) -> Vec<f32> {
+    if cycle.len() == TABLE_LEN {
+        return cycle.to_vec();
+    }
+
+    (0..TABLE_LEN)
+        .map(|i| {
+            let at = i as f32 * cycle.len() as f32 / TABLE_LEN as f32;
+            let (i, t) = (at as usize, at.fract());
+
+            cycle[i] * (1.0 - t) + cycle[(i + 1) % cycle.len()] * t
+        })
+        .collect()
+}
+
+/// one or more single cycles, `position` sweeps across them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaveTable {
+    pub name: String,
+    frames: Vec<Vec<f32>>,
+}
+
+impl WaveTable {
+    pub fn builtin(table: BuiltinTable) -> Self {
+        Self {
+            name: table.to_string(),
+            frames: table.frames(),
+        }
+    }
+
+    /// reads a single cycle or multi frame table from a WAV file, mixed to mono and normalized.
+    pub fn from_wav(name: &str, bytes: &[u8]) -> Result<Self, TableError> {
+        let wav = Wav::parse(bytes)?;
+        let samples = wav.mono();
+
+        if samples.is_empty() {
+            return Err(TableError::Empty);
+        }
+
+        let frame_len = wav
+            .frame_len
+            .unwrap_or(if samples.len() <= MAX_SINGLE_CYCLE {
+                samples.len()
+            } else {
+                TABLE_LEN
+            });
+        let mut frames: Vec<Vec<f32>> = samples
+            .chunks_exact(frame_len.min(samples.len()))
+            .take(MAX_FRAMES)
+            .map(resample)
+            .collect();
+        let peak = frames
+            .iter()
+            .flatten()
+            .fold(0.0_f32, |peak, s| peak.max(s.abs()));
+
+        if peak > 0.0 {
+            frames.iter_mut().flatten().for_each(|s| *s /= peak);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            frames,
+        })
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// the cycle at `position`, from 0.0 (the first frame) to 1.0 (the last), blended between
+    /// the two nearest frames.
+    pub fn frame(&self, position: f32) -> Arc<[f32]> {
+        let at = position.clamp(0.0, 1.0) * (self.frames.len() - 1) as f32;
+        let (i, t) = (at as usize, at.fract());
+        let a = &self.frames[i];
+
+        match self.frames.get(i + 1) {
+            Some(b) if t > 0.0 => a.iter().zip(b).map(|(a, b)| a + (b - a) * t).collect(),
+            _ => a.as_slice().into(),
+        }
+    }
+
+    fn info(&self, choice: TableChoice) -> TableInfo {
+        TableInfo {
+            choice,
+            name: self.name.clone(),
+            frames: self.frames(),
+        }
+    }
+}
+
+/// the table an oscillator plays and where in it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OscTable {
+    pub table: TableChoice,
+    /// from 0.0 (the first frame) to 1.0 (the last).
+    pub position: f32,
+}
+
+/// the built in and imported tables, and which one each channel's oscillators play.
+#[derive(Clone, Debug)]
+pub struct WaveTables {
+    builtin: Vec<WaveTable>,
+    user: Vec<WaveTable>,
+    oscs: [[OscTable; N_MOD_OSCS as usize]; N_CHANNELS],
+    /// where imported tables are kept.
+    dir: Option<PathBuf>,
+}
+
+impl Default for WaveTables {
+    fn default() -> Self {
+        Self {
+            builtin: BuiltinTable::ALL.map(WaveTable::builtin).to_vec(),
+            user: Vec::new(),
+            oscs: Default::default(),
+            dir: None,
+        }
+    }
+}
+
+impl WaveTables {
+    /// the built in tables and the WAV files in `dir`, which imports are saved to. returns the
+    /// files that couldn't be read, with why.
+    pub fn load(dir: &Path) -> (Self, Vec<(PathBuf, TableError)>) {
+        let mut tables = Self {
+            dir: Some(dir.to_path_buf()),
+            ..Self::default()
+        };
+        let mut problems = Vec::new();
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let table = fs::read(&path)
+                .map_err(|e| TableError::Io(e.to_string()))
+                .and_then(|bytes| WaveTable::from_wav(&name, &bytes));
+
+            match table {
+                Ok(table) => {
+                    tables.add(table);
+                }
+                Err(e) => problems.push((path, e)),
+            }
+        }
+
+        (tables, problems)
+    }
+
+    /// adds a user table, replacing one with the same name.
+    fn add(&mut self, table: WaveTable) -> TableChoice {
+        let choice = TableChoice::User(TableId::of(&table.name));
+
+        match self.user.iter_mut().find(|t| t.name == table.name) {
+            Some(existing) => *existing = table,
+            None => self.user.push(table),
+        }
+
+        choice
+    }
+
+    /// reads a WAV file's bytes into a user table and saves a copy to the tables directory, so
+    /// it is there after a restart.
+    pub fn import(&mut self, name: &str, wav: &[u8]) -> Result<TableChoice, TableError> {
+        // the name becomes a file name.
+        let name: String = name
+            .trim()
+            .trim_end_matches(".wav")
+            .chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+            .collect();
+
+        if name.is_empty() {
+            return Err(TableError::Io("the table needs a name".into()));
+        }
+
+        let table = WaveTable::from_wav(&name, wav)?;
+
+        if let Some(ref dir) = self.dir {
+            fs::create_dir_all(dir)
+                .and_then(|_| fs::write(dir.join(format!("{name}.wav")), wav))
+                .map_err(|e| TableError::Io(e.to_string()))?;
+        }
+
+        Ok(self.add(table))
+    }
+
+    pub fn get(&self, choice: TableChoice) -> Option<&WaveTable> {
+        match choice {
+            TableChoice::Builtin(table) => self.builtin.get(table as usize),
+            TableChoice::User(id) => self.user.iter().find(|t| TableId::of(&t.name) == id),
+        }
+    }
+
+    /// every table, built in first.
+    pub fn list(&self) -> Vec<TableInfo> {
+        let builtin = BuiltinTable::ALL
+            .iter()
+            .zip(self.builtin.iter())
+            .map(|(choice, table)| table.info(TableChoice::Builtin(*choice)));
+        let user = self
+            .user
+            .iter()
+            .map(|table| table.info(TableChoice::User(TableId::of(&table.name))));
+
+        builtin.chain(user).collect()
+    }
+
+    pub fn osc(&self, channel: usize, osc: usize) -> Option<OscTable> {
+        self.oscs.get(channel)?.get(osc).copied()
+    }
+
+    /// sets an oscillator's table or position, and returns the cycle it should play. a table
+    /// that is gone plays nothing new.
+    pub fn set_osc(&mut self, channel: usize, osc: usize, table: OscTable) -> Option<Arc<[f32]>> {
+        *self.oscs.get_mut(channel)?.get_mut(osc)? = OscTable {
+            position: table.position.clamp(0.0, 1.0),
+            ..table
+        };
+
+        self.frame(channel, osc, table.position)
+    }
+
+    /// the cycle an oscillator plays at `position`, for the mod matrix to move through its
+    /// table.
+    pub fn frame(&self, channel: usize, osc: usize, position: f32) -> Option<Arc<[f32]>> {
+        let table = self.osc(channel, osc)?;
+
+        self.get(table.table).map(|t| t.frame(position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a RIFF WAVE file with a `fmt ` chunk and the given chunks after it.
+    fn wav(fmt: &[u8], chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut body = b"WAVE".to_vec();
+
+        for (id, data) in [(b"fmt ", fmt)].into_iter().chain(chunks.iter().copied()) {
+            body.extend_from_slice(id);
+            body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            body.extend_from_slice(data);
+
+            if data.len() % 2 == 1 {
+                body.push(0);
+            }
+        }
+
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        file.extend(body);
+
+        file
+    }
+
+    fn fmt(tag: u16, channels: u16, bits: u16) -> Vec<u8> {
+        let align = channels * bits / 8;
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&tag.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&48000_u32.to_le_bytes());
+        fmt.extend_from_slice(&(48000 * align as u32).to_le_bytes());
+        fmt.extend_from_slice(&align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+
+        fmt
+    }
+
+    /// WAVE_FORMAT_EXTENSIBLE, with the real format in the first two bytes of the sub format.
+    fn extensible(tag: u16, channels: u16, bits: u16) -> Vec<u8> {
+        let mut fmt = fmt(0xFFFE, channels, bits);
+        fmt.extend_from_slice(&22_u16.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+        fmt.extend_from_slice(&0_u32.to_le_bytes());
+        fmt.extend_from_slice(&tag.to_le_bytes());
+        fmt.extend_from_slice(&[0; 14]);
+
+        fmt
+    }
+
+    fn parse(fmt: &[u8], data: &[u8]) -> Result<Wav, TableError> {
+        Wav::parse(&wav(fmt, &[(b"data", data)]))
+    }
+
+    fn assert_samples(wav: Wav, expected: &[f32]) {
+        assert_eq!(wav.samples.len(), expected.len());
+
+        for (sample, expected) in wav.samples.iter().zip(expected) {
+            assert!((sample - expected).abs() < 1e-4, "{sample} != {expected}");
+        }
+    }
+
+    #[test]
+    fn pcm_8_bit() {
+        let wav = parse(&fmt(1, 1, 8), &[128, 255, 0]).unwrap();
+
+        assert_samples(wav, &[0.0, 127.0 / 128.0, -1.0]);
+    }
+
+    #[test]
+    fn pcm_16_bit() {
+        let data: Vec<u8> = [0_i16, 16384, -32768]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+
+        assert_samples(parse(&fmt(1, 1, 16), &data).unwrap(), &[0.0, 0.5, -1.0]);
+    }
+
+    #[test]
+    fn pcm_24_bit() {
+        let data = [0, 0, 0, 0, 0, 0x40, 0, 0, 0x80];
+
+        assert_samples(parse(&fmt(1, 1, 24), &data).unwrap(), &[0.0, 0.5, -1.0]);
+    }
+
+    #[test]
+    fn pcm_32_bit() {
+        let data: Vec<u8> = [0_i32, 1 << 30, i32::MIN]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+
+        assert_samples(parse(&fmt(1, 1, 32), &data).unwrap(), &[0.0, 0.5, -1.0]);
+    }
+
+    #[test]
+    fn float_32_and_64_bit() {
+        let data: Vec<u8> = [0.25_f32, -0.75]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_samples(parse(&fmt(3, 1, 32), &data).unwrap(), &[0.25, -0.75]);
+
+        let data: Vec<u8> = [0.25_f64, -0.75]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_samples(parse(&fmt(3, 1, 64), &data).unwrap(), &[0.25, -0.75]);
+    }
+
+    #[test]
+    fn extensible_uses_the_sub_format() {
+        let data: Vec<u8> = [0.5_f32].iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_samples(parse(&extensible(3, 1, 32), &data).unwrap(), &[0.5]);
+
+        let data: Vec<u8> = [16384_i16].iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_samples(parse(&extensible(1, 1, 16), &data).unwrap(), &[0.5]);
+    }
+
+    #[test]
+    fn stereo_is_mixed_to_mono() {
+        let data: Vec<u8> = [0.5_f32, -0.5, 1.0, 0.0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+
+        assert_eq!(parse(&fmt(3, 2, 32), &data).unwrap().mono(), vec![0.0, 0.5]);
+    }
+
+    #[test]
+    fn clm_sets_the_frame_length() {
+        let data: Vec<u8> = (0..512_i16).flat_map(|s| s.to_le_bytes()).collect();
+        let file = wav(
+            &fmt(1, 1, 16),
+            &[
+                (b"clm ", b"<!>256 10000000 wavetable (www.xferrecords.com)"),
+                (b"data", &data),
+            ],
+        );
+
+        assert_eq!(Wav::parse(&file).unwrap().frame_len, Some(256));
+        assert_eq!(WaveTable::from_wav("clm", &file).unwrap().frames(), 2);
+    }
+
+    #[test]
+    fn unsupported_formats_are_refused() {
+        assert_eq!(
+            parse(&fmt(2, 1, 4), &[0; 4]).err(),
+            Some(TableError::Unsupported { format: 2, bits: 4 })
+        );
+        assert_eq!(
+            Wav::parse(b"not a wav file").err(),
+            Some(TableError::NotWav)
+        );
+    }
+
+    #[test]
+    fn non_finite_samples_are_refused() {
+        for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let data: Vec<u8> = [0.0, bad].iter().flat_map(|s| s.to_le_bytes()).collect();
+
+            assert_eq!(
+                parse(&fmt(3, 1, 32), &data).err(),
+                Some(TableError::NotFinite)
+            );
+        }
+    }
+
+    #[test]
+    fn huge_chunk_lengths_end_the_file() {
+        let mut file = wav(&fmt(1, 1, 16), &[(b"data", &[0, 0x40])]);
+        // a junk chunk claiming to be 4 GiB long, then nothing.
+        file.extend_from_slice(b"junk");
+        file.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        assert_samples(Wav::parse(&file).unwrap(), &[0.5]);
+    }
+
+    #[test]
+    fn cut_short_data_is_read_to_the_end() {
+        let mut file = wav(&fmt(1, 1, 16), &[(b"data", &[0, 0x40, 0, 0xC0])]);
+        file.truncate(file.len() - 2);
+
+        assert_samples(Wav::parse(&file).unwrap(), &[0.5]);
+    }
+}